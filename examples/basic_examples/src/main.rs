@@ -9,7 +9,6 @@ use mlx_rust::closure::MLXFunc;
 use mlx_rust::compile::{compile, enable_compile};
 use mlx_rust::module::Module;
 use mlx_rust::random::{key, uniform};
-use mlx_rust::stream::get_default_stream;
 use mlx_rust::transform::{grad, value_and_grad};
 
 pub fn f1(input: MLXArray) -> MLXArray {
@@ -17,7 +16,7 @@ pub fn f1(input: MLXArray) -> MLXArray {
 }
 
 pub fn gelu(x: MLXArray) -> MLXArray {
-    x.clone() * (1 + erf(x / sqrt(2.into()))) / 2
+    &x * (1 + erf(&(&x / sqrt(&2.0.into())))) / 2
 }
 
 pub fn vector_f1(input: &VectorMLXArray) -> VectorMLXArray {
@@ -25,7 +24,7 @@ pub fn vector_f1(input: &VectorMLXArray) -> VectorMLXArray {
 }
 
 fn loss_fn(w: MLXArray, x: MLXArray, y: MLXArray) -> MLXArray {
-    let r = square(w * x - y).mean_all(false, None);
+    let r = square(&(w * x - y)).mean_all(false);
     r
 }
 
@@ -48,10 +47,10 @@ pub fn main() {
     let r = grad(f1).apply(1.into());
     println!("grad: {}", r.get(0).unwrap());
 
-    let dfdx = grad(sin);
+    let dfdx = grad(|x: MLXArray| sin(&x));
     println!("sin grad: {}", dfdx.apply(PI.into()).get(0).unwrap());
 
-    let r = grad(grad(sin)).apply((PI / 2.0).into());
+    let r = grad(grad(|x: MLXArray| sin(&x))).apply((PI / 2.0).into());
     println!("hessian : {}", r.get(0).unwrap());
 
     //value and grad
@@ -76,7 +75,7 @@ pub fn main() {
     println!("loss: {}", dloss_dw.get(0).unwrap());
 
     enable_compile();
-    let one = MLXArray::ones::<f32>(&[32, 1000, 4096], get_default_stream());
+    let one = MLXArray::ones::<f32>(&[32, 1000, 4096]);
     println!("start compile fn");
     measure(gelu, one.clone());
     measure(compile(gelu, false), one);
@@ -112,16 +111,14 @@ fn measure_matmul() {
     let weight = uniform::<f32>(
         0.0..=1.0,
         &[M, K],
-        rng_key,
-        get_default_stream()
+        &rng_key
     );
 
     let rng_key = key(2);
     let input = uniform::<f32>(
         0.0..=1.0,
         &[K, N],
-        rng_key,
-        get_default_stream()
+        &rng_key
     );
 
     input.eval();
@@ -129,13 +126,13 @@ fn measure_matmul() {
 
     //warm up
     for _ in 0..10 {
-        weight.matmul(input.clone(), None).eval()
+        weight.matmul(&input).eval()
     }
 
     let iterations = 100;
     let now = SystemTime::now();
     for _ in 0..iterations {
-        weight.matmul(input.clone(), None).eval()
+        weight.matmul(&input).eval()
     }
 
     println!(
@@ -152,8 +149,7 @@ fn measure_linear() {
     let input = uniform::<f16>(
         0.0..=1.0,
         &[1, 1, in_features as i32],
-        rng_key,
-        get_default_stream()
+        &rng_key
     );
     input.eval();
 
@@ -132,7 +132,7 @@ pub fn module(item: TokenStream) -> TokenStream {
                 }
                 // fn gather_params(&self, params: &mut std::collections::HashMap<usize, ::#crate_root::Tensor>) {}
                 // fn update_params(&self, params: &mut std::collections::HashMap<usize, ::#crate_root::Tensor>) {}
-                // fn gather_named_params(&self, prefix: &str, params: &mut std::collections::HashMap<String, ::#crate_root::Tensor>) {}
+                fn gather_named_params(&self, prefix: &str, params: &mut std::collections::HashMap<String, ::#crate_root::MLXArray>) {}
                 fn update_named_params(&mut self, prefix: &str, params: &mut std::collections::HashMap<String, ::#crate_root::MLXArray>) {}
             }
             //
@@ -180,18 +180,18 @@ pub fn module(item: TokenStream) -> TokenStream {
             })
             .collect();
 
-        // let gather_named_params: Vec<_> = field_opts
-        //     .iter()
-        //     .filter(|f| !f.skip)
-        //     .map(|f| {
-        //         let field_name = f.field.ident.as_ref().unwrap();
-        //         let f_name = field_name.to_string();
-        //         let param_name = f.rename.as_ref().unwrap_or(&f_name);
-        //         quote::quote! {
-        //             ::#crate_root::nn::WithParams::gather_by_name(&self.#field_name, params, prefix, #param_name);
-        //         }
-        //     })
-        //     .collect();
+        let gather_named_params: Vec<_> = field_opts
+            .iter()
+            .filter(|f| !f.skip)
+            .map(|f| {
+                let field_name = f.field.ident.as_ref().unwrap();
+                let f_name = field_name.to_string();
+                let param_name = f.rename.as_ref().unwrap_or(&f_name);
+                quote::quote! {
+                    ::#crate_root::module::WithParams::gather_by_name(&self.#field_name, params, prefix, #param_name);
+                }
+            })
+            .collect();
 
         quote::quote! {
             impl #impl_generics ::#crate_root::module::Module for #receiver_name #type_generics #where_clause {
@@ -210,10 +210,10 @@ pub fn module(item: TokenStream) -> TokenStream {
                 // fn update_params(&self, params: &mut std::collections::HashMap<usize, ::#crate_root::Tensor>) {
                 //     #(#update_params)*
                 // }
-                //
-                // fn gather_named_params(&self, prefix: &str, params: &mut std::collections::HashMap<String, ::#crate_root::Tensor>) {
-                //     #(#gather_named_params)*
-                // }
+
+                fn gather_named_params(&self, prefix: &str, params: &mut std::collections::HashMap<String, ::#crate_root::MLXArray>) {
+                    #(#gather_named_params)*
+                }
 
                 fn update_named_params(&mut self, prefix: &str, params: &mut std::collections::HashMap<String, ::#crate_root::MLXArray>) {
                     #(#update_named_params)*
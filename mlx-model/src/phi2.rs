@@ -13,7 +13,6 @@ use mlx_rust::fast::{fast_RoPE, fast_scaled_dot_product_attention};
 use mlx_rust::MLXArray;
 use mlx_rust::module::Module;
 use mlx_rust::r#type::MlxType;
-use mlx_rust::stream::get_default_stream;
 
 #[derive(Debug, Clone, PartialEq, Deserialize)]
 pub struct Config {
@@ -94,12 +93,8 @@ impl Model {
     }
 
     fn get_mask(size: usize) -> MLXArray {
-        let mask = MLXArray::arange::<f32>(0.0..(size as f64), 1.0, get_default_stream());
-        let mask = mask.reshape(&[size as  i32, 1]).less_with_stream(
-            &mask.reshape(&[1, size as i32]),
-            get_default_stream()
-        );
-        mask.as_type::<i32>() * (-1e9)
+        let size = size as i32;
+        MLXArray::full(&[size, size], -1e9f32).triu(1)
     }
 }
 
@@ -135,7 +130,6 @@ impl DecoderLayer {
         // x.eval();
         // mask.clone().map(|m|m.eval());
         // let instant = std::time::Instant::now();
-        let x = x.as_type::<f16>();
         let feed_forward_hidden_states = self.mlp.forward(x);
         // attn_outputs.eval();
         // feed_forward_hidden_states.eval();
@@ -249,14 +243,12 @@ impl Attention {
                 let query_states = self.rotary_emb.forward((query_states, offset));
                 let key_states = self.rotary_emb.forward((key_states, offset));
                 let k = MLXArray::cat(
-                    (key_cache.clone(), key_states.clone()),
+                    (key_cache.clone(), key_states),
                     2,
-                    get_default_stream(),
                 );
                 let v = MLXArray::cat(
-                    (value_cache.clone(), value_states.clone()),
+                    (value_cache.clone(), value_states),
                     2,
-                    get_default_stream(),
                 );
                 (query_states, k, v)
             }
@@ -288,8 +280,6 @@ impl Attention {
         //     }
         // };
         drop(kv_cache);
-        self.kv_cache
-            .replace(Some((key_states.clone(), value_states.clone())));
 
         // query_states.eval();
         // key_states.eval();
@@ -315,16 +305,16 @@ impl Attention {
 
         let output = fast_scaled_dot_product_attention(
             // query_states,
-            query_states.as_type::<f32>(),
-            key_states,
-            value_states,
+            &query_states.as_type::<f32>(),
+            &key_states,
+            &value_states,
             scale,
-            mask,
-            get_default_stream(),
+            mask.as_ref(),
         )
             .as_type::<f16>()
             .transpose(&[0, 2, 1, 3])
             .reshape(&[b_size, seq_len, -1]);
+        self.kv_cache.replace(Some((key_states, value_states)));
         let r = self.dense.forward(output);
         r
     }
@@ -347,13 +337,12 @@ impl RotaryEmbedding {
         let shape = x.shape();
         let x = x.reshape(&[-1, x.dim(-2), x.dim(-1)]);
         let x = fast_RoPE(
-            x,
+            &x,
             self.dim.clone() as i32,
             false,
             10_000.0,
             1.0,
             offset as i32,
-            get_default_stream(),
         );
         return x.reshape(shape);
     }
@@ -1,10 +1,10 @@
-use std::f32::consts::PI;
+use std::f32::consts::{PI, SQRT_2};
 use std::fmt::{Debug, Formatter};
 use std::sync::Arc;
 
 use serde::Deserialize;
 
-use mlx_rust::array_op::{erf, sigmoid, sqrt, square, tanh};
+use mlx_rust::array_op::{erf, sigmoid, square, tanh};
 use mlx_rust::closure::MLXFunc;
 use mlx_rust::compile::compile;
 
@@ -22,15 +22,15 @@ pub enum Activation {
 }
 
 fn gelu(x: MLXArray) -> MLXArray {
-    x.clone() * (1 + erf(x / sqrt(2.0.into()))) / 2
+    &x * (1 + erf(&(&x / SQRT_2))) / 2
 }
 
 fn new_gelu(x: MLXArray) -> MLXArray {
-    0.5 * x.clone() * (1.0 + tanh((2.0 / PI).sqrt() * (x.clone() + 0.044715 * x.clone().powf(&3.0.into(), None))))
+    0.5 * &x * (1.0 + tanh(&((2.0 / PI).sqrt() * (&x + 0.044715 * square(&x) * &x))))
 }
 
 pub fn approximate_gelu(x: MLXArray) -> MLXArray {
-    &x * sigmoid(1.60033 * &x * (1 + 0.0433603 * square(x.clone())))
+    &x * sigmoid(&(1.60033 * &x * (1 + 0.0433603 * square(&x))))
 }
 
 // static compiled_gelu: Box<dyn Fn(MLXArray) -> MLXArray>  = {
@@ -65,7 +65,7 @@ impl  ActivationLayer {
                 f
             }
             Activation::Relu => {
-                let f: Box<dyn Fn(MLXArray) -> MLXArray> = Box::new(|x: MLXArray| x.maximum_with_stream(&(0.into()), None));
+                let f: Box<dyn Fn(MLXArray) -> MLXArray> = Box::new(|x: MLXArray| x.maximum(&(0.into())));
                 f
             }
         };
@@ -1,7 +1,6 @@
 use mlx_derive::Module;
 use mlx_rust::r#type::MlxType;
 use mlx_rust::random::{key, uniform};
-use mlx_rust::stream::get_default_stream;
 
 use crate::MLXArray;
 
@@ -17,13 +16,12 @@ impl Embedding {
         let weight = uniform::<T>(
             -scale..=scale,
             &[num_embeddings as i32, features as i32],
-            rng_key,
-            get_default_stream(),
+            &rng_key,
         );
         Self { weight: weight }
     }
 
     fn fwd(&self, index: MLXArray) -> MLXArray {
-        self.weight.index_select(0, index, None)
+        self.weight.index_select(0, &index)
     }
 }
@@ -1,7 +1,6 @@
 use mlx_derive::Module;
 use mlx_rust::fast::fast_layer_norm;
 use mlx_rust::r#type::MlxType;
-use mlx_rust::stream::get_default_stream;
 use crate::MLXArray;
 
 #[derive(Clone, Debug, Module)]
@@ -16,8 +15,8 @@ impl LayerNorm {
     pub fn new<T: MlxType>(dim: i32, affine: bool, eps: f32) -> Self {
         let (weight, bias) = if affine {
             (
-                Some(MLXArray::ones::<T>(&[dim], get_default_stream())),
-                Some(MLXArray::zeros::<T>(&[dim], get_default_stream())),
+                Some(MLXArray::ones::<T>(&[dim])),
+                Some(MLXArray::zeros::<T>(&[dim])),
             )
         } else {
             (None, None)
@@ -29,11 +28,10 @@ impl LayerNorm {
 impl LayerNorm {
     fn fwd(&self, value: MLXArray) -> MLXArray {
         fast_layer_norm(
-            value,
-            self.weight.clone(),
-            self.bias.clone(),
+            &value,
+            self.weight.as_ref(),
+            self.bias.as_ref(),
             self.eps,
-            get_default_stream(),
         )
     }
 }
@@ -2,7 +2,6 @@ use mlx_derive::Module;
 use mlx_rust::array_op::addmm;
 use mlx_rust::r#type::MlxType;
 use mlx_rust::random::{key, uniform};
-use mlx_rust::stream::get_default_stream;
 
 use crate::MLXArray;
 
@@ -19,18 +18,16 @@ impl Linear {
         let weight = uniform::<T>(
             -scale..=scale,
             &[out_features as i32, in_features as i32],
-            rng_key,
-            get_default_stream(),
+            &rng_key,
         );
         let rng_key = key(1);
         let bias = if has_bias {
             let bias = uniform::<T>(
                 -scale..=scale,
                 &[out_features as i32],
-                rng_key,
-                get_default_stream(),
+                &rng_key,
             );
-            // let bias = MLXArray::ones::<T>(&[out_features as i32], get_default_stream());
+            // let bias = MLXArray::ones::<T>(&[out_features as i32], None);
             Some(bias)
         } else {
             None
@@ -45,14 +42,13 @@ impl Linear {
 impl Linear {
     fn fwd(&self, value: MLXArray) -> MLXArray {
         match &self.bias {
-            None => value.matmul(self.weight.t(None), None),
+            None => value.matmul(&self.weight.t()),
             Some(bias) => addmm(
                 &value,
                 bias,
                 &self.weight,
                 1.0,
-                1.0,
-                None
+                1.0
             ),
         }
     }
@@ -62,32 +58,100 @@ impl Linear {
 mod tests {
     use std::collections::HashMap;
 
+    use half::f16;
     use mlx_rust::MLXArray;
+    use mlx_rust::io::shard::INDEX_FILE;
     use mlx_rust::module::Module;
-    use mlx_rust::stream::get_default_stream;
 
     use crate::linear::Linear;
 
     #[test]
     pub fn test_forward_params() {
-        let x = MLXArray::ones::<f32>(&[3, 1024], get_default_stream());
+        let x = MLXArray::ones::<f32>(&[3, 1024]);
         let linear = Linear::new::<f32>(1024, 1024, true);
         let y = linear.forward(x);
         println!("{}", y)
     }
 
+    #[test]
+    pub fn test_named_params() {
+        let linear = Linear::new::<f32>(4, 3, true);
+        let params = linear.named_params();
+        assert_eq!(&[3, 4], params["weight"].shape());
+        assert_eq!(&[3], params["bias"].shape());
+        assert_eq!((3 * 4 + 3) * 4, linear.nbytes());
+    }
+
+    #[test]
+    pub fn test_save_and_load_safetensors() {
+        let linear = Linear::new::<f16>(4, 3, true);
+        let dir = tempfile::tempdir().unwrap();
+        let file_path = dir.path().join("linear.safetensors");
+        linear.save_safetensors(&file_path);
+
+        let mut loaded = Linear::new::<f16>(4, 3, true);
+        loaded.weight = MLXArray::zeros::<f16>(&[3, 4]);
+        loaded.bias = Some(MLXArray::zeros::<f16>(&[3]));
+        loaded.update_by_safetensors(&[&file_path]);
+        let x = MLXArray::ones::<f16>(&[2, 4]);
+        let expected = linear.forward(x.clone());
+        assert_eq!(expected.to_slice::<f16>().unwrap(), loaded.forward(x).to_slice::<f16>().unwrap());
+    }
+
+    #[test]
+    pub fn test_load_mmap_safetensors() {
+        let linear = Linear::new::<f32>(4, 3, true);
+        let dir = tempfile::tempdir().unwrap();
+        let file_path = dir.path().join("linear.safetensors");
+        linear.save_safetensors(&file_path);
+
+        let mut loaded = Linear::new::<f32>(4, 3, true);
+        loaded.weight = MLXArray::zeros::<f32>(&[3, 4]);
+        loaded.bias = Some(MLXArray::zeros::<f32>(&[3]));
+        loaded.update_by_mmap_safetensors(&[&file_path]);
+        let x = MLXArray::ones::<f32>(&[2, 4]);
+        let expected = linear.forward(x.clone());
+        assert_eq!(expected.to_slice::<f32>().unwrap(), loaded.forward(x).to_slice::<f32>().unwrap());
+    }
+
+    #[test]
+    pub fn test_save_and_load_sharded_safetensors() {
+        let linear = Linear::new::<f32>(4, 3, true);
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().join("checkpoint");
+        linear.save_sharded_safetensors(&dir, 16);
+
+        let mut loaded = Linear::new::<f32>(4, 3, true);
+        loaded.weight = MLXArray::zeros::<f32>(&[3, 4]);
+        loaded.bias = Some(MLXArray::zeros::<f32>(&[3]));
+        let mut shards = 0;
+        loaded.update_by_sharded_safetensors(dir.join(INDEX_FILE), |p| shards = p.shards);
+        assert_eq!(2, shards);
+        let x = MLXArray::ones::<f32>(&[2, 4]);
+        let expected = linear.forward(x.clone());
+        assert_eq!(expected.to_slice::<f32>().unwrap(), loaded.forward(x).to_slice::<f32>().unwrap());
+
+        // the bias shard is not needed without a bias
+        let mut no_bias = Linear::new::<f32>(4, 3, false);
+        no_bias.update_by_sharded_safetensors(dir.join(INDEX_FILE), |p| shards = p.shards);
+        assert_eq!(1, shards);
+
+        std::fs::write(dir.join(INDEX_FILE), r#"{"weight_map": {}}"#).unwrap();
+        assert!(no_bias.try_update_by_sharded_safetensors(dir.join(INDEX_FILE), |_| {}).is_err());
+    }
+
     #[test]
     pub fn test_update_params() {
         let in_features = 1024;
         let out_features = 1024;
-        let x = MLXArray::ones::<f32>(&[3, in_features as i32], get_default_stream());
+        let x = MLXArray::ones::<f32>(&[3, in_features as i32]);
         let mut linear = Linear::new::<f32>(in_features, out_features, true);
         let mut params: HashMap<String, MLXArray> = HashMap::new();
-        params.insert("weight".into(), MLXArray::ones::<f32>(&[out_features as i32, in_features as i32], get_default_stream()));
-        params.insert("bias".into(), MLXArray::ones::<f32>(&[out_features as i32], get_default_stream()));
+        params.insert("weight".into(), MLXArray::ones::<f32>(&[out_features as i32, in_features as i32]));
+        params.insert("bias".into(), MLXArray::ones::<f32>(&[out_features as i32]));
         linear.update_named_params("", &mut params);
         let y = linear.forward(x);
         println!("{}", y);
-        // assert_eq!(y, MLXArray::ones::<f32>(&[out_features as i32], get_default_stream()))
+        // assert_eq!(y, MLXArray::ones::<f32>(&[out_features as i32], None))
     }
 }
@@ -1,5 +1,4 @@
 use std::time::Instant;
-use half::f16;
 use mlx_derive::Module;
 use mlx_rust::module::Module;
 use mlx_rust::r#type::MlxType;
@@ -37,7 +36,6 @@ impl MLP {
         let y = self.fc1.forward(x);
         let y = self.act.fwd(y);
         // y.eval();
-        let y = y.as_type::<f16>();
         // println!("act time: {:?}", instant.elapsed());
 
         self.fc2.forward(y)
@@ -1,7 +1,6 @@
 use mlx_derive::Module;
 use mlx_rust::fast::fast_rms_norm;
 use mlx_rust::r#type::MlxType;
-use mlx_rust::stream::get_default_stream;
 use crate::MLXArray;
 
 #[derive(Clone, Debug, Module)]
@@ -13,12 +12,12 @@ pub struct RmsNorm {
 
 impl RmsNorm {
     pub fn new<T: MlxType>(dim: i32, eps: f32) -> Self {
-        let weight = MLXArray::ones::<T>(&[dim], get_default_stream());
+        let weight = MLXArray::ones::<T>(&[dim]);
         Self { weight, eps }
     }
 }
 impl RmsNorm {
     fn fwd(&self, value: MLXArray) -> MLXArray {
-        fast_rms_norm(value, self.weight.clone(), self.eps, get_default_stream())
+        fast_rms_norm(&value, &self.weight, self.eps)
     }
 }
@@ -3,9 +3,10 @@ use std::slice;
 
 use mlx_sys::{mlx_array, mlx_array_, mlx_array_dim, mlx_array_dtype_, mlx_array_eval, mlx_array_get_dtype, mlx_array_itemsize, mlx_array_nbytes, mlx_array_ndim, mlx_array_shape, mlx_array_size, mlx_array_strides, mlx_astype, mlx_expand_dims, mlx_reshape, mlx_transpose};
 
+use crate::error::{guard, guard_unit, MLXError};
 use crate::object::MLXObject;
-use crate::r#type::MlxType;
-use crate::stream::get_default_stream;
+use crate::r#type::{Dtype, MlxType};
+use crate::stream::{get_default_stream, MLXStream};
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct MLXArray {
@@ -25,9 +26,14 @@ impl MLXArray {
         self.handle.as_ptr()
     }
 
-    pub(crate) fn dtype(&self) -> mlx_array_dtype_ {
+    pub(crate) fn raw_dtype(&self) -> mlx_array_dtype_ {
         unsafe { mlx_array_get_dtype(self.as_ptr()) }
     }
+
+    /// The array's element type.
+    pub fn dtype(&self) -> Dtype {
+        Dtype::try_from(self.raw_dtype()).unwrap()
+    }
 }
 
 impl MLXArray {
@@ -60,6 +66,12 @@ impl MLXArray {
         }
     }
 
+    /// The number of dimensions of the array.
+    #[inline]
+    pub fn ndim(&self) -> usize {
+        unsafe { mlx_array_ndim(self.as_ptr()) }
+    }
+
     ///The shape of the array
     pub fn shape(&self) -> &[i32] {
         unsafe {
@@ -71,15 +83,27 @@ impl MLXArray {
     }
 
     pub fn reshape(&self, shape: &[i32]) -> MLXArray {
-        let handle = unsafe {
+        self.try_reshape(shape).unwrap()
+    }
+
+    pub fn reshape_on(&self, shape: &[i32], stream: &MLXStream) -> MLXArray {
+        self.try_reshape_on(shape, stream).unwrap()
+    }
+
+    pub fn try_reshape(&self, shape: &[i32]) -> Result<MLXArray, MLXError> {
+        self.try_reshape_on(shape, &get_default_stream())
+    }
+
+    pub fn try_reshape_on(&self, shape: &[i32], stream: &MLXStream) -> Result<MLXArray, MLXError> {
+        let handle = guard(|| unsafe {
             mlx_reshape(
                 self.as_ptr(),
                 shape.as_ptr() as *const ::std::os::raw::c_int,
                 shape.len(),
-                get_default_stream().as_ptr(),
+                stream.as_ptr(),
             )
-        };
-        MLXArray::from_raw(handle)
+        })?;
+        Ok(MLXArray::from_raw(handle))
     }
 
     pub fn dim(&self, dim: i32) -> i32 {
@@ -89,37 +113,95 @@ impl MLXArray {
     }
 
     pub fn expand_dims(&self, axes: &[i32]) -> MLXArray {
-        let handle = unsafe {
+        self.try_expand_dims(axes).unwrap()
+    }
+
+    pub fn expand_dims_on(&self, axes: &[i32], stream: &MLXStream) -> MLXArray {
+        self.try_expand_dims_on(axes, stream).unwrap()
+    }
+
+    pub fn try_expand_dims(&self, axes: &[i32]) -> Result<MLXArray, MLXError> {
+        self.try_expand_dims_on(axes, &get_default_stream())
+    }
+
+    pub fn try_expand_dims_on(&self, axes: &[i32], stream: &MLXStream) -> Result<MLXArray, MLXError> {
+        let handle = guard(|| unsafe {
             mlx_expand_dims(
                 self.as_ptr(),
                 axes.as_ptr() as *const ::std::os::raw::c_int,
                 axes.len(),
-                get_default_stream().as_ptr()
+                stream.as_ptr()
             )
-        };
-        MLXArray::from_raw(handle)
+        })?;
+        Ok(MLXArray::from_raw(handle))
     }
 
     pub fn transpose(&self, dims: &[i32]) -> MLXArray {
-        let handle = unsafe {
+        self.try_transpose(dims).unwrap()
+    }
+
+    pub fn transpose_on(&self, dims: &[i32], stream: &MLXStream) -> MLXArray {
+        self.try_transpose_on(dims, stream).unwrap()
+    }
+
+    pub fn try_transpose(&self, dims: &[i32]) -> Result<MLXArray, MLXError> {
+        self.try_transpose_on(dims, &get_default_stream())
+    }
+
+    pub fn try_transpose_on(&self, dims: &[i32], stream: &MLXStream) -> Result<MLXArray, MLXError> {
+        let handle = guard(|| unsafe {
             mlx_transpose(
                 self.as_ptr(),
                 dims.as_ptr() as *const ::std::os::raw::c_int,
                 dims.len(),
-                get_default_stream().as_ptr()
+                stream.as_ptr()
             )
-        };
-        MLXArray::from_raw(handle)
+        })?;
+        Ok(MLXArray::from_raw(handle))
     }
 
     pub fn as_type<T: MlxType>(&self) -> MLXArray {
-        let handle = unsafe  {
-            mlx_astype(self.as_ptr(), T::mlx_array_dtype, get_default_stream().as_ptr())
-        };
-        MLXArray::from_raw(handle)
+        self.try_as_type::<T>().unwrap()
+    }
+
+    pub fn as_type_on<T: MlxType>(&self, stream: &MLXStream) -> MLXArray {
+        self.try_as_type_on::<T>(stream).unwrap()
+    }
+
+    pub fn try_as_type<T: MlxType>(&self) -> Result<MLXArray, MLXError> {
+        self.try_as_type_on::<T>(&get_default_stream())
+    }
+
+    pub fn try_as_type_on<T: MlxType>(&self, stream: &MLXStream) -> Result<MLXArray, MLXError> {
+        let handle = guard(|| unsafe { mlx_astype(self.as_ptr(), T::mlx_array_dtype, stream.as_ptr()) })?;
+        Ok(MLXArray::from_raw(handle))
+    }
+
+    /// Casts to a dtype only known at runtime, e.g. parsed from a model config.
+    pub fn as_dtype(&self, dtype: Dtype) -> MLXArray {
+        self.try_as_dtype(dtype).unwrap()
+    }
+
+    pub fn as_dtype_on(&self, dtype: Dtype, stream: &MLXStream) -> MLXArray {
+        self.try_as_dtype_on(dtype, stream).unwrap()
     }
+
+    pub fn try_as_dtype(&self, dtype: Dtype) -> Result<MLXArray, MLXError> {
+        self.try_as_dtype_on(dtype, &get_default_stream())
+    }
+
+    pub fn try_as_dtype_on(&self, dtype: Dtype, stream: &MLXStream) -> Result<MLXArray, MLXError> {
+        let handle = guard(|| unsafe { mlx_astype(self.as_ptr(), dtype.as_raw(), stream.as_ptr()) })?;
+        Ok(MLXArray::from_raw(handle))
+    }
+
     pub fn eval(&self) {
-        unsafe { mlx_array_eval(self.as_ptr()) }
+        self.try_eval().unwrap()
+    }
+
+    /// Evaluates the array, reporting errors raised while computing the graph.
+    pub fn try_eval(&self) -> Result<(), MLXError> {
+        guard_unit(|| unsafe { mlx_array_eval(self.as_ptr()) })
     }
 }
 
@@ -132,6 +214,7 @@ impl Display for MLXArray {
 #[cfg(test)]
 mod tests {
     use crate::array::MLXArray;
+    use crate::r#type::Dtype;
 
     #[test]
     fn it_works() {
@@ -139,4 +222,14 @@ mod tests {
         let array1 = MLXArray::array(&[123., 134.], &[2]);
         println!("{}", array + array1.clone() + array1)
     }
+
+    #[test]
+    fn test_as_dtype() {
+        let array = MLXArray::array(&[1.0f32, 2.0], &[2]);
+        assert_eq!(Dtype::F32, array.dtype());
+        let dtype: Dtype = "bfloat16".parse().unwrap();
+        let r = array.as_dtype(dtype);
+        assert_eq!(Dtype::BF16, r.dtype());
+        assert_eq!(2, r.item_size());
+    }
 }
@@ -1,59 +1,171 @@
-use std::ops::{Add, Div, Mul, Sub};
+use std::ffi::CString;
+use std::ops::{Add, BitAnd, BitOr, BitXor, Div, Mul, Neg, Not, Rem, Shl, Shr, Sub};
 
-use mlx_sys::{mlx_add, mlx_addmm, mlx_argmax, mlx_argsort, mlx_cos, mlx_cosh, mlx_cumsum, mlx_divide, mlx_erf, mlx_erfinv, mlx_exp, mlx_floor, mlx_greater, mlx_less, mlx_log, mlx_log10, mlx_matmul, mlx_maximum, mlx_mean, mlx_mean_all, mlx_moveaxis, mlx_multiply, mlx_power, mlx_sigmoid, mlx_sign, mlx_sin, mlx_sinh, mlx_softmax, mlx_sqrt, mlx_square, mlx_squeeze, mlx_subtract, mlx_swapaxes, mlx_take, mlx_tan, mlx_tanh, mlx_transpose_all, mlx_where};
+use half::{bf16, f16};
+use num_complex::Complex32;
+use mlx_sys::{mlx_abs, mlx_add, mlx_addmm, mlx_all, mlx_all_all, mlx_any, mlx_any_all, mlx_arccos, mlx_arcsin, mlx_arctan, mlx_arctan2, mlx_argmax, mlx_argmax_all, mlx_argmin, mlx_argmin_all, mlx_argpartition, mlx_argsort, mlx_bitwise_and, mlx_bitwise_invert, mlx_bitwise_or, mlx_bitwise_xor, mlx_block_masked_mm, mlx_ceil, mlx_clip, mlx_cos, mlx_cosh, mlx_cummax, mlx_cummin, mlx_cumprod, mlx_cumsum, mlx_divide, mlx_einsum, mlx_equal, mlx_erf, mlx_erfinv, mlx_exp, mlx_expm1, mlx_floor, mlx_floor_divide, mlx_gather_mm, mlx_greater, mlx_greater_equal, mlx_inner, mlx_isinf, mlx_isnan, mlx_kron, mlx_left_shift, mlx_less, mlx_less_equal, mlx_log, mlx_log10, mlx_log1p, mlx_log2, mlx_logaddexp, mlx_logcumsumexp, mlx_logical_and, mlx_logical_not, mlx_logical_or, mlx_logsumexp, mlx_logsumexp_all, mlx_matmul, mlx_max, mlx_max_all, mlx_maximum, mlx_mean, mlx_mean_all, mlx_min, mlx_min_all, mlx_minimum, mlx_moveaxis, mlx_multiply, mlx_nan_to_num, mlx_negative, mlx_not_equal, mlx_optional_float, mlx_outer, mlx_partition, mlx_power, mlx_prod, mlx_prod_all, mlx_reciprocal, mlx_remainder, mlx_right_shift, mlx_round, mlx_rsqrt, mlx_sigmoid, mlx_sign, mlx_sin, mlx_sinh, mlx_softmax, mlx_sort, mlx_sqrt, mlx_square, mlx_squeeze, mlx_std, mlx_std_all, mlx_stop_gradient, mlx_subtract, mlx_sum, mlx_sum_all, mlx_swapaxes, mlx_take, mlx_tan, mlx_tanh, mlx_tensordot, mlx_tensordot_along_axis, mlx_transpose_all, mlx_var, mlx_var_all, mlx_where};
 
 use crate::array::MLXArray;
+use crate::error::{guard, MLXError};
+use crate::index::slice_axis;
+use crate::scatter::try_take_along_axis_on;
+use crate::r#type::{weak_scalar_dtype, Dtype};
 use crate::stream::{get_default_stream, MLXStream};
+use crate::VectorMLXArray;
 
 impl MLXArray {
-    pub fn maximum_with_stream(&self, rhs: &MLXArray, stream: Option<MLXStream>) -> Self {
-        let stream = stream.unwrap_or_else(|| get_default_stream());
-        let handle = unsafe { mlx_maximum(self.as_ptr(), rhs.as_ptr(), stream.as_ptr()) };
-        Self::from_raw(handle)
+    pub fn maximum(&self, rhs: &MLXArray) -> Self {
+        self.try_maximum(rhs).unwrap()
     }
 
-    pub fn powf(&self, b: &MLXArray, stream: Option<MLXStream>) -> Self {
-        let stream = stream.unwrap_or_else(|| get_default_stream());
-        let handle = unsafe {
+    pub fn maximum_on(&self, rhs: &MLXArray, stream: &MLXStream) -> Self {
+        self.try_maximum_on(rhs, stream).unwrap()
+    }
+
+    pub fn try_maximum(&self, rhs: &MLXArray) -> Result<Self, MLXError> {
+        self.try_maximum_on(rhs, &get_default_stream())
+    }
+
+    pub fn try_maximum_on(&self, rhs: &MLXArray, stream: &MLXStream) -> Result<Self, MLXError> {
+        let handle = guard(|| unsafe { mlx_maximum(self.as_ptr(), rhs.as_ptr(), stream.as_ptr()) })?;
+        Ok(Self::from_raw(handle))
+    }
+
+    pub fn powf(&self, b: &MLXArray) -> Self {
+        self.try_powf(b).unwrap()
+    }
+
+    pub fn powf_on(&self, b: &MLXArray, stream: &MLXStream) -> Self {
+        self.try_powf_on(b, stream).unwrap()
+    }
+
+    pub fn try_powf(&self, b: &MLXArray) -> Result<Self, MLXError> {
+        self.try_powf_on(b, &get_default_stream())
+    }
+
+    pub fn try_powf_on(&self, b: &MLXArray, stream: &MLXStream) -> Result<Self, MLXError> {
+        let handle = guard(|| unsafe {
             mlx_power(self.as_ptr(), b.as_ptr(), stream.as_ptr())
-        };
-        Self::from_raw(handle)
+        })?;
+        Ok(Self::from_raw(handle))
+    }
+
+    pub fn add(&self, rhs: &MLXArray) -> Self {
+        self.try_add(rhs).unwrap()
+    }
+
+    pub fn add_on(&self, rhs: &MLXArray, stream: &MLXStream) -> Self {
+        self.try_add_on(rhs, stream).unwrap()
+    }
+
+    pub fn try_add(&self, rhs: &MLXArray) -> Result<Self, MLXError> {
+        self.try_add_on(rhs, &get_default_stream())
+    }
+
+    pub fn try_add_on(&self, rhs: &MLXArray, stream: &MLXStream) -> Result<Self, MLXError> {
+        let handle = guard(|| unsafe { mlx_add(self.as_ptr(), rhs.as_ptr(), stream.as_ptr()) })?;
+        Ok(Self::from_raw(handle))
+    }
+
+    pub fn sub(&self, rhs: &MLXArray) -> Self {
+        self.try_sub(rhs).unwrap()
+    }
+
+    pub fn sub_on(&self, rhs: &MLXArray, stream: &MLXStream) -> Self {
+        self.try_sub_on(rhs, stream).unwrap()
+    }
+
+    pub fn try_sub(&self, rhs: &MLXArray) -> Result<Self, MLXError> {
+        self.try_sub_on(rhs, &get_default_stream())
+    }
+
+    pub fn try_sub_on(&self, rhs: &MLXArray, stream: &MLXStream) -> Result<Self, MLXError> {
+        let handle = guard(|| unsafe { mlx_subtract(self.as_ptr(), rhs.as_ptr(), stream.as_ptr()) })?;
+        Ok(Self::from_raw(handle))
+    }
+
+    pub fn less(&self, rhs: &MLXArray) -> Self {
+        self.try_less(rhs).unwrap()
+    }
+
+    pub fn less_on(&self, rhs: &MLXArray, stream: &MLXStream) -> Self {
+        self.try_less_on(rhs, stream).unwrap()
+    }
+
+    pub fn try_less(&self, rhs: &MLXArray) -> Result<Self, MLXError> {
+        self.try_less_on(rhs, &get_default_stream())
+    }
+
+    pub fn try_less_on(&self, rhs: &MLXArray, stream: &MLXStream) -> Result<Self, MLXError> {
+        let handle = guard(|| unsafe { mlx_less(self.as_ptr(), rhs.as_ptr(), stream.as_ptr()) })?;
+        Ok(Self::from_raw(handle))
+    }
+
+    pub fn greater(&self, rhs: &MLXArray) -> Self {
+        self.try_greater(rhs).unwrap()
+    }
+
+    pub fn greater_on(&self, rhs: &MLXArray, stream: &MLXStream) -> Self {
+        self.try_greater_on(rhs, stream).unwrap()
+    }
+
+    pub fn try_greater(&self, rhs: &MLXArray) -> Result<Self, MLXError> {
+        self.try_greater_on(rhs, &get_default_stream())
+    }
+
+    pub fn try_greater_on(&self, rhs: &MLXArray, stream: &MLXStream) -> Result<Self, MLXError> {
+        let handle = guard(|| unsafe { mlx_greater(self.as_ptr(), rhs.as_ptr(), stream.as_ptr()) })?;
+        Ok(Self::from_raw(handle))
     }
 
-    pub fn add_with_stream(&self, rhs: &MLXArray, stream: MLXStream) -> Self {
-        let handle = unsafe { mlx_add(self.as_ptr(), rhs.as_ptr(), stream.as_ptr()) };
-        Self::from_raw(handle)
+    pub fn div(&self, rhs: &MLXArray) -> Self {
+        self.try_div(rhs).unwrap()
     }
 
-    pub fn sub_with_stream(&self, rhs: &MLXArray, stream: MLXStream) -> Self {
-        let handle = unsafe { mlx_subtract(self.as_ptr(), rhs.as_ptr(), stream.as_ptr()) };
-        Self::from_raw(handle)
+    pub fn div_on(&self, rhs: &MLXArray, stream: &MLXStream) -> Self {
+        self.try_div_on(rhs, stream).unwrap()
     }
 
-    pub fn less_with_stream(&self, rhs: &MLXArray, stream: MLXStream) -> Self {
-        let handle = unsafe { mlx_less(self.as_ptr(), rhs.as_ptr(), stream.as_ptr()) };
-        Self::from_raw(handle)
+    pub fn try_div(&self, rhs: &MLXArray) -> Result<Self, MLXError> {
+        self.try_div_on(rhs, &get_default_stream())
     }
 
-    pub fn greater_with_stream(&self, rhs: &MLXArray, stream: Option<MLXStream>) -> Self {
-        let stream = stream.unwrap_or_else(|| get_default_stream());
-        let handle = unsafe { mlx_greater(self.as_ptr(), rhs.as_ptr(), stream.as_ptr()) };
-        Self::from_raw(handle)
+    pub fn try_div_on(&self, rhs: &MLXArray, stream: &MLXStream) -> Result<Self, MLXError> {
+        let handle = guard(|| unsafe { mlx_divide(self.as_ptr(), rhs.as_ptr(), stream.as_ptr()) })?;
+        Ok(Self::from_raw(handle))
     }
 
-    pub fn div_with_stream(&self, rhs: &MLXArray, stream: MLXStream) -> Self {
-        let handle = unsafe { mlx_divide(self.as_ptr(), rhs.as_ptr(), stream.as_ptr()) };
-        Self::from_raw(handle)
+    pub fn mul(&self, rhs: &MLXArray) -> Self {
+        self.try_mul(rhs).unwrap()
     }
 
-    pub fn mul_with_stream(&self, rhs: &MLXArray, stream: MLXStream) -> Self {
-        let handle = unsafe { mlx_multiply(self.as_ptr(), rhs.as_ptr(), stream.as_ptr()) };
-        Self::from_raw(handle)
+    pub fn mul_on(&self, rhs: &MLXArray, stream: &MLXStream) -> Self {
+        self.try_mul_on(rhs, stream).unwrap()
     }
 
-    pub fn mean(&self, axes: &[i32], keep_dims: bool, stream: Option<MLXStream>) -> MLXArray {
-        let stream = stream.unwrap_or_else(|| get_default_stream());
-        let handle = unsafe {
+    pub fn try_mul(&self, rhs: &MLXArray) -> Result<Self, MLXError> {
+        self.try_mul_on(rhs, &get_default_stream())
+    }
+
+    pub fn try_mul_on(&self, rhs: &MLXArray, stream: &MLXStream) -> Result<Self, MLXError> {
+        let handle = guard(|| unsafe { mlx_multiply(self.as_ptr(), rhs.as_ptr(), stream.as_ptr()) })?;
+        Ok(Self::from_raw(handle))
+    }
+
+    pub fn mean(&self, axes: &[i32], keep_dims: bool) -> MLXArray {
+        self.try_mean(axes, keep_dims).unwrap()
+    }
+
+    pub fn mean_on(&self, axes: &[i32], keep_dims: bool, stream: &MLXStream) -> MLXArray {
+        self.try_mean_on(axes, keep_dims, stream).unwrap()
+    }
+
+    pub fn try_mean(&self, axes: &[i32], keep_dims: bool) -> Result<MLXArray, MLXError> {
+        self.try_mean_on(axes, keep_dims, &get_default_stream())
+    }
+
+    pub fn try_mean_on(&self, axes: &[i32], keep_dims: bool, stream: &MLXStream) -> Result<MLXArray, MLXError> {
+        let handle = guard(|| unsafe {
             mlx_mean(
                 self.as_ptr(),
                 axes.as_ptr(),
@@ -61,59 +173,456 @@ impl MLXArray {
                 keep_dims,
                 stream.as_ptr(),
             )
-        };
-        MLXArray::from_raw(handle)
+        })?;
+        Ok(MLXArray::from_raw(handle))
+    }
+
+    pub fn mean_all(&self, keep_dims: bool) -> MLXArray {
+        self.try_mean_all(keep_dims).unwrap()
+    }
+
+    pub fn mean_all_on(&self, keep_dims: bool, stream: &MLXStream) -> MLXArray {
+        self.try_mean_all_on(keep_dims, stream).unwrap()
     }
 
-    pub fn mean_all(&self, keep_dims: bool, stream: Option<MLXStream>) -> MLXArray {
-        let stream = stream.unwrap_or_else(|| get_default_stream());
-        let handle = unsafe { mlx_mean_all(self.as_ptr(), keep_dims, stream.as_ptr()) };
-        MLXArray::from_raw(handle)
+    pub fn try_mean_all(&self, keep_dims: bool) -> Result<MLXArray, MLXError> {
+        self.try_mean_all_on(keep_dims, &get_default_stream())
     }
 
-    pub fn matmul(&self, b: MLXArray, stream: Option<MLXStream>) -> MLXArray {
-        let stream = stream.unwrap_or_else(|| get_default_stream());
-        let handle = unsafe { mlx_matmul(self.as_ptr(), b.as_ptr(), stream.as_ptr()) };
-        MLXArray::from_raw(handle)
+    pub fn try_mean_all_on(&self, keep_dims: bool, stream: &MLXStream) -> Result<MLXArray, MLXError> {
+        let handle = guard(|| unsafe { mlx_mean_all(self.as_ptr(), keep_dims, stream.as_ptr()) })?;
+        Ok(MLXArray::from_raw(handle))
     }
 
-    pub fn index_select(&self, dim: i32, index: MLXArray, stream: Option<MLXStream>) -> MLXArray {
-        let stream = stream.unwrap_or_else(|| get_default_stream());
-        let handle = unsafe {
+    pub fn matmul(&self, b: &MLXArray) -> MLXArray {
+        self.try_matmul(b).unwrap()
+    }
+
+    pub fn matmul_on(&self, b: &MLXArray, stream: &MLXStream) -> MLXArray {
+        self.try_matmul_on(b, stream).unwrap()
+    }
+
+    pub fn try_matmul(&self, b: &MLXArray) -> Result<MLXArray, MLXError> {
+        self.try_matmul_on(b, &get_default_stream())
+    }
+
+    pub fn try_matmul_on(&self, b: &MLXArray, stream: &MLXStream) -> Result<MLXArray, MLXError> {
+        let handle = guard(|| unsafe { mlx_matmul(self.as_ptr(), b.as_ptr(), stream.as_ptr()) })?;
+        Ok(MLXArray::from_raw(handle))
+    }
+
+    pub fn index_select(&self, dim: i32, index: &MLXArray) -> MLXArray {
+        self.try_index_select(dim, index).unwrap()
+    }
+
+    pub fn index_select_on(&self, dim: i32, index: &MLXArray, stream: &MLXStream) -> MLXArray {
+        self.try_index_select_on(dim, index, stream).unwrap()
+    }
+
+    pub fn try_index_select(&self, dim: i32, index: &MLXArray) -> Result<MLXArray, MLXError> {
+        self.try_index_select_on(dim, index, &get_default_stream())
+    }
+
+    pub fn try_index_select_on(&self, dim: i32, index: &MLXArray, stream: &MLXStream) -> Result<MLXArray, MLXError> {
+        let handle = guard(|| unsafe {
             mlx_take(self.as_ptr(), index.as_ptr(), dim, stream.as_ptr())
-        };
-        MLXArray::from_raw(handle)
+        })?;
+        Ok(MLXArray::from_raw(handle))
     }
 
-    pub fn t(&self, stream: Option<MLXStream>) -> MLXArray {
-        let stream = stream.unwrap_or_else(|| get_default_stream());
-        let handle = unsafe { mlx_transpose_all(self.as_ptr(), stream.as_ptr()) };
-        MLXArray::from_raw(handle)
+    pub fn t(&self) -> MLXArray {
+        self.try_t().unwrap()
     }
 
-    pub fn swap_axes(&self, a: i32, b: i32, stream: Option<MLXStream>) -> MLXArray {
-        let stream = stream.unwrap_or_else(|| get_default_stream());
-        let handle = unsafe {
+    pub fn t_on(&self, stream: &MLXStream) -> MLXArray {
+        self.try_t_on(stream).unwrap()
+    }
+
+    pub fn try_t(&self) -> Result<MLXArray, MLXError> {
+        self.try_t_on(&get_default_stream())
+    }
+
+    pub fn try_t_on(&self, stream: &MLXStream) -> Result<MLXArray, MLXError> {
+        let handle = guard(|| unsafe { mlx_transpose_all(self.as_ptr(), stream.as_ptr()) })?;
+        Ok(MLXArray::from_raw(handle))
+    }
+
+    pub fn swap_axes(&self, a: i32, b: i32) -> MLXArray {
+        self.try_swap_axes(a, b).unwrap()
+    }
+
+    pub fn swap_axes_on(&self, a: i32, b: i32, stream: &MLXStream) -> MLXArray {
+        self.try_swap_axes_on(a, b, stream).unwrap()
+    }
+
+    pub fn try_swap_axes(&self, a: i32, b: i32) -> Result<MLXArray, MLXError> {
+        self.try_swap_axes_on(a, b, &get_default_stream())
+    }
+
+    pub fn try_swap_axes_on(&self, a: i32, b: i32, stream: &MLXStream) -> Result<MLXArray, MLXError> {
+        let handle = guard(|| unsafe {
             mlx_swapaxes(self.as_ptr(), a, b, stream.as_ptr())
-        };
-        MLXArray::from_raw(handle)
+        })?;
+        Ok(MLXArray::from_raw(handle))
+    }
+
+    pub fn move_axes(&self, source: i32, destination: i32) -> MLXArray {
+        self.try_move_axes(source, destination).unwrap()
     }
 
-    pub fn move_axes(&self, source: i32, destination: i32, stream: Option<MLXStream>) -> MLXArray {
-        let stream = stream.unwrap_or_else(|| get_default_stream());
-        let handle = unsafe {
+    pub fn move_axes_on(&self, source: i32, destination: i32, stream: &MLXStream) -> MLXArray {
+        self.try_move_axes_on(source, destination, stream).unwrap()
+    }
+
+    pub fn try_move_axes(&self, source: i32, destination: i32) -> Result<MLXArray, MLXError> {
+        self.try_move_axes_on(source, destination, &get_default_stream())
+    }
+
+    pub fn try_move_axes_on(&self, source: i32, destination: i32, stream: &MLXStream) -> Result<MLXArray, MLXError> {
+        let handle = guard(|| unsafe {
             mlx_moveaxis(self.as_ptr(), source, destination, stream.as_ptr())
+        })?;
+        Ok(MLXArray::from_raw(handle))
+    }
+}
+
+macro_rules! impl_binary_op {
+    ($name:ident, $mlx_func:ident, $doc:literal) => {
+        paste::paste! {
+            impl MLXArray {
+                #[doc = $doc]
+                pub fn $name(&self, rhs: &MLXArray) -> MLXArray {
+                    self.[<try_ $name>](rhs).unwrap()
+                }
+
+                pub fn [<$name _on>](&self, rhs: &MLXArray, stream: &MLXStream) -> MLXArray {
+                    self.[<try_ $name _on>](rhs, stream).unwrap()
+                }
+
+                pub fn [<try_ $name>](&self, rhs: &MLXArray) -> Result<MLXArray, MLXError> {
+                    self.[<try_ $name _on>](rhs, &get_default_stream())
+                }
+
+                pub fn [<try_ $name _on>](&self, rhs: &MLXArray, stream: &MLXStream) -> Result<MLXArray, MLXError> {
+                    let handle = guard(|| unsafe { $mlx_func(self.as_ptr(), rhs.as_ptr(), stream.as_ptr()) })?;
+                    Ok(MLXArray::from_raw(handle))
+                }
+            }
+        }
+    };
+}
+
+impl_binary_op!(equal, mlx_equal, "Element-wise `==`, returns a bool array.");
+impl_binary_op!(not_equal, mlx_not_equal, "Element-wise `!=`, returns a bool array.");
+impl_binary_op!(less_equal, mlx_less_equal, "Element-wise `<=`, returns a bool array.");
+impl_binary_op!(greater_equal, mlx_greater_equal, "Element-wise `>=`, returns a bool array.");
+impl_binary_op!(logical_and, mlx_logical_and, "Element-wise logical and.");
+impl_binary_op!(logical_or, mlx_logical_or, "Element-wise logical or.");
+impl_binary_op!(bitwise_and, mlx_bitwise_and, "Element-wise bitwise and, for integer and bool arrays.");
+impl_binary_op!(bitwise_or, mlx_bitwise_or, "Element-wise bitwise or, for integer and bool arrays.");
+impl_binary_op!(bitwise_xor, mlx_bitwise_xor, "Element-wise bitwise xor, for integer and bool arrays.");
+impl_binary_op!(left_shift, mlx_left_shift, "Element-wise left shift of integer arrays.");
+impl_binary_op!(right_shift, mlx_right_shift, "Element-wise right shift of integer arrays.");
+impl_binary_op!(remainder, mlx_remainder, "Element-wise remainder, the result has the sign of `rhs` like numpy.");
+impl_binary_op!(floor_divide, mlx_floor_divide, "Element-wise division rounded towards negative infinity.");
+impl_binary_op!(minimum, mlx_minimum, "Element-wise minimum.");
+impl_binary_op!(logaddexp, mlx_logaddexp, "Element-wise numerically stable `log(exp(a) + exp(b))`.");
+impl_binary_op!(arctan2, mlx_arctan2, "Element-wise arc tangent of `self / rhs`, using the signs of both to pick the quadrant.");
+
+impl MLXArray {
+    /// Rounds to the given number of decimals, halfway values go to the nearest even value.
+    pub fn round(&self, decimals: i32) -> MLXArray {
+        self.try_round(decimals).unwrap()
+    }
+
+    pub fn round_on(&self, decimals: i32, stream: &MLXStream) -> MLXArray {
+        self.try_round_on(decimals, stream).unwrap()
+    }
+
+    pub fn try_round(&self, decimals: i32) -> Result<MLXArray, MLXError> {
+        self.try_round_on(decimals, &get_default_stream())
+    }
+
+    pub fn try_round_on(&self, decimals: i32, stream: &MLXStream) -> Result<MLXArray, MLXError> {
+        let handle = guard(|| unsafe { mlx_round(self.as_ptr(), decimals, stream.as_ptr()) })?;
+        Ok(MLXArray::from_raw(handle))
+    }
+
+    /// Clips the values to `[min, max]`, at least one of the bounds must be given.
+    pub fn clip(&self, min: Option<&MLXArray>, max: Option<&MLXArray>) -> MLXArray {
+        self.try_clip(min, max).unwrap()
+    }
+
+    pub fn clip_on(&self, min: Option<&MLXArray>, max: Option<&MLXArray>, stream: &MLXStream) -> MLXArray {
+        self.try_clip_on(min, max, stream).unwrap()
+    }
+
+    pub fn try_clip(&self, min: Option<&MLXArray>, max: Option<&MLXArray>) -> Result<MLXArray, MLXError> {
+        self.try_clip_on(min, max, &get_default_stream())
+    }
+
+    pub fn try_clip_on(&self, min: Option<&MLXArray>, max: Option<&MLXArray>, stream: &MLXStream) -> Result<MLXArray, MLXError> {
+        if min.is_none() && max.is_none() {
+            return Err(MLXError::InvalidArgument("clip needs at least one of min and max".into()));
+        }
+        let min = min.map_or(std::ptr::null_mut(), |a| a.as_ptr());
+        let max = max.map_or(std::ptr::null_mut(), |a| a.as_ptr());
+        let handle = guard(|| unsafe { mlx_clip(self.as_ptr(), min, max, stream.as_ptr()) })?;
+        Ok(MLXArray::from_raw(handle))
+    }
+
+    /// Replaces NaN with `nan` and infinities with `posinf` / `neginf`, which default to the
+    /// largest and lowest finite values of the dtype.
+    pub fn nan_to_num(&self, nan: f32, posinf: Option<f32>, neginf: Option<f32>) -> MLXArray {
+        self.try_nan_to_num(nan, posinf, neginf).unwrap()
+    }
+
+    pub fn nan_to_num_on(&self, nan: f32, posinf: Option<f32>, neginf: Option<f32>, stream: &MLXStream) -> MLXArray {
+        self.try_nan_to_num_on(nan, posinf, neginf, stream).unwrap()
+    }
+
+    pub fn try_nan_to_num(&self, nan: f32, posinf: Option<f32>, neginf: Option<f32>) -> Result<MLXArray, MLXError> {
+        self.try_nan_to_num_on(nan, posinf, neginf, &get_default_stream())
+    }
+
+    pub fn try_nan_to_num_on(&self, nan: f32, posinf: Option<f32>, neginf: Option<f32>, stream: &MLXStream) -> Result<MLXArray, MLXError> {
+        let optional = |v: Option<f32>| mlx_optional_float {
+            value: v.unwrap_or_default(),
+            has_value: v.is_some(),
         };
-        MLXArray::from_raw(handle)
+        let handle = guard(|| unsafe {
+            mlx_nan_to_num(self.as_ptr(), nan, optional(posinf), optional(neginf), stream.as_ptr())
+        })?;
+        Ok(MLXArray::from_raw(handle))
     }
 }
 
-pub fn argmax(x: &MLXArray, axis: i32, keep_dims: bool, stream: Option<MLXStream>) -> MLXArray {
-    let stream = stream.unwrap_or_else(|| get_default_stream());
-    let handle = unsafe {
-        mlx_argmax(x.as_ptr(), axis, keep_dims, stream.as_ptr())
+macro_rules! impl_reduce_op {
+    ($name:ident, $mlx_func:ident, $mlx_all_func:ident, $doc:literal) => {
+        paste::paste! {
+            impl MLXArray {
+                #[doc = $doc]
+                #[doc = ""]
+                #[doc = "Reduces over `axes`, keeping them with size one if `keep_dims` is set."]
+                pub fn $name(&self, axes: &[i32], keep_dims: bool) -> MLXArray {
+                    self.[<try_ $name>](axes, keep_dims).unwrap()
+                }
+
+                pub fn [<$name _on>](&self, axes: &[i32], keep_dims: bool, stream: &MLXStream) -> MLXArray {
+                    self.[<try_ $name _on>](axes, keep_dims, stream).unwrap()
+                }
+
+                pub fn [<try_ $name>](&self, axes: &[i32], keep_dims: bool) -> Result<MLXArray, MLXError> {
+                    self.[<try_ $name _on>](axes, keep_dims, &get_default_stream())
+                }
+
+                pub fn [<try_ $name _on>](&self, axes: &[i32], keep_dims: bool, stream: &MLXStream) -> Result<MLXArray, MLXError> {
+                    let handle = guard(|| unsafe {
+                        $mlx_func(
+                            self.as_ptr(),
+                            axes.as_ptr(),
+                            axes.len(),
+                            keep_dims,
+                            stream.as_ptr(),
+                        )
+                    })?;
+                    Ok(MLXArray::from_raw(handle))
+                }
+
+                #[doc = $doc]
+                #[doc = ""]
+                #[doc = "Reduces over all axes."]
+                pub fn [<$name _all>](&self, keep_dims: bool) -> MLXArray {
+                    self.[<try_ $name _all>](keep_dims).unwrap()
+                }
+
+                pub fn [<$name _all_on>](&self, keep_dims: bool, stream: &MLXStream) -> MLXArray {
+                    self.[<try_ $name _all_on>](keep_dims, stream).unwrap()
+                }
+
+                pub fn [<try_ $name _all>](&self, keep_dims: bool) -> Result<MLXArray, MLXError> {
+                    self.[<try_ $name _all_on>](keep_dims, &get_default_stream())
+                }
+
+                pub fn [<try_ $name _all_on>](&self, keep_dims: bool, stream: &MLXStream) -> Result<MLXArray, MLXError> {
+                    let handle = guard(|| unsafe { $mlx_all_func(self.as_ptr(), keep_dims, stream.as_ptr()) })?;
+                    Ok(MLXArray::from_raw(handle))
+                }
+            }
+        }
     };
-    MLXArray::from_raw(handle)
+}
+
+impl_reduce_op!(sum, mlx_sum, mlx_sum_all, "Sum reduction.");
+impl_reduce_op!(prod, mlx_prod, mlx_prod_all, "Product reduction.");
+impl_reduce_op!(max, mlx_max, mlx_max_all, "Max reduction.");
+impl_reduce_op!(min, mlx_min, mlx_min_all, "Min reduction.");
+impl_reduce_op!(logsumexp, mlx_logsumexp, mlx_logsumexp_all, "A numerically stable `log(sum(exp(x)))` reduction.");
+impl_reduce_op!(all, mlx_all, mlx_all_all, "Logical and reduction, true if every element is non zero.");
+impl_reduce_op!(any, mlx_any, mlx_any_all, "Logical or reduction, true if any element is non zero.");
+
+impl MLXArray {
+    /// Variance over `axes`, divided by `N - ddof` where `N` is the number of reduced elements.
+    pub fn var(&self, axes: &[i32], keep_dims: bool, ddof: i32) -> MLXArray {
+        self.try_var(axes, keep_dims, ddof).unwrap()
+    }
+
+    pub fn var_on(&self, axes: &[i32], keep_dims: bool, ddof: i32, stream: &MLXStream) -> MLXArray {
+        self.try_var_on(axes, keep_dims, ddof, stream).unwrap()
+    }
+
+    pub fn try_var(&self, axes: &[i32], keep_dims: bool, ddof: i32) -> Result<MLXArray, MLXError> {
+        self.try_var_on(axes, keep_dims, ddof, &get_default_stream())
+    }
+
+    pub fn try_var_on(&self, axes: &[i32], keep_dims: bool, ddof: i32, stream: &MLXStream) -> Result<MLXArray, MLXError> {
+        let handle = guard(|| unsafe {
+            mlx_var(
+                self.as_ptr(),
+                axes.as_ptr(),
+                axes.len(),
+                keep_dims,
+                ddof,
+                stream.as_ptr(),
+            )
+        })?;
+        Ok(MLXArray::from_raw(handle))
+    }
+
+    /// Variance over all axes, divided by `N - ddof`.
+    pub fn var_all(&self, keep_dims: bool, ddof: i32) -> MLXArray {
+        self.try_var_all(keep_dims, ddof).unwrap()
+    }
+
+    pub fn var_all_on(&self, keep_dims: bool, ddof: i32, stream: &MLXStream) -> MLXArray {
+        self.try_var_all_on(keep_dims, ddof, stream).unwrap()
+    }
+
+    pub fn try_var_all(&self, keep_dims: bool, ddof: i32) -> Result<MLXArray, MLXError> {
+        self.try_var_all_on(keep_dims, ddof, &get_default_stream())
+    }
+
+    pub fn try_var_all_on(&self, keep_dims: bool, ddof: i32, stream: &MLXStream) -> Result<MLXArray, MLXError> {
+        let handle = guard(|| unsafe { mlx_var_all(self.as_ptr(), keep_dims, ddof, stream.as_ptr()) })?;
+        Ok(MLXArray::from_raw(handle))
+    }
+
+    /// Standard deviation over `axes`, see [`MLXArray::var`] for `ddof`.
+    pub fn std(&self, axes: &[i32], keep_dims: bool, ddof: i32) -> MLXArray {
+        self.try_std(axes, keep_dims, ddof).unwrap()
+    }
+
+    pub fn std_on(&self, axes: &[i32], keep_dims: bool, ddof: i32, stream: &MLXStream) -> MLXArray {
+        self.try_std_on(axes, keep_dims, ddof, stream).unwrap()
+    }
+
+    pub fn try_std(&self, axes: &[i32], keep_dims: bool, ddof: i32) -> Result<MLXArray, MLXError> {
+        self.try_std_on(axes, keep_dims, ddof, &get_default_stream())
+    }
+
+    pub fn try_std_on(&self, axes: &[i32], keep_dims: bool, ddof: i32, stream: &MLXStream) -> Result<MLXArray, MLXError> {
+        let handle = guard(|| unsafe {
+            mlx_std(
+                self.as_ptr(),
+                axes.as_ptr(),
+                axes.len(),
+                keep_dims,
+                ddof,
+                stream.as_ptr(),
+            )
+        })?;
+        Ok(MLXArray::from_raw(handle))
+    }
+
+    /// Standard deviation over all axes, see [`MLXArray::var`] for `ddof`.
+    pub fn std_all(&self, keep_dims: bool, ddof: i32) -> MLXArray {
+        self.try_std_all(keep_dims, ddof).unwrap()
+    }
+
+    pub fn std_all_on(&self, keep_dims: bool, ddof: i32, stream: &MLXStream) -> MLXArray {
+        self.try_std_all_on(keep_dims, ddof, stream).unwrap()
+    }
+
+    pub fn try_std_all(&self, keep_dims: bool, ddof: i32) -> Result<MLXArray, MLXError> {
+        self.try_std_all_on(keep_dims, ddof, &get_default_stream())
+    }
+
+    pub fn try_std_all_on(&self, keep_dims: bool, ddof: i32, stream: &MLXStream) -> Result<MLXArray, MLXError> {
+        let handle = guard(|| unsafe { mlx_std_all(self.as_ptr(), keep_dims, ddof, stream.as_ptr()) })?;
+        Ok(MLXArray::from_raw(handle))
+    }
+}
+
+pub fn argmax(x: &MLXArray, axis: i32, keep_dims: bool) -> MLXArray {
+    try_argmax(x, axis, keep_dims).unwrap()
+}
+
+pub fn argmax_on(x: &MLXArray, axis: i32, keep_dims: bool, stream: &MLXStream) -> MLXArray {
+    try_argmax_on(x, axis, keep_dims, stream).unwrap()
+}
+
+pub fn try_argmax(x: &MLXArray, axis: i32, keep_dims: bool) -> Result<MLXArray, MLXError> {
+    try_argmax_on(x, axis, keep_dims, &get_default_stream())
+}
+
+pub fn try_argmax_on(x: &MLXArray, axis: i32, keep_dims: bool, stream: &MLXStream) -> Result<MLXArray, MLXError> {
+    let handle = guard(|| unsafe {
+        mlx_argmax(x.as_ptr(), axis, keep_dims, stream.as_ptr())
+    })?;
+    Ok(MLXArray::from_raw(handle))
+}
+
+pub fn argmax_all(x: &MLXArray, keep_dims: bool) -> MLXArray {
+    try_argmax_all(x, keep_dims).unwrap()
+}
+
+pub fn argmax_all_on(x: &MLXArray, keep_dims: bool, stream: &MLXStream) -> MLXArray {
+    try_argmax_all_on(x, keep_dims, stream).unwrap()
+}
+
+pub fn try_argmax_all(x: &MLXArray, keep_dims: bool) -> Result<MLXArray, MLXError> {
+    try_argmax_all_on(x, keep_dims, &get_default_stream())
+}
+
+pub fn try_argmax_all_on(x: &MLXArray, keep_dims: bool, stream: &MLXStream) -> Result<MLXArray, MLXError> {
+    let handle = guard(|| unsafe { mlx_argmax_all(x.as_ptr(), keep_dims, stream.as_ptr()) })?;
+    Ok(MLXArray::from_raw(handle))
+}
+
+pub fn argmin(x: &MLXArray, axis: i32, keep_dims: bool) -> MLXArray {
+    try_argmin(x, axis, keep_dims).unwrap()
+}
+
+pub fn argmin_on(x: &MLXArray, axis: i32, keep_dims: bool, stream: &MLXStream) -> MLXArray {
+    try_argmin_on(x, axis, keep_dims, stream).unwrap()
+}
+
+pub fn try_argmin(x: &MLXArray, axis: i32, keep_dims: bool) -> Result<MLXArray, MLXError> {
+    try_argmin_on(x, axis, keep_dims, &get_default_stream())
+}
+
+pub fn try_argmin_on(x: &MLXArray, axis: i32, keep_dims: bool, stream: &MLXStream) -> Result<MLXArray, MLXError> {
+    let handle = guard(|| unsafe {
+        mlx_argmin(x.as_ptr(), axis, keep_dims, stream.as_ptr())
+    })?;
+    Ok(MLXArray::from_raw(handle))
+}
+
+pub fn argmin_all(x: &MLXArray, keep_dims: bool) -> MLXArray {
+    try_argmin_all(x, keep_dims).unwrap()
+}
+
+pub fn argmin_all_on(x: &MLXArray, keep_dims: bool, stream: &MLXStream) -> MLXArray {
+    try_argmin_all_on(x, keep_dims, stream).unwrap()
+}
+
+pub fn try_argmin_all(x: &MLXArray, keep_dims: bool) -> Result<MLXArray, MLXError> {
+    try_argmin_all_on(x, keep_dims, &get_default_stream())
+}
+
+pub fn try_argmin_all_on(x: &MLXArray, keep_dims: bool, stream: &MLXStream) -> Result<MLXArray, MLXError> {
+    let handle = guard(|| unsafe { mlx_argmin_all(x.as_ptr(), keep_dims, stream.as_ptr()) })?;
+    Ok(MLXArray::from_raw(handle))
 }
 
 pub fn addmm(
@@ -122,86 +631,495 @@ pub fn addmm(
     weight: &MLXArray,
     alpha: f32,
     beta: f32,
-    stream: Option<MLXStream>,
 ) -> MLXArray {
-    let stream = stream.unwrap_or_else(|| get_default_stream());
-    let handle = unsafe {
+    try_addmm(x, bias, weight, alpha, beta).unwrap()
+}
+
+pub fn addmm_on(
+    x: &MLXArray,
+    bias: &MLXArray,
+    weight: &MLXArray,
+    alpha: f32,
+    beta: f32,
+    stream: &MLXStream,
+) -> MLXArray {
+    try_addmm_on(x, bias, weight, alpha, beta, stream).unwrap()
+}
+
+pub fn try_addmm(
+    x: &MLXArray,
+    bias: &MLXArray,
+    weight: &MLXArray,
+    alpha: f32,
+    beta: f32,
+) -> Result<MLXArray, MLXError> {
+    try_addmm_on(x, bias, weight, alpha, beta, &get_default_stream())
+}
+
+pub fn try_addmm_on(
+    x: &MLXArray,
+    bias: &MLXArray,
+    weight: &MLXArray,
+    alpha: f32,
+    beta: f32,
+    stream: &MLXStream,
+) -> Result<MLXArray, MLXError> {
+    let weight_t = weight.try_t_on(stream)?;
+    let handle = guard(|| unsafe {
         mlx_addmm(
             bias.as_ptr(),
             x.as_ptr(),
-            weight.t(None).as_ptr(),
+            weight_t.as_ptr(),
             alpha,
             beta,
             stream.as_ptr(),
         )
-    };
-    MLXArray::from_raw(handle)
+    })?;
+    Ok(MLXArray::from_raw(handle))
+}
+
+/// Einstein summation, e.g. `einsum("bhqd,bhkd->bhqk", &[&q, &k])`.
+///
+/// mlx picks the contraction order, so long chains are not evaluated left to right.
+pub fn einsum(subscripts: &str, operands: &[&MLXArray]) -> MLXArray {
+    try_einsum(subscripts, operands).unwrap()
+}
+
+pub fn einsum_on(subscripts: &str, operands: &[&MLXArray], stream: &MLXStream) -> MLXArray {
+    try_einsum_on(subscripts, operands, stream).unwrap()
+}
+
+pub fn try_einsum(subscripts: &str, operands: &[&MLXArray]) -> Result<MLXArray, MLXError> {
+    try_einsum_on(subscripts, operands, &get_default_stream())
+}
+
+pub fn try_einsum_on(subscripts: &str, operands: &[&MLXArray], stream: &MLXStream) -> Result<MLXArray, MLXError> {
+    let subscripts = CString::new(subscripts)
+        .map_err(|_| MLXError::InvalidArgument("einsum subscripts contain a nul byte".into()))?;
+    let mut vec = VectorMLXArray::new();
+    for operand in operands {
+        vec.add((*operand).clone());
+    }
+    let handle = guard(|| unsafe { mlx_einsum(subscripts.as_ptr(), vec.as_ptr(), stream.as_ptr()) })?;
+    Ok(MLXArray::from_raw(handle))
+}
+
+/// Sums the products of `a` and `b` over `axes_a` and `axes_b`, paired up in order.
+pub fn tensordot(a: &MLXArray, b: &MLXArray, axes_a: &[i32], axes_b: &[i32]) -> MLXArray {
+    try_tensordot(a, b, axes_a, axes_b).unwrap()
+}
+
+pub fn tensordot_on(a: &MLXArray, b: &MLXArray, axes_a: &[i32], axes_b: &[i32], stream: &MLXStream) -> MLXArray {
+    try_tensordot_on(a, b, axes_a, axes_b, stream).unwrap()
+}
+
+pub fn try_tensordot(a: &MLXArray, b: &MLXArray, axes_a: &[i32], axes_b: &[i32]) -> Result<MLXArray, MLXError> {
+    try_tensordot_on(a, b, axes_a, axes_b, &get_default_stream())
+}
+
+pub fn try_tensordot_on(a: &MLXArray, b: &MLXArray, axes_a: &[i32], axes_b: &[i32], stream: &MLXStream) -> Result<MLXArray, MLXError> {
+    if axes_a.len() != axes_b.len() {
+        return Err(MLXError::InvalidArgument(format!(
+            "tensordot needs the same number of axes for both arrays, got {} and {}",
+            axes_a.len(),
+            axes_b.len()
+        )));
+    }
+    let handle = guard(|| unsafe {
+        mlx_tensordot(
+            a.as_ptr(),
+            b.as_ptr(),
+            axes_a.as_ptr(),
+            axes_a.len(),
+            axes_b.as_ptr(),
+            axes_b.len(),
+            stream.as_ptr(),
+        )
+    })?;
+    Ok(MLXArray::from_raw(handle))
+}
+
+/// [`tensordot`] over the last `n` axes of `a` and the first `n` axes of `b`.
+pub fn tensordot_n(a: &MLXArray, b: &MLXArray, n: i32) -> MLXArray {
+    try_tensordot_n(a, b, n).unwrap()
+}
+
+pub fn tensordot_n_on(a: &MLXArray, b: &MLXArray, n: i32, stream: &MLXStream) -> MLXArray {
+    try_tensordot_n_on(a, b, n, stream).unwrap()
+}
+
+pub fn try_tensordot_n(a: &MLXArray, b: &MLXArray, n: i32) -> Result<MLXArray, MLXError> {
+    try_tensordot_n_on(a, b, n, &get_default_stream())
+}
+
+pub fn try_tensordot_n_on(a: &MLXArray, b: &MLXArray, n: i32, stream: &MLXStream) -> Result<MLXArray, MLXError> {
+    let handle = guard(|| unsafe { mlx_tensordot_along_axis(a.as_ptr(), b.as_ptr(), n, stream.as_ptr()) })?;
+    Ok(MLXArray::from_raw(handle))
+}
+
+impl_binary_op!(outer, mlx_outer, "Outer product of the flattened arrays.");
+impl_binary_op!(inner, mlx_inner, "Inner product over the last axes, a dot product for vectors.");
+impl_binary_op!(kron, mlx_kron, "Kronecker product.");
+
+/// Matrix multiplication where `block_size x block_size` blocks can be masked out. `mask_out`
+/// masks blocks of the output, `mask_lhs` / `mask_rhs` blocks of the inputs, bool masks skip
+/// blocks and float masks scale them.
+pub fn block_masked_mm(
+    a: &MLXArray,
+    b: &MLXArray,
+    block_size: i32,
+    mask_out: Option<&MLXArray>,
+    mask_lhs: Option<&MLXArray>,
+    mask_rhs: Option<&MLXArray>,
+) -> MLXArray {
+    try_block_masked_mm(a, b, block_size, mask_out, mask_lhs, mask_rhs).unwrap()
+}
+
+pub fn block_masked_mm_on(
+    a: &MLXArray,
+    b: &MLXArray,
+    block_size: i32,
+    mask_out: Option<&MLXArray>,
+    mask_lhs: Option<&MLXArray>,
+    mask_rhs: Option<&MLXArray>,
+    stream: &MLXStream,
+) -> MLXArray {
+    try_block_masked_mm_on(a, b, block_size, mask_out, mask_lhs, mask_rhs, stream).unwrap()
+}
+
+pub fn try_block_masked_mm(
+    a: &MLXArray,
+    b: &MLXArray,
+    block_size: i32,
+    mask_out: Option<&MLXArray>,
+    mask_lhs: Option<&MLXArray>,
+    mask_rhs: Option<&MLXArray>,
+) -> Result<MLXArray, MLXError> {
+    try_block_masked_mm_on(a, b, block_size, mask_out, mask_lhs, mask_rhs, &get_default_stream())
+}
+
+pub fn try_block_masked_mm_on(
+    a: &MLXArray,
+    b: &MLXArray,
+    block_size: i32,
+    mask_out: Option<&MLXArray>,
+    mask_lhs: Option<&MLXArray>,
+    mask_rhs: Option<&MLXArray>,
+    stream: &MLXStream,
+) -> Result<MLXArray, MLXError> {
+    let ptr = |m: Option<&MLXArray>| m.map_or(std::ptr::null_mut(), |m| m.as_ptr());
+    let handle = guard(|| unsafe {
+        mlx_block_masked_mm(
+            a.as_ptr(),
+            b.as_ptr(),
+            block_size,
+            ptr(mask_out),
+            ptr(mask_lhs),
+            ptr(mask_rhs),
+            stream.as_ptr(),
+        )
+    })?;
+    Ok(MLXArray::from_raw(handle))
+}
+
+/// Batched matrix multiplication that picks the matrices of `a` and `b` with `lhs_indices` and
+/// `rhs_indices` (over the flattened batch axes), e.g. to apply the selected experts of a
+/// mixture of experts layer without gathering the weights first.
+pub fn gather_mm(
+    a: &MLXArray,
+    b: &MLXArray,
+    lhs_indices: Option<&MLXArray>,
+    rhs_indices: Option<&MLXArray>,
+) -> MLXArray {
+    try_gather_mm(a, b, lhs_indices, rhs_indices).unwrap()
+}
+
+pub fn gather_mm_on(
+    a: &MLXArray,
+    b: &MLXArray,
+    lhs_indices: Option<&MLXArray>,
+    rhs_indices: Option<&MLXArray>,
+    stream: &MLXStream,
+) -> MLXArray {
+    try_gather_mm_on(a, b, lhs_indices, rhs_indices, stream).unwrap()
+}
+
+pub fn try_gather_mm(
+    a: &MLXArray,
+    b: &MLXArray,
+    lhs_indices: Option<&MLXArray>,
+    rhs_indices: Option<&MLXArray>,
+) -> Result<MLXArray, MLXError> {
+    try_gather_mm_on(a, b, lhs_indices, rhs_indices, &get_default_stream())
+}
+
+pub fn try_gather_mm_on(
+    a: &MLXArray,
+    b: &MLXArray,
+    lhs_indices: Option<&MLXArray>,
+    rhs_indices: Option<&MLXArray>,
+    stream: &MLXStream,
+) -> Result<MLXArray, MLXError> {
+    let ptr = |m: Option<&MLXArray>| m.map_or(std::ptr::null_mut(), |m| m.as_ptr());
+    let handle = guard(|| unsafe {
+        mlx_gather_mm(
+            a.as_ptr(),
+            b.as_ptr(),
+            ptr(lhs_indices),
+            ptr(rhs_indices),
+            stream.as_ptr(),
+        )
+    })?;
+    Ok(MLXArray::from_raw(handle))
+}
+
+pub fn soft_max(x: &MLXArray, axes: &[i32]) -> MLXArray {
+    try_soft_max(x, axes).unwrap()
+}
+
+pub fn soft_max_on(x: &MLXArray, axes: &[i32], stream: &MLXStream) -> MLXArray {
+    try_soft_max_on(x, axes, stream).unwrap()
 }
 
-pub fn soft_max(x: &MLXArray, axes: &[i32], stream: Option<MLXStream>) -> MLXArray {
-    let stream = stream.unwrap_or_else(|| get_default_stream());
-    let handle = unsafe {
+pub fn try_soft_max(x: &MLXArray, axes: &[i32]) -> Result<MLXArray, MLXError> {
+    try_soft_max_on(x, axes, &get_default_stream())
+}
+
+pub fn try_soft_max_on(x: &MLXArray, axes: &[i32], stream: &MLXStream) -> Result<MLXArray, MLXError> {
+    let handle = guard(|| unsafe {
         mlx_softmax(
             x.as_ptr(),
             axes.as_ptr() as *const ::std::os::raw::c_int,
             axes.len(),
             stream.as_ptr()
         )
-    };
-    MLXArray::from_raw(handle)
+    })?;
+    Ok(MLXArray::from_raw(handle))
+}
+
+pub fn arg_sort(x: &MLXArray, axes: i32) -> MLXArray {
+    try_arg_sort(x, axes).unwrap()
+}
+
+pub fn arg_sort_on(x: &MLXArray, axes: i32, stream: &MLXStream) -> MLXArray {
+    try_arg_sort_on(x, axes, stream).unwrap()
 }
 
-pub fn arg_sort(x: &MLXArray, axes: i32, stream: Option<MLXStream>) -> MLXArray {
-    let stream = stream.unwrap_or_else(|| get_default_stream());
-    let handle = unsafe {
+pub fn try_arg_sort(x: &MLXArray, axes: i32) -> Result<MLXArray, MLXError> {
+    try_arg_sort_on(x, axes, &get_default_stream())
+}
+
+pub fn try_arg_sort_on(x: &MLXArray, axes: i32, stream: &MLXStream) -> Result<MLXArray, MLXError> {
+    let handle = guard(|| unsafe {
         mlx_argsort(
             x.as_ptr(),
             axes,
             stream.as_ptr()
         )
-    };
-    MLXArray::from_raw(handle)
+    })?;
+    Ok(MLXArray::from_raw(handle))
 }
 
-pub fn cum_sum(x: &MLXArray, axes: i32, reverse: bool, inclusive: bool, stream: Option<MLXStream>) -> MLXArray {
-    let stream = stream.unwrap_or_else(|| get_default_stream());
-    let handle = unsafe {
-        mlx_cumsum(
-            x.as_ptr(),
-            axes,
-            reverse,
-            inclusive,
-            stream.as_ptr()
-        )
+/// Sorts along `axis`.
+pub fn sort(x: &MLXArray, axis: i32) -> MLXArray {
+    try_sort(x, axis).unwrap()
+}
+
+pub fn sort_on(x: &MLXArray, axis: i32, stream: &MLXStream) -> MLXArray {
+    try_sort_on(x, axis, stream).unwrap()
+}
+
+pub fn try_sort(x: &MLXArray, axis: i32) -> Result<MLXArray, MLXError> {
+    try_sort_on(x, axis, &get_default_stream())
+}
+
+pub fn try_sort_on(x: &MLXArray, axis: i32, stream: &MLXStream) -> Result<MLXArray, MLXError> {
+    let handle = guard(|| unsafe { mlx_sort(x.as_ptr(), axis, stream.as_ptr()) })?;
+    Ok(MLXArray::from_raw(handle))
+}
+
+/// Moves the element that would be at position `kth` of a sorted `axis` into that position,
+/// with smaller or equal elements before it and larger or equal ones after it, in no particular
+/// order.
+pub fn partition(x: &MLXArray, kth: i32, axis: i32) -> MLXArray {
+    try_partition(x, kth, axis).unwrap()
+}
+
+pub fn partition_on(x: &MLXArray, kth: i32, axis: i32, stream: &MLXStream) -> MLXArray {
+    try_partition_on(x, kth, axis, stream).unwrap()
+}
+
+pub fn try_partition(x: &MLXArray, kth: i32, axis: i32) -> Result<MLXArray, MLXError> {
+    try_partition_on(x, kth, axis, &get_default_stream())
+}
+
+pub fn try_partition_on(x: &MLXArray, kth: i32, axis: i32, stream: &MLXStream) -> Result<MLXArray, MLXError> {
+    let handle = guard(|| unsafe { mlx_partition(x.as_ptr(), kth, axis, stream.as_ptr()) })?;
+    Ok(MLXArray::from_raw(handle))
+}
+
+/// The indices that would partition `x`, see [`partition`].
+pub fn argpartition(x: &MLXArray, kth: i32, axis: i32) -> MLXArray {
+    try_argpartition(x, kth, axis).unwrap()
+}
+
+pub fn argpartition_on(x: &MLXArray, kth: i32, axis: i32, stream: &MLXStream) -> MLXArray {
+    try_argpartition_on(x, kth, axis, stream).unwrap()
+}
+
+pub fn try_argpartition(x: &MLXArray, kth: i32, axis: i32) -> Result<MLXArray, MLXError> {
+    try_argpartition_on(x, kth, axis, &get_default_stream())
+}
+
+pub fn try_argpartition_on(x: &MLXArray, kth: i32, axis: i32, stream: &MLXStream) -> Result<MLXArray, MLXError> {
+    let handle = guard(|| unsafe { mlx_argpartition(x.as_ptr(), kth, axis, stream.as_ptr()) })?;
+    Ok(MLXArray::from_raw(handle))
+}
+
+/// The `k` largest elements along `axis` and their indices, in no particular order.
+///
+/// This partitions instead of sorting the whole axis, so it stays cheap for large vocabularies.
+pub fn topk(x: &MLXArray, k: i32, axis: i32) -> (MLXArray, MLXArray) {
+    try_topk(x, k, axis).unwrap()
+}
+
+pub fn topk_on(x: &MLXArray, k: i32, axis: i32, stream: &MLXStream) -> (MLXArray, MLXArray) {
+    try_topk_on(x, k, axis, stream).unwrap()
+}
+
+pub fn try_topk(x: &MLXArray, k: i32, axis: i32) -> Result<(MLXArray, MLXArray), MLXError> {
+    try_topk_on(x, k, axis, &get_default_stream())
+}
+
+pub fn try_topk_on(x: &MLXArray, k: i32, axis: i32, stream: &MLXStream) -> Result<(MLXArray, MLXArray), MLXError> {
+    let ndim = x.ndim() as i32;
+    if axis < -ndim || axis >= ndim {
+        return Err(MLXError::InvalidArgument(format!(
+            "axis {} is out of bounds for an array with {} dimensions",
+            axis, ndim
+        )));
+    }
+    let ax = if axis < 0 { axis + ndim } else { axis } as usize;
+    let dim = x.shape()[ax];
+    if k < 1 || k > dim {
+        return Err(MLXError::InvalidArgument(format!(
+            "k must be in [1, {}], got {}",
+            dim, k
+        )));
+    }
+    // the last k positions of an ascending partition around `dim - k`, negating the input
+    // instead would wrap unsigned values and `i32::MIN`
+    let indices = try_argpartition_on(x, dim - k, axis, stream)?;
+    let indices = slice_axis(&indices, ax, dim - k, dim, 1, stream)?;
+    let values = try_take_along_axis_on(x, &indices, axis, stream)?;
+    Ok((values, indices))
+}
+
+macro_rules! impl_scan_op {
+    ($name:ident, $mlx_func:ident, $doc:literal) => {
+        paste::paste! {
+            #[doc = $doc]
+            ///
+            /// Scans along `axis`, from the end if `reverse`. With `inclusive == false` every
+            /// position only sees the elements before it.
+            pub fn $name(x: &MLXArray, axis: i32, reverse: bool, inclusive: bool) -> MLXArray {
+                [<try_ $name>](x, axis, reverse, inclusive).unwrap()
+            }
+
+            pub fn [<$name _on>](x: &MLXArray, axis: i32, reverse: bool, inclusive: bool, stream: &MLXStream) -> MLXArray {
+                [<try_ $name _on>](x, axis, reverse, inclusive, stream).unwrap()
+            }
+
+            pub fn [<try_ $name>](x: &MLXArray, axis: i32, reverse: bool, inclusive: bool) -> Result<MLXArray, MLXError> {
+                [<try_ $name _on>](x, axis, reverse, inclusive, &get_default_stream())
+            }
+
+            pub fn [<try_ $name _on>](x: &MLXArray, axis: i32, reverse: bool, inclusive: bool, stream: &MLXStream) -> Result<MLXArray, MLXError> {
+                let handle = guard(|| unsafe {
+                    $mlx_func(
+                        x.as_ptr(),
+                        axis,
+                        reverse,
+                        inclusive,
+                        stream.as_ptr()
+                    )
+                })?;
+                Ok(MLXArray::from_raw(handle))
+            }
+        }
     };
-    MLXArray::from_raw(handle)
 }
 
+impl_scan_op!(cum_sum, mlx_cumsum, "Cumulative sum.");
+impl_scan_op!(cum_prod, mlx_cumprod, "Cumulative product.");
+impl_scan_op!(cum_max, mlx_cummax, "Running maximum.");
+impl_scan_op!(cum_min, mlx_cummin, "Running minimum.");
+impl_scan_op!(log_cum_sum_exp, mlx_logcumsumexp, "Cumulative `log(sum(exp(x)))`, computed without overflowing for large inputs.");
+
 pub fn where_condition(
-    condition: &MLXArray, true_sub_clause: &MLXArray, false_sub_clause: &MLXArray, stream: Option<MLXStream>) -> MLXArray {
-    let stream = stream.unwrap_or_else(|| get_default_stream());
-    let handle = unsafe {
+    condition: &MLXArray,
+    true_sub_clause: &MLXArray,
+    false_sub_clause: &MLXArray,
+) -> MLXArray {
+    try_where_condition(condition, true_sub_clause, false_sub_clause).unwrap()
+}
+
+pub fn where_condition_on(
+    condition: &MLXArray,
+    true_sub_clause: &MLXArray,
+    false_sub_clause: &MLXArray,
+    stream: &MLXStream,
+) -> MLXArray {
+    try_where_condition_on(condition, true_sub_clause, false_sub_clause, stream).unwrap()
+}
+
+pub fn try_where_condition(
+    condition: &MLXArray,
+    true_sub_clause: &MLXArray,
+    false_sub_clause: &MLXArray,
+) -> Result<MLXArray, MLXError> {
+    try_where_condition_on(condition, true_sub_clause, false_sub_clause, &get_default_stream())
+}
+
+pub fn try_where_condition_on(
+    condition: &MLXArray,
+    true_sub_clause: &MLXArray,
+    false_sub_clause: &MLXArray,
+    stream: &MLXStream,
+) -> Result<MLXArray, MLXError> {
+    let handle = guard(|| unsafe {
         mlx_where(
             condition.as_ptr(),
             true_sub_clause.as_ptr(),
             false_sub_clause.as_ptr(),
             stream.as_ptr()
         )
-    };
-    MLXArray::from_raw(handle)
+    })?;
+    Ok(MLXArray::from_raw(handle))
+}
+
+pub fn squeeze(x: &MLXArray, axes: &[i32]) -> MLXArray {
+    try_squeeze(x, axes).unwrap()
 }
 
-pub fn squeeze(x: &MLXArray, axes: &[i32], stream: Option<MLXStream>) -> MLXArray {
-    let stream = stream.unwrap_or_else(|| get_default_stream());
-    let handle = unsafe {
+pub fn squeeze_on(x: &MLXArray, axes: &[i32], stream: &MLXStream) -> MLXArray {
+    try_squeeze_on(x, axes, stream).unwrap()
+}
+
+pub fn try_squeeze(x: &MLXArray, axes: &[i32]) -> Result<MLXArray, MLXError> {
+    try_squeeze_on(x, axes, &get_default_stream())
+}
+
+pub fn try_squeeze_on(x: &MLXArray, axes: &[i32], stream: &MLXStream) -> Result<MLXArray, MLXError> {
+    let handle = guard(|| unsafe {
         mlx_squeeze(
             x.as_ptr(),
             axes.as_ptr() as *const ::std::os::raw::c_int,
             axes.len(),
             stream.as_ptr()
         )
-    };
-    MLXArray::from_raw(handle)
+    })?;
+    Ok(MLXArray::from_raw(handle))
 }
 
 
@@ -210,9 +1128,33 @@ pub fn squeeze(x: &MLXArray, axes: &[i32], stream: Option<MLXStream>) -> MLXArra
 
 macro_rules! impl_unary_op {
     ($func_name:ident, $mlx_func:ident) => {
-        pub fn $func_name(v: MLXArray) -> MLXArray {
-            let handle = unsafe { $mlx_func(v.as_ptr(), get_default_stream().as_ptr()) };
-            MLXArray::from_raw(handle)
+        paste::paste! {
+            pub fn $func_name(v: &MLXArray) -> MLXArray {
+                [<try_ $func_name>](v).unwrap()
+            }
+
+            pub fn [<try_ $func_name>](v: &MLXArray) -> Result<MLXArray, MLXError> {
+                v.[<try_ $func_name>]()
+            }
+
+            impl MLXArray {
+                pub fn $func_name(&self) -> MLXArray {
+                    self.[<try_ $func_name>]().unwrap()
+                }
+
+                pub fn [<$func_name _on>](&self, stream: &MLXStream) -> MLXArray {
+                    self.[<try_ $func_name _on>](stream).unwrap()
+                }
+
+                pub fn [<try_ $func_name>](&self) -> Result<MLXArray, MLXError> {
+                    self.[<try_ $func_name _on>](&get_default_stream())
+                }
+
+                pub fn [<try_ $func_name _on>](&self, stream: &MLXStream) -> Result<MLXArray, MLXError> {
+                    let handle = guard(|| unsafe { $mlx_func(self.as_ptr(), stream.as_ptr()) })?;
+                    Ok(MLXArray::from_raw(handle))
+                }
+            }
         }
     };
 }
@@ -234,16 +1176,53 @@ impl_unary_op!(erf, mlx_erf);
 impl_unary_op!(erfinv, mlx_erfinv);
 impl_unary_op!(exp, mlx_exp);
 impl_unary_op!(floor, mlx_floor);
+impl_unary_op!(ceil, mlx_ceil);
+impl_unary_op!(abs, mlx_abs);
+impl_unary_op!(negative, mlx_negative);
+impl_unary_op!(reciprocal, mlx_reciprocal);
+impl_unary_op!(rsqrt, mlx_rsqrt);
+impl_unary_op!(log1p, mlx_log1p);
+impl_unary_op!(log2, mlx_log2);
+impl_unary_op!(expm1, mlx_expm1);
+impl_unary_op!(arcsin, mlx_arcsin);
+impl_unary_op!(arccos, mlx_arccos);
+impl_unary_op!(arctan, mlx_arctan);
+impl_unary_op!(isnan, mlx_isnan);
+impl_unary_op!(isinf, mlx_isinf);
+impl_unary_op!(logical_not, mlx_logical_not);
+impl_unary_op!(bitwise_invert, mlx_bitwise_invert);
+impl_unary_op!(stop_gradient, mlx_stop_gradient);
 // impl_unary_op!(t, mlx_transpose_all);
 
+/// The other operand of the operator traits (`+`, `*`, `&`, `<<`, ...).
+///
+/// Arrays are used as they are. Rust scalars are weakly typed like Python scalars in MLX: they
+/// take the dtype of the array they are combined with unless that would change their kind (see
+/// [`weak_scalar_dtype`]), so `x_f16 * 0.5` stays `float16` and `x_u8 + 1` stays `uint8`, while
+/// `x_i32 * 0.5` is `float32`. Like MLX does for Python ints, an integer scalar that does not fit
+/// the integer dtype it would take is an error instead of wrapping, so `x_u8 + 1000` panics.
+///
+/// An `f64` scalar is narrowed to `float32` first, like `MLXArray::from(f64)`. Since both `f32` and
+/// `f64` work on the left, an untyped float literal there needs a suffix when a method is called on
+/// the result right away, e.g. `(0.5f32 * &x).dtype()`.
+pub trait Operand {
+    fn into_operand(self, other: &MLXArray) -> Result<MLXArray, MLXError>;
+}
+
+impl Operand for MLXArray {
+    fn into_operand(self, _other: &MLXArray) -> Result<MLXArray, MLXError> {
+        Ok(self)
+    }
+}
+
 macro_rules! impl_lhs_binary_trait {
-    ($type:ty, $trait:ident, $op:ident) => {
+    ($type:ty, $trait:ident, $op:ident, $method:ident) => {
         impl $trait<MLXArray> for $type {
             type Output = MLXArray;
 
             fn $op(self, rhs: MLXArray) -> Self::Output {
-                let l: MLXArray = self.into();
-                l.$op(&rhs)
+                let l = self.into_operand(&rhs).unwrap();
+                l.$method(&rhs).unwrap()
             }
         }
 
@@ -251,8 +1230,8 @@ macro_rules! impl_lhs_binary_trait {
             type Output = MLXArray;
 
             fn $op(self, rhs: &MLXArray) -> Self::Output {
-                let l: MLXArray = self.into();
-                l.$op(rhs)
+                let l = self.into_operand(rhs).unwrap();
+                l.$method(rhs).unwrap()
             }
         }
     };
@@ -260,13 +1239,12 @@ macro_rules! impl_lhs_binary_trait {
 
 macro_rules! impl_binary_trait {
     ($trait:ident, $op:ident, $method: ident) => {
-        impl<R: Into<MLXArray>> $trait<R> for MLXArray {
+        impl<R: Operand> $trait<R> for MLXArray {
             type Output = MLXArray;
 
             fn $op(self, rhs: R) -> Self::Output {
-                let stream = get_default_stream();
-                let r: MLXArray = rhs.into();
-                self.$method(&r, stream)
+                let r = rhs.into_operand(&self).unwrap();
+                self.$method(&r).unwrap()
             }
         }
 
@@ -274,18 +1252,16 @@ macro_rules! impl_binary_trait {
             type Output = MLXArray;
 
             fn $op(self, rhs: &MLXArray) -> Self::Output {
-                let stream = get_default_stream();
-                self.$method(rhs, stream)
+                self.$method(rhs).unwrap()
             }
         }
 
-        impl<R: Into<MLXArray>> $trait<R> for &MLXArray {
+        impl<R: Operand> $trait<R> for &MLXArray {
             type Output = MLXArray;
 
             fn $op(self, rhs: R) -> Self::Output {
-                let stream = get_default_stream();
-                let r: MLXArray = rhs.into();
-                self.$method(&r, stream)
+                let r = rhs.into_operand(self).unwrap();
+                self.$method(&r).unwrap()
             }
         }
 
@@ -293,37 +1269,138 @@ macro_rules! impl_binary_trait {
             type Output = MLXArray;
 
             fn $op(self, rhs: &MLXArray) -> Self::Output {
-                let stream = get_default_stream();
-                self.$method(rhs, stream)
+                self.$method(rhs).unwrap()
             }
         }
-        // impl_lhs_binary_trait!(u8, $trait, $op);
-        // impl_lhs_binary_trait!(u16, $trait, $op);
-        // impl_lhs_binary_trait!(u32, $trait, $op);
-        // impl_lhs_binary_trait!(u64, $trait, $op);
-        impl_lhs_binary_trait!(i8, $trait, $op);
-        impl_lhs_binary_trait!(i16, $trait, $op);
-        impl_lhs_binary_trait!(i32, $trait, $op);
-        // impl_lhs_binary_trait!(i64, $trait, $op);
-        impl_lhs_binary_trait!(f32, $trait, $op);
-        // impl_lhs_binary_trait!(f16, $trait, $op);
-        // impl_lhs_binary_trait!(bf16, $trait, $op);
+        impl_lhs_binary_trait!(bool, $trait, $op, $method);
+        impl_lhs_binary_trait!(u8, $trait, $op, $method);
+        impl_lhs_binary_trait!(u16, $trait, $op, $method);
+        impl_lhs_binary_trait!(u32, $trait, $op, $method);
+        impl_lhs_binary_trait!(u64, $trait, $op, $method);
+        impl_lhs_binary_trait!(i8, $trait, $op, $method);
+        impl_lhs_binary_trait!(i16, $trait, $op, $method);
+        impl_lhs_binary_trait!(i32, $trait, $op, $method);
+        impl_lhs_binary_trait!(i64, $trait, $op, $method);
+        impl_lhs_binary_trait!(f16, $trait, $op, $method);
+        impl_lhs_binary_trait!(bf16, $trait, $op, $method);
+        impl_lhs_binary_trait!(f32, $trait, $op, $method);
+        impl_lhs_binary_trait!(f64, $trait, $op, $method);
+        impl_lhs_binary_trait!(Complex32, $trait, $op, $method);
+    };
+}
+
+fn weak_scalar(scalar: MLXArray, other: &MLXArray) -> MLXArray {
+    let dtype = weak_scalar_dtype(scalar.dtype(), other.dtype());
+    if dtype == scalar.dtype() {
+        scalar
+    } else {
+        scalar.as_dtype(dtype)
+    }
+}
+
+macro_rules! impl_scalar_operand {
+    ($($type:ty),*) => {
+        $(
+            impl Operand for $type {
+                fn into_operand(self, other: &MLXArray) -> Result<MLXArray, MLXError> {
+                    Ok(weak_scalar(MLXArray::from(self), other))
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_integer_operand {
+    ($($type:ty),*) => {
+        $(
+            impl Operand for $type {
+                fn into_operand(self, other: &MLXArray) -> Result<MLXArray, MLXError> {
+                    let scalar = MLXArray::from(self);
+                    let dtype = weak_scalar_dtype(scalar.dtype(), other.dtype());
+                    if let Some((min, max)) = dtype.integer_bounds() {
+                        if (self as i128) < min || (self as i128) > max {
+                            return Err(MLXError::InvalidArgument(format!(
+                                "scalar {} is out of range for {}",
+                                self,
+                                dtype.name()
+                            )));
+                        }
+                    }
+                    Ok(weak_scalar(scalar, other))
+                }
+            }
+        )*
     };
 }
 
-impl_binary_trait!(Add, add, add_with_stream);
-impl_binary_trait!(Sub, sub, sub_with_stream);
-impl_binary_trait!(Mul, mul, mul_with_stream);
-impl_binary_trait!(Div, div, div_with_stream);
+impl_scalar_operand!(bool, f16, bf16, f32, f64, Complex32);
+impl_integer_operand!(u8, u16, u32, u64, i8, i16, i32, i64);
+
+impl_binary_trait!(Add, add, try_add);
+impl_binary_trait!(Sub, sub, try_sub);
+impl_binary_trait!(Mul, mul, try_mul);
+impl_binary_trait!(Div, div, try_div);
+impl_binary_trait!(Rem, rem, try_remainder);
+impl_binary_trait!(BitAnd, bitand, try_bitwise_and);
+impl_binary_trait!(BitOr, bitor, try_bitwise_or);
+impl_binary_trait!(BitXor, bitxor, try_bitwise_xor);
+impl_binary_trait!(Shl, shl, try_left_shift);
+impl_binary_trait!(Shr, shr, try_right_shift);
+
+impl Neg for MLXArray {
+    type Output = MLXArray;
+
+    fn neg(self) -> Self::Output {
+        self.negative()
+    }
+}
+
+impl Neg for &MLXArray {
+    type Output = MLXArray;
+
+    fn neg(self) -> Self::Output {
+        self.negative()
+    }
+}
+
+/// `!` is a logical not on bool arrays and a bitwise invert on integer arrays, like it is for
+/// rust scalars.
+impl Not for &MLXArray {
+    type Output = MLXArray;
+
+    fn not(self) -> Self::Output {
+        if self.dtype() == Dtype::Bool {
+            self.logical_not()
+        } else {
+            self.bitwise_invert()
+        }
+    }
+}
+
+impl Not for MLXArray {
+    type Output = MLXArray;
+
+    fn not(self) -> Self::Output {
+        !&self
+    }
+}
 
 #[cfg(test)]
 mod tests {
+    use half::{bf16, f16};
+
     use crate::array::MLXArray;
+    use crate::r#type::Dtype;
+    use crate::array_op::{
+        argmin, argpartition, block_masked_mm, cum_max, cum_min, cum_prod, cum_sum, einsum,
+        gather_mm, log_cum_sum_exp, partition, Operand, sort, tensordot, tensordot_n, topk, try_tensordot,
+        try_topk,
+    };
 
     #[test]
     fn test_add() {
         let array1: MLXArray = 12.0.into();
-        let result = 12.0 + array1.clone() + array1 + 12.0;
+        let result: MLXArray = 12.0 + array1.clone() + array1 + 12.0;
         println!("{}", result.size());
         assert_eq!(1, result.size())
     }
@@ -331,7 +1408,7 @@ mod tests {
     #[test]
     fn test_sub() {
         let array1: MLXArray = 12.0.into();
-        let result = 12.0 - array1.clone() - array1 - 12.0;
+        let result: MLXArray = 12.0 - array1.clone() - array1 - 12.0;
         println!("{}", result.size());
         assert_eq!(1, result.size())
     }
@@ -339,15 +1416,243 @@ mod tests {
     #[test]
     fn test_mul() {
         let array1: MLXArray = 12.0.into();
-        let result = 12.0 * array1.clone() * array1 * 12.0;
+        let result: MLXArray = 12.0 * array1.clone() * array1 * 12.0;
         println!("{}", result.size());
         assert_eq!(1, result.size())
     }
 
+    #[test]
+    fn test_reductions() {
+        let x = MLXArray::array(&[1.0f32, 2.0, 3.0, 4.0], &[2, 2]);
+        assert_eq!(&[3.0, 7.0], x.sum(&[1], false).to_slice::<f32>().unwrap());
+        assert_eq!(&[2, 1], x.sum(&[1], true).shape());
+        assert_eq!(24.0, x.prod_all(false).to_scalar::<f32>().unwrap());
+        assert_eq!(&[3.0, 4.0], x.max(&[0], false).to_slice::<f32>().unwrap());
+        assert_eq!(1.0, x.min_all(false).to_scalar::<f32>().unwrap());
+        assert_eq!(&[0, 0], argmin(&x, 1, false).to_slice::<u32>().unwrap());
+        let y = MLXArray::array(&[3.0f32, 1.0, 2.0, 4.0], &[2, 2]);
+        assert_eq!(&[1, 0], argmin(&y, 0, false).to_slice::<u32>().unwrap());
+        assert_eq!(1.25, x.var_all(false, 0).to_scalar::<f32>().unwrap());
+        assert_eq!(0.5, x.var(&[1], false, 1).to_slice::<f32>().unwrap()[0]);
+        assert!((x.std_all(false, 0).to_scalar::<f32>().unwrap() - 1.25f32.sqrt()).abs() < 1e-6);
+        assert!(!x.greater(&2.0.into()).all_all(false).to_scalar::<bool>().unwrap());
+        assert!(x.greater(&2.0.into()).any_all(false).to_scalar::<bool>().unwrap());
+    }
+
+    #[test]
+    fn test_logsumexp_is_stable() {
+        let x = MLXArray::array(&[1000.0f32, 1000.0], &[2]);
+        let r = x.logsumexp_all(false).to_scalar::<f32>().unwrap();
+        assert!((r - (1000.0 + 2.0f32.ln())).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_comparison() {
+        let x = MLXArray::array(&[1i32, 2, 3], &[3]);
+        let y = MLXArray::array(&[3i32, 2, 1], &[3]);
+        assert_eq!(&[false, true, false], x.equal(&y).to_slice::<bool>().unwrap());
+        assert_eq!(&[true, false, true], x.not_equal(&y).to_slice::<bool>().unwrap());
+        assert_eq!(&[true, true, false], x.less_equal(&y).to_slice::<bool>().unwrap());
+        assert_eq!(&[false, true, true], x.greater_equal(&y).to_slice::<bool>().unwrap());
+    }
+
+    #[test]
+    fn test_logical() {
+        let a = MLXArray::array(&[true, true, false], &[3]);
+        let b = MLXArray::array(&[true, false, false], &[3]);
+        assert_eq!(&[true, false, false], a.logical_and(&b).to_slice::<bool>().unwrap());
+        assert_eq!(&[true, true, false], a.logical_or(&b).to_slice::<bool>().unwrap());
+        assert_eq!(&[false, false, true], (!&a).to_slice::<bool>().unwrap());
+    }
+
+    #[test]
+    fn test_bitwise_ops() {
+        let x = MLXArray::array(&[0b1100i32, 0b1010], &[2]);
+        let y = MLXArray::array(&[0b1010i32, 0b0110], &[2]);
+        assert_eq!(&[0b1000, 0b0010], (&x & &y).to_slice::<i32>().unwrap());
+        assert_eq!(&[0b1110, 0b1110], (&x | &y).to_slice::<i32>().unwrap());
+        assert_eq!(&[0b0110, 0b1100], (&x ^ &y).to_slice::<i32>().unwrap());
+        assert_eq!(&[0b11000, 0b10100], (&x << 1).to_slice::<i32>().unwrap());
+        assert_eq!(&[0b0110, 0b0101], (&x >> 1).to_slice::<i32>().unwrap());
+        assert_eq!(&[!0b1100, !0b1010], (!x).to_slice::<i32>().unwrap());
+    }
+
+    #[test]
+    fn test_remainder_and_floor_divide() {
+        let x = MLXArray::array(&[7i32, -7], &[2]);
+        assert_eq!(&[1, 2], (&x % 3).to_slice::<i32>().unwrap());
+        let three: MLXArray = 3.into();
+        assert_eq!(&[2, -3], x.floor_divide(&three).to_slice::<i32>().unwrap());
+        assert_eq!(&[-7, 7], (-x).to_slice::<i32>().unwrap());
+    }
+
+    #[test]
+    fn test_unary_methods_chain() {
+        let x = MLXArray::array(&[-1.0f32, 0.0, 1.0], &[3]);
+        assert_eq!(&[3.0, 1.0, 3.0], x.abs().exp().log().exp().ceil().to_slice::<f32>().unwrap());
+        assert_eq!(&[1.0, -0.0, -1.0], (-&x).to_slice::<f32>().unwrap());
+        let r = x.arctan2(&MLXArray::array(&[1.0f32, 1.0, 1.0], &[3]));
+        assert!((r.to_slice::<f32>().unwrap()[2] - std::f32::consts::FRAC_PI_4).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_round_and_clip() {
+        let x = MLXArray::array(&[-1.26f32, 0.5, 2.54], &[3]);
+        assert_eq!(&[-1.3, 0.5, 2.5], x.round(1).to_slice::<f32>().unwrap());
+        assert_eq!(&[-1.0, 0.0, 3.0], x.round(0).to_slice::<f32>().unwrap());
+        let lo: MLXArray = 0.0.into();
+        let hi: MLXArray = 1.0.into();
+        assert_eq!(&[0.0, 0.5, 1.0], x.clip(Some(&lo), Some(&hi)).to_slice::<f32>().unwrap());
+        assert_eq!(&[-1.26, 0.5, 1.0], x.clip(None, Some(&hi)).to_slice::<f32>().unwrap());
+        assert!(x.try_clip(None, None).is_err());
+    }
+
+    #[test]
+    fn test_nan_and_inf() {
+        let x = MLXArray::array(&[f32::NAN, f32::INFINITY, f32::NEG_INFINITY, 1.0], &[4]);
+        assert_eq!(&[true, false, false, false], x.isnan().to_slice::<bool>().unwrap());
+        assert_eq!(&[false, true, true, false], x.isinf().to_slice::<bool>().unwrap());
+        let y = x.nan_to_num(0.0, Some(10.0), None);
+        assert_eq!(&[0.0, 10.0, f32::MIN, 1.0], y.to_slice::<f32>().unwrap());
+    }
+
+    #[test]
+    fn test_sort_and_partition() {
+        let x = MLXArray::array(&[3i32, 1, 4, 1, 5, 9, 2, 6], &[2, 4]);
+        assert_eq!(&[1, 1, 3, 4, 2, 5, 6, 9], sort(&x, -1).to_slice::<i32>().unwrap());
+
+        let p = partition(&x, 1, 1);
+        let p = p.to_slice::<i32>().unwrap();
+        assert_eq!(1, p[1]);
+        assert!(p[0] <= 1 && p[2] >= 1 && p[3] >= 1);
+
+        let idx = argpartition(&x, 2, 1);
+        assert_eq!(&[2, 4], idx.shape());
+        assert_eq!(3, idx.to_slice::<u32>().unwrap()[6]);
+    }
+
+    #[test]
+    fn test_topk() {
+        let x = MLXArray::array(&[0.1f32, 0.5, 0.2, 0.9, 0.3], &[5]);
+        let (values, indices) = topk(&x, 2, 0);
+        let mut values = values.to_slice::<f32>().unwrap().to_vec();
+        values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert_eq!(vec![0.5, 0.9], values);
+        let mut indices = indices.to_slice::<u32>().unwrap().to_vec();
+        indices.sort();
+        assert_eq!(vec![1, 3], indices);
+        assert!(try_topk(&x, 6, 0).is_err());
+
+        let x = MLXArray::array(&[3u8, 250, 7, 0], &[4]);
+        let mut values = topk(&x, 2, 0).0.to_slice::<u8>().unwrap().to_vec();
+        values.sort();
+        assert_eq!(vec![7, 250], values);
+
+        let x = MLXArray::array(&[i32::MIN, 1, -5], &[3]);
+        let mut values = topk(&x, 2, 0).0.to_slice::<i32>().unwrap().to_vec();
+        values.sort();
+        assert_eq!(vec![-5, 1], values);
+    }
+
+    #[test]
+    fn test_einsum() {
+        let a = MLXArray::array(&[1.0f32, 2.0, 3.0, 4.0], &[2, 2]);
+        let b = MLXArray::array(&[5.0f32, 6.0, 7.0, 8.0], &[2, 2]);
+        let c = einsum("ij,jk->ik", &[&a, &b]);
+        assert_eq!(&[19.0, 22.0, 43.0, 50.0], c.to_slice::<f32>().unwrap());
+        assert_eq!(5.0, einsum("ii", &[&a]).to_scalar::<f32>().unwrap());
+        assert_eq!(&[1.0, 3.0, 2.0, 4.0], einsum("ij->ji", &[&a]).to_slice::<f32>().unwrap());
+    }
+
+    #[test]
+    fn test_tensordot() {
+        let a = MLXArray::array(&[1.0f32; 24], &[2, 3, 4]);
+        let b = MLXArray::array(&[1.0f32; 12], &[4, 3]);
+        let c = tensordot(&a, &b, &[1, 2], &[1, 0]);
+        assert_eq!(&[2], c.shape());
+        assert_eq!(&[12.0, 12.0], c.to_slice::<f32>().unwrap());
+        assert_eq!(&[2, 3, 3], tensordot_n(&a, &b, 1).shape());
+        assert!(try_tensordot(&a, &b, &[1], &[0, 1]).is_err());
+    }
+
+    #[test]
+    fn test_outer_inner_kron() {
+        let a = MLXArray::array(&[1i32, 2], &[2]);
+        let b = MLXArray::array(&[3i32, 4], &[2]);
+        assert_eq!(&[3, 4, 6, 8], a.outer(&b).to_slice::<i32>().unwrap());
+        assert_eq!(11, a.inner(&b).to_scalar::<i32>().unwrap());
+        assert_eq!(&[3, 4, 6, 8], a.kron(&b).to_slice::<i32>().unwrap());
+    }
+
+    #[test]
+    fn test_block_masked_and_gather_mm() {
+        let a = MLXArray::array(&[1.0f32; 64 * 64], &[64, 64]);
+        let mask = MLXArray::array(&[true, false, false, true], &[2, 2]);
+        let c = block_masked_mm(&a, &a, 32, Some(&mask), None, None);
+        assert_eq!(&[64, 64], c.shape());
+        let c = c.to_slice::<f32>().unwrap();
+        assert_eq!(64.0, c[0]);
+        assert_eq!(0.0, c[63]);
+
+        let experts = MLXArray::array(&[1.0f32, 0.0, 0.0, 1.0, 2.0, 0.0, 0.0, 2.0], &[2, 2, 2]);
+        let x = MLXArray::array(&[1.0f32, 1.0], &[1, 2]);
+        let idx = MLXArray::array(&[1u32], &[1]);
+        let y = gather_mm(&x, &experts, None, Some(&idx));
+        assert_eq!(&[2.0, 2.0], y.to_slice::<f32>().unwrap());
+    }
+
+    #[test]
+    fn test_scans() {
+        let x = MLXArray::array(&[1i32, 3, 2, 4], &[4]);
+        assert_eq!(&[1, 4, 6, 10], cum_sum(&x, 0, false, true).to_slice::<i32>().unwrap());
+        assert_eq!(&[0, 1, 4, 6], cum_sum(&x, 0, false, false).to_slice::<i32>().unwrap());
+        assert_eq!(&[1, 3, 6, 24], cum_prod(&x, 0, false, true).to_slice::<i32>().unwrap());
+        assert_eq!(&[1, 3, 3, 4], cum_max(&x, 0, false, true).to_slice::<i32>().unwrap());
+        assert_eq!(&[1, 2, 2, 4], cum_min(&x, 0, true, true).to_slice::<i32>().unwrap());
+    }
+
+    #[test]
+    fn test_log_cum_sum_exp_is_stable() {
+        let x = MLXArray::array(&[1000.0f32, 1000.0], &[2]);
+        let y = log_cum_sum_exp(&x, 0, false, true).to_slice::<f32>().unwrap().to_vec();
+        assert_eq!(1000.0, y[0]);
+        assert!((y[1] - (1000.0 + 2.0f32.ln())).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_weak_scalars() {
+        let x = MLXArray::array(&[f16::from_f32(1.0), f16::from_f32(2.0)], &[2]);
+        let y = &x * 0.5;
+        assert_eq!(Dtype::F16, y.dtype());
+        assert_eq!(&[f16::from_f32(0.5), f16::ONE], y.to_slice::<f16>().unwrap());
+        assert_eq!(Dtype::F16, (&x * 0.5f64).dtype());
+        assert_eq!(Dtype::F16, (0.5f32 * &x).dtype());
+        assert_eq!(Dtype::F16, (0.5f64 * &x).dtype());
+        assert_eq!(Dtype::F16, (2i32 + &x).dtype());
+
+        let x = MLXArray::array(&[bf16::ONE], &[1]);
+        assert_eq!(Dtype::BF16, (&x - 1u64).dtype());
+        assert_eq!(Dtype::BF16, (&x + f16::ONE).dtype());
+
+        let x = MLXArray::array(&[250u8, 3], &[2]);
+        let y = &x + 1;
+        assert_eq!(Dtype::U8, y.dtype());
+        assert_eq!(&[251, 4], y.to_slice::<u8>().unwrap());
+        assert_eq!(Dtype::F32, (&x * 0.5).dtype());
+        assert_eq!(&[8, 2], (8i64 % &x).to_slice::<u8>().unwrap());
+        assert!(1000i64.into_operand(&x).is_err());
+        assert!((-1i32).into_operand(&x).is_err());
+        assert_eq!(Dtype::F32, (&x + 1000.0).dtype());
+
+        let x = MLXArray::array(&[1i32, 2], &[2]);
+        assert_eq!(Dtype::I32, (x.clone() << 2u8).dtype());
+        assert_eq!(&[0.5, 1.0], (x / 2.0).to_slice::<f32>().unwrap());
+    }
+
     #[test]
     fn test_div() {
         let array1: MLXArray = 12.0.into();
-        let result = 12.0 / array1.clone() / array1 / 12.0;
+        let result: MLXArray = 12.0 / array1.clone() / array1 / 12.0;
         println!("{}", result.size());
         assert_eq!(1, result.size())
     }
@@ -0,0 +1,587 @@
+//! Convolution and pooling.
+//!
+//! Inputs are channels last like everywhere in MLX, `[N, L, C]` for 1-D, `[N, H, W, C]` for 2-D
+//! and `[N, D, H, W, C]` for 3-D. Weights are `[C_out, *kernel, C_in / groups]`.
+
+use mlx_sys::{
+    mlx_conv1d, mlx_conv2d, mlx_conv3d, mlx_conv_general, mlx_conv_transpose1d,
+    mlx_conv_transpose2d,
+};
+
+use crate::array::MLXArray;
+use crate::error::{guard, MLXError};
+use crate::stream::{get_default_stream, MLXStream};
+
+pub fn conv1d(
+    input: &MLXArray,
+    weight: &MLXArray,
+    stride: i32,
+    padding: i32,
+    dilation: i32,
+    groups: i32,
+) -> MLXArray {
+    try_conv1d(input, weight, stride, padding, dilation, groups).unwrap()
+}
+
+pub fn conv1d_on(
+    input: &MLXArray,
+    weight: &MLXArray,
+    stride: i32,
+    padding: i32,
+    dilation: i32,
+    groups: i32,
+    stream: &MLXStream,
+) -> MLXArray {
+    try_conv1d_on(input, weight, stride, padding, dilation, groups, stream).unwrap()
+}
+
+pub fn try_conv1d(
+    input: &MLXArray,
+    weight: &MLXArray,
+    stride: i32,
+    padding: i32,
+    dilation: i32,
+    groups: i32,
+) -> Result<MLXArray, MLXError> {
+    try_conv1d_on(input, weight, stride, padding, dilation, groups, &get_default_stream())
+}
+
+pub fn try_conv1d_on(
+    input: &MLXArray,
+    weight: &MLXArray,
+    stride: i32,
+    padding: i32,
+    dilation: i32,
+    groups: i32,
+    stream: &MLXStream,
+) -> Result<MLXArray, MLXError> {
+    let handle = guard(|| unsafe {
+        mlx_conv1d(
+            input.as_ptr(),
+            weight.as_ptr(),
+            stride,
+            padding,
+            dilation,
+            groups,
+            stream.as_ptr(),
+        )
+    })?;
+    Ok(MLXArray::from_raw(handle))
+}
+
+pub fn conv2d(
+    input: &MLXArray,
+    weight: &MLXArray,
+    stride: (i32, i32),
+    padding: (i32, i32),
+    dilation: (i32, i32),
+    groups: i32,
+) -> MLXArray {
+    try_conv2d(input, weight, stride, padding, dilation, groups).unwrap()
+}
+
+pub fn conv2d_on(
+    input: &MLXArray,
+    weight: &MLXArray,
+    stride: (i32, i32),
+    padding: (i32, i32),
+    dilation: (i32, i32),
+    groups: i32,
+    stream: &MLXStream,
+) -> MLXArray {
+    try_conv2d_on(input, weight, stride, padding, dilation, groups, stream).unwrap()
+}
+
+pub fn try_conv2d(
+    input: &MLXArray,
+    weight: &MLXArray,
+    stride: (i32, i32),
+    padding: (i32, i32),
+    dilation: (i32, i32),
+    groups: i32,
+) -> Result<MLXArray, MLXError> {
+    try_conv2d_on(input, weight, stride, padding, dilation, groups, &get_default_stream())
+}
+
+pub fn try_conv2d_on(
+    input: &MLXArray,
+    weight: &MLXArray,
+    stride: (i32, i32),
+    padding: (i32, i32),
+    dilation: (i32, i32),
+    groups: i32,
+    stream: &MLXStream,
+) -> Result<MLXArray, MLXError> {
+    let handle = guard(|| unsafe {
+        mlx_conv2d(
+            input.as_ptr(),
+            weight.as_ptr(),
+            stride.0,
+            stride.1,
+            padding.0,
+            padding.1,
+            dilation.0,
+            dilation.1,
+            groups,
+            stream.as_ptr(),
+        )
+    })?;
+    Ok(MLXArray::from_raw(handle))
+}
+
+pub fn conv3d(
+    input: &MLXArray,
+    weight: &MLXArray,
+    stride: (i32, i32, i32),
+    padding: (i32, i32, i32),
+    dilation: (i32, i32, i32),
+    groups: i32,
+) -> MLXArray {
+    try_conv3d(input, weight, stride, padding, dilation, groups).unwrap()
+}
+
+pub fn conv3d_on(
+    input: &MLXArray,
+    weight: &MLXArray,
+    stride: (i32, i32, i32),
+    padding: (i32, i32, i32),
+    dilation: (i32, i32, i32),
+    groups: i32,
+    stream: &MLXStream,
+) -> MLXArray {
+    try_conv3d_on(input, weight, stride, padding, dilation, groups, stream).unwrap()
+}
+
+pub fn try_conv3d(
+    input: &MLXArray,
+    weight: &MLXArray,
+    stride: (i32, i32, i32),
+    padding: (i32, i32, i32),
+    dilation: (i32, i32, i32),
+    groups: i32,
+) -> Result<MLXArray, MLXError> {
+    try_conv3d_on(input, weight, stride, padding, dilation, groups, &get_default_stream())
+}
+
+pub fn try_conv3d_on(
+    input: &MLXArray,
+    weight: &MLXArray,
+    stride: (i32, i32, i32),
+    padding: (i32, i32, i32),
+    dilation: (i32, i32, i32),
+    groups: i32,
+    stream: &MLXStream,
+) -> Result<MLXArray, MLXError> {
+    let handle = guard(|| unsafe {
+        mlx_conv3d(
+            input.as_ptr(),
+            weight.as_ptr(),
+            stride.0,
+            stride.1,
+            stride.2,
+            padding.0,
+            padding.1,
+            padding.2,
+            dilation.0,
+            dilation.1,
+            dilation.2,
+            groups,
+            stream.as_ptr(),
+        )
+    })?;
+    Ok(MLXArray::from_raw(handle))
+}
+
+/// Convolution over any number of spatial axes with separate low and high padding and input
+/// dilation, every slice has one entry per spatial axis. With `flip` the kernel is flipped,
+/// which makes this a true convolution instead of a cross-correlation.
+#[allow(clippy::too_many_arguments)]
+pub fn conv_general(
+    input: &MLXArray,
+    weight: &MLXArray,
+    stride: &[i32],
+    padding_lo: &[i32],
+    padding_hi: &[i32],
+    kernel_dilation: &[i32],
+    input_dilation: &[i32],
+    groups: i32,
+    flip: bool,
+) -> MLXArray {
+    try_conv_general(input, weight, stride, padding_lo, padding_hi, kernel_dilation, input_dilation, groups, flip).unwrap()
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn conv_general_on(
+    input: &MLXArray,
+    weight: &MLXArray,
+    stride: &[i32],
+    padding_lo: &[i32],
+    padding_hi: &[i32],
+    kernel_dilation: &[i32],
+    input_dilation: &[i32],
+    groups: i32,
+    flip: bool,
+    stream: &MLXStream,
+) -> MLXArray {
+    try_conv_general_on(input, weight, stride, padding_lo, padding_hi, kernel_dilation, input_dilation, groups, flip, stream).unwrap()
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn try_conv_general(
+    input: &MLXArray,
+    weight: &MLXArray,
+    stride: &[i32],
+    padding_lo: &[i32],
+    padding_hi: &[i32],
+    kernel_dilation: &[i32],
+    input_dilation: &[i32],
+    groups: i32,
+    flip: bool,
+) -> Result<MLXArray, MLXError> {
+    try_conv_general_on(input, weight, stride, padding_lo, padding_hi, kernel_dilation, input_dilation, groups, flip, &get_default_stream())
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn try_conv_general_on(
+    input: &MLXArray,
+    weight: &MLXArray,
+    stride: &[i32],
+    padding_lo: &[i32],
+    padding_hi: &[i32],
+    kernel_dilation: &[i32],
+    input_dilation: &[i32],
+    groups: i32,
+    flip: bool,
+    stream: &MLXStream,
+) -> Result<MLXArray, MLXError> {
+    let spatial = input.ndim().saturating_sub(2);
+    for (name, v) in [
+        ("stride", stride),
+        ("padding_lo", padding_lo),
+        ("padding_hi", padding_hi),
+        ("kernel_dilation", kernel_dilation),
+        ("input_dilation", input_dilation),
+    ] {
+        if v.len() != spatial {
+            return Err(MLXError::InvalidArgument(format!(
+                "{} has {} entries but the input has {} spatial axes",
+                name,
+                v.len(),
+                spatial
+            )));
+        }
+    }
+    let handle = guard(|| unsafe {
+        mlx_conv_general(
+            input.as_ptr(),
+            weight.as_ptr(),
+            stride.as_ptr(),
+            stride.len(),
+            padding_lo.as_ptr(),
+            padding_lo.len(),
+            padding_hi.as_ptr(),
+            padding_hi.len(),
+            kernel_dilation.as_ptr(),
+            kernel_dilation.len(),
+            input_dilation.as_ptr(),
+            input_dilation.len(),
+            groups,
+            flip,
+            stream.as_ptr(),
+        )
+    })?;
+    Ok(MLXArray::from_raw(handle))
+}
+
+/// Transposed 1-D convolution, weights are `[C_out, K, C_in / groups]`.
+pub fn conv_transpose1d(
+    input: &MLXArray,
+    weight: &MLXArray,
+    stride: i32,
+    padding: i32,
+    dilation: i32,
+    groups: i32,
+) -> MLXArray {
+    try_conv_transpose1d(input, weight, stride, padding, dilation, groups).unwrap()
+}
+
+pub fn conv_transpose1d_on(
+    input: &MLXArray,
+    weight: &MLXArray,
+    stride: i32,
+    padding: i32,
+    dilation: i32,
+    groups: i32,
+    stream: &MLXStream,
+) -> MLXArray {
+    try_conv_transpose1d_on(input, weight, stride, padding, dilation, groups, stream).unwrap()
+}
+
+pub fn try_conv_transpose1d(
+    input: &MLXArray,
+    weight: &MLXArray,
+    stride: i32,
+    padding: i32,
+    dilation: i32,
+    groups: i32,
+) -> Result<MLXArray, MLXError> {
+    try_conv_transpose1d_on(input, weight, stride, padding, dilation, groups, &get_default_stream())
+}
+
+pub fn try_conv_transpose1d_on(
+    input: &MLXArray,
+    weight: &MLXArray,
+    stride: i32,
+    padding: i32,
+    dilation: i32,
+    groups: i32,
+    stream: &MLXStream,
+) -> Result<MLXArray, MLXError> {
+    let handle = guard(|| unsafe {
+        mlx_conv_transpose1d(
+            input.as_ptr(),
+            weight.as_ptr(),
+            stride,
+            padding,
+            dilation,
+            groups,
+            stream.as_ptr(),
+        )
+    })?;
+    Ok(MLXArray::from_raw(handle))
+}
+
+/// Transposed 2-D convolution, weights are `[C_out, KH, KW, C_in / groups]`.
+pub fn conv_transpose2d(
+    input: &MLXArray,
+    weight: &MLXArray,
+    stride: (i32, i32),
+    padding: (i32, i32),
+    dilation: (i32, i32),
+    groups: i32,
+) -> MLXArray {
+    try_conv_transpose2d(input, weight, stride, padding, dilation, groups).unwrap()
+}
+
+pub fn conv_transpose2d_on(
+    input: &MLXArray,
+    weight: &MLXArray,
+    stride: (i32, i32),
+    padding: (i32, i32),
+    dilation: (i32, i32),
+    groups: i32,
+    stream: &MLXStream,
+) -> MLXArray {
+    try_conv_transpose2d_on(input, weight, stride, padding, dilation, groups, stream).unwrap()
+}
+
+pub fn try_conv_transpose2d(
+    input: &MLXArray,
+    weight: &MLXArray,
+    stride: (i32, i32),
+    padding: (i32, i32),
+    dilation: (i32, i32),
+    groups: i32,
+) -> Result<MLXArray, MLXError> {
+    try_conv_transpose2d_on(input, weight, stride, padding, dilation, groups, &get_default_stream())
+}
+
+pub fn try_conv_transpose2d_on(
+    input: &MLXArray,
+    weight: &MLXArray,
+    stride: (i32, i32),
+    padding: (i32, i32),
+    dilation: (i32, i32),
+    groups: i32,
+    stream: &MLXStream,
+) -> Result<MLXArray, MLXError> {
+    let handle = guard(|| unsafe {
+        mlx_conv_transpose2d(
+            input.as_ptr(),
+            weight.as_ptr(),
+            stride.0,
+            stride.1,
+            padding.0,
+            padding.1,
+            dilation.0,
+            dilation.1,
+            groups,
+            stream.as_ptr(),
+        )
+    })?;
+    Ok(MLXArray::from_raw(handle))
+}
+
+/// Sliding windows over the spatial axes of a channels last input, `[N, *spatial, C]` becomes
+/// `[N, *out, *kernel, C]` without copying.
+fn pool_windows(x: &MLXArray, kernel: &[i32], stride: &[i32], stream: &MLXStream) -> Result<MLXArray, MLXError> {
+    let shape = x.shape();
+    if shape.len() != kernel.len() + 2 {
+        return Err(MLXError::Shape(format!(
+            "expected an input with {} dimensions for a {}-D pooling, got shape {:?}",
+            kernel.len() + 2,
+            kernel.len(),
+            shape
+        )));
+    }
+    if kernel.iter().chain(stride).any(|v| *v < 1) {
+        return Err(MLXError::InvalidArgument("kernel size and stride must be positive".into()));
+    }
+    let mut contiguous = vec![1usize; shape.len()];
+    for i in (0..shape.len() - 1).rev() {
+        contiguous[i] = contiguous[i + 1] * shape[i + 1] as usize;
+    }
+    let spatial = &shape[1..shape.len() - 1];
+    let mut window_shape = vec![shape[0]];
+    let mut window_strides = vec![contiguous[0]];
+    for i in 0..kernel.len() {
+        if spatial[i] < kernel[i] {
+            return Err(MLXError::Shape(format!(
+                "pooling window {} is larger than the input size {}",
+                kernel[i], spatial[i]
+            )));
+        }
+        window_shape.push((spatial[i] - kernel[i]) / stride[i] + 1);
+        window_strides.push(contiguous[i + 1] * stride[i] as usize);
+    }
+    for i in 0..kernel.len() {
+        window_shape.push(kernel[i]);
+        window_strides.push(contiguous[i + 1]);
+    }
+    window_shape.push(shape[shape.len() - 1]);
+    window_strides.push(1);
+    x.try_as_strided_on(&window_shape, &window_strides, 0, stream)
+}
+
+fn pool(x: &MLXArray, kernel: &[i32], stride: &[i32], max: bool, stream: &MLXStream) -> Result<MLXArray, MLXError> {
+    let windows = pool_windows(x, kernel, stride, stream)?;
+    let axes: Vec<i32> = (0..kernel.len() as i32).map(|i| -2 - i).collect();
+    if max {
+        windows.try_max_on(&axes, false, stream)
+    } else {
+        windows.try_mean_on(&axes, false, stream)
+    }
+}
+
+/// Max pooling of a `[N, L, C]` input, without padding.
+pub fn max_pool1d(x: &MLXArray, kernel_size: i32, stride: i32) -> MLXArray {
+    try_max_pool1d(x, kernel_size, stride).unwrap()
+}
+
+pub fn max_pool1d_on(x: &MLXArray, kernel_size: i32, stride: i32, stream: &MLXStream) -> MLXArray {
+    try_max_pool1d_on(x, kernel_size, stride, stream).unwrap()
+}
+
+pub fn try_max_pool1d(x: &MLXArray, kernel_size: i32, stride: i32) -> Result<MLXArray, MLXError> {
+    try_max_pool1d_on(x, kernel_size, stride, &get_default_stream())
+}
+
+pub fn try_max_pool1d_on(x: &MLXArray, kernel_size: i32, stride: i32, stream: &MLXStream) -> Result<MLXArray, MLXError> {
+    pool(x, &[kernel_size], &[stride], true, stream)
+}
+
+/// Max pooling of a `[N, H, W, C]` input, without padding.
+pub fn max_pool2d(x: &MLXArray, kernel_size: (i32, i32), stride: (i32, i32)) -> MLXArray {
+    try_max_pool2d(x, kernel_size, stride).unwrap()
+}
+
+pub fn max_pool2d_on(x: &MLXArray, kernel_size: (i32, i32), stride: (i32, i32), stream: &MLXStream) -> MLXArray {
+    try_max_pool2d_on(x, kernel_size, stride, stream).unwrap()
+}
+
+pub fn try_max_pool2d(x: &MLXArray, kernel_size: (i32, i32), stride: (i32, i32)) -> Result<MLXArray, MLXError> {
+    try_max_pool2d_on(x, kernel_size, stride, &get_default_stream())
+}
+
+pub fn try_max_pool2d_on(x: &MLXArray, kernel_size: (i32, i32), stride: (i32, i32), stream: &MLXStream) -> Result<MLXArray, MLXError> {
+    pool(x, &[kernel_size.0, kernel_size.1], &[stride.0, stride.1], true, stream)
+}
+
+/// Average pooling of a `[N, L, C]` input, without padding.
+pub fn avg_pool1d(x: &MLXArray, kernel_size: i32, stride: i32) -> MLXArray {
+    try_avg_pool1d(x, kernel_size, stride).unwrap()
+}
+
+pub fn avg_pool1d_on(x: &MLXArray, kernel_size: i32, stride: i32, stream: &MLXStream) -> MLXArray {
+    try_avg_pool1d_on(x, kernel_size, stride, stream).unwrap()
+}
+
+pub fn try_avg_pool1d(x: &MLXArray, kernel_size: i32, stride: i32) -> Result<MLXArray, MLXError> {
+    try_avg_pool1d_on(x, kernel_size, stride, &get_default_stream())
+}
+
+pub fn try_avg_pool1d_on(x: &MLXArray, kernel_size: i32, stride: i32, stream: &MLXStream) -> Result<MLXArray, MLXError> {
+    pool(x, &[kernel_size], &[stride], false, stream)
+}
+
+/// Average pooling of a `[N, H, W, C]` input, without padding.
+pub fn avg_pool2d(x: &MLXArray, kernel_size: (i32, i32), stride: (i32, i32)) -> MLXArray {
+    try_avg_pool2d(x, kernel_size, stride).unwrap()
+}
+
+pub fn avg_pool2d_on(x: &MLXArray, kernel_size: (i32, i32), stride: (i32, i32), stream: &MLXStream) -> MLXArray {
+    try_avg_pool2d_on(x, kernel_size, stride, stream).unwrap()
+}
+
+pub fn try_avg_pool2d(x: &MLXArray, kernel_size: (i32, i32), stride: (i32, i32)) -> Result<MLXArray, MLXError> {
+    try_avg_pool2d_on(x, kernel_size, stride, &get_default_stream())
+}
+
+pub fn try_avg_pool2d_on(x: &MLXArray, kernel_size: (i32, i32), stride: (i32, i32), stream: &MLXStream) -> Result<MLXArray, MLXError> {
+    pool(x, &[kernel_size.0, kernel_size.1], &[stride.0, stride.1], false, stream)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::array::MLXArray;
+    use crate::conv::{
+        avg_pool1d, conv1d, conv2d, conv_general, conv_transpose1d, conv_transpose2d, max_pool2d,
+        try_conv_general, try_max_pool1d,
+    };
+
+    #[test]
+    fn test_conv1d() {
+        let x = MLXArray::array(&[1.0f32, 2.0, 3.0, 4.0], &[1, 4, 1]);
+        let w = MLXArray::array(&[1.0f32, 1.0], &[1, 2, 1]);
+        let y = conv1d(&x, &w, 1, 0, 1, 1);
+        assert_eq!(&[1, 3, 1], y.shape());
+        assert_eq!(&[3.0, 5.0, 7.0], y.to_slice::<f32>().unwrap());
+        let y = conv1d(&x, &w, 2, 1, 1, 1);
+        assert_eq!(&[1.0, 5.0, 4.0], y.to_slice::<f32>().unwrap());
+    }
+
+    #[test]
+    fn test_conv2d_and_general() {
+        let x = MLXArray::array(&[1.0f32; 2 * 5 * 5 * 3], &[2, 5, 5, 3]);
+        let w = MLXArray::array(&[1.0f32; 4 * 3 * 3 * 3], &[4, 3, 3, 3]);
+        let y = conv2d(&x, &w, (2, 2), (1, 1), (1, 1), 1);
+        assert_eq!(&[2, 3, 3, 4], y.shape());
+        let g = conv_general(&x, &w, &[2, 2], &[1, 1], &[1, 1], &[1, 1], &[1, 1], 1, false);
+        assert_eq!(&[2, 3, 3, 4], g.shape());
+        assert!(try_conv_general(&x, &w, &[2], &[1, 1], &[1, 1], &[1, 1], &[1, 1], 1, false).is_err());
+    }
+
+    #[test]
+    fn test_conv_transpose() {
+        let x = MLXArray::array(&[1.0f32, 2.0], &[1, 2, 1]);
+        let w = MLXArray::array(&[1.0f32, 1.0], &[1, 2, 1]);
+        let y = conv_transpose1d(&x, &w, 2, 0, 1, 1);
+        assert_eq!(&[1.0, 1.0, 2.0, 2.0], y.to_slice::<f32>().unwrap());
+
+        let x = MLXArray::array(&[1.0f32; 4], &[1, 2, 2, 1]);
+        let w = MLXArray::array(&[1.0f32; 4], &[1, 2, 2, 1]);
+        assert_eq!(&[1, 4, 4, 1], conv_transpose2d(&x, &w, (2, 2), (0, 0), (1, 1), 1).shape());
+    }
+
+    #[test]
+    fn test_pooling() {
+        let x = MLXArray::array(&[1.0f32, 5.0, 2.0, 4.0, 3.0, 6.0], &[1, 6, 1]);
+        assert_eq!(&[3.0, 3.0, 4.5], avg_pool1d(&x, 2, 2).to_slice::<f32>().unwrap());
+        assert!(try_max_pool1d(&x, 7, 1).is_err());
+
+        let x = MLXArray::arange::<f32>(0.0..16.0, 1.0).reshape(&[1, 4, 4, 1]);
+        let y = max_pool2d(&x, (2, 2), (2, 2));
+        assert_eq!(&[1, 2, 2, 1], y.shape());
+        assert_eq!(&[5.0, 7.0, 13.0, 15.0], y.to_slice::<f32>().unwrap());
+    }
+}
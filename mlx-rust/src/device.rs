@@ -1,11 +1,12 @@
-use std::{fmt::Display, sync::Mutex};
+use std::{cell::RefCell, fmt::Display, sync::Mutex};
 
 use mlx_sys::{
     mlx_default_device, mlx_device, mlx_device_, mlx_device_get_type, mlx_device_new,
-    mlx_device_type__MLX_CPU, mlx_device_type__MLX_GPU,
+    mlx_device_type__MLX_CPU, mlx_device_type__MLX_GPU, mlx_set_default_device,
 };
 
 use crate::object::MLXObject;
+use crate::stream::{set_default_stream, with_stream, MLXStream};
 
 lazy_static::lazy_static! {
     static ref DEFAULT_DEVICE: Mutex<MLXDevice> = Mutex::new(MLXDevice::from_raw(unsafe {
@@ -13,21 +14,58 @@ lazy_static::lazy_static! {
     }));
 }
 
+thread_local! {
+    static SCOPED_DEVICES: RefCell<Vec<MLXDevice>> = const { RefCell::new(Vec::new()) };
+}
+
+/// The innermost [`with_device`] scope of this thread, the process wide default otherwise.
 pub fn get_default_device() -> MLXDevice {
+    if let Some(device) = SCOPED_DEVICES.with(|d| d.borrow().last().cloned()) {
+        return device;
+    }
+    global_default_device()
+}
+
+pub(crate) fn global_default_device() -> MLXDevice {
     let guard = DEFAULT_DEVICE.lock().unwrap();
-    // let handle = unsafe { mlx_default_device() };
-    // MLXDevice::from_raw(handle)
     guard.clone()
 }
 
-//todo is it safe?
+/// Replaces the process wide default device, in mlx too, and moves the default stream to it.
 pub fn set_default_device(device: MLXDevice) {
-    let mut guard = DEFAULT_DEVICE.lock().unwrap();
-    // unsafe {
-    //     mlx_set_default_device(device.as_ptr());
-    //     println!("after get: {:?} ", mlx_default_device())
-    // }
-    *guard = device
+    let stream = MLXStream::default_stream(device.clone());
+    {
+        let mut guard = DEFAULT_DEVICE.lock().unwrap();
+        unsafe {
+            mlx_set_default_device(device.as_ptr());
+        }
+        *guard = device;
+    }
+    // the first use of the default stream reads the default device, so the lock must be released
+    set_default_stream(stream);
+}
+
+struct PopDevice;
+
+impl Drop for PopDevice {
+    fn drop(&mut self) {
+        SCOPED_DEVICES.with(|d| d.borrow_mut().pop());
+    }
+}
+
+/// Runs `f` on the default stream of `device` for the current thread, see
+/// [`with_stream`](crate::stream::with_stream).
+///
+/// ```
+/// use mlx_rust::device::{get_default_device, with_device, MLXDevice};
+/// let cpu = MLXDevice::cpu();
+/// with_device(cpu.clone(), || assert_eq!(cpu, get_default_device()));
+/// ```
+pub fn with_device<R>(device: MLXDevice, f: impl FnOnce() -> R) -> R {
+    let stream = MLXStream::default_stream(device.clone());
+    SCOPED_DEVICES.with(|d| d.borrow_mut().push(device));
+    let _pop = PopDevice;
+    with_stream(&stream, f)
 }
 
 #[derive(Clone, PartialEq, Debug)]
@@ -105,20 +143,44 @@ impl TryFrom<u32> for DeviceType {
 #[cfg(test)]
 mod tests {
     use crate::device::DeviceType;
+    use crate::stream::DEFAULT_STREAM_TESTS;
+
+    use super::{get_default_device, set_default_device, with_device, MLXDevice};
 
-    use super::{get_default_device, set_default_device, MLXDevice};
+    /// Puts the previous process wide default device back when dropped.
+    struct RestoreDevice(MLXDevice);
+
+    impl Drop for RestoreDevice {
+        fn drop(&mut self) {
+            set_default_device(self.0.clone());
+        }
+    }
 
     #[test]
     pub fn test_get_default_device() {
+        let _lock = DEFAULT_STREAM_TESTS.lock().unwrap_or_else(|e| e.into_inner());
         let device_type = get_default_device().device_type().unwrap();
         assert_eq!(DeviceType::GPU, device_type)
     }
 
     #[test]
     pub fn test_set_default_device() {
+        let _lock = DEFAULT_STREAM_TESTS.lock().unwrap_or_else(|e| e.into_inner());
+        let _restore = RestoreDevice(get_default_device());
         let cpu = MLXDevice::cpu();
-        println!("before set: {:?}", get_default_device());
         set_default_device(cpu.clone());
         assert_eq!(cpu, get_default_device())
     }
+
+    #[test]
+    pub fn test_with_device() {
+        let _lock = DEFAULT_STREAM_TESTS.lock().unwrap_or_else(|e| e.into_inner());
+        let before = get_default_device();
+        let cpu = MLXDevice::cpu();
+        with_device(cpu.clone(), || {
+            assert_eq!(cpu, get_default_device());
+            assert_eq!(DeviceType::CPU, get_default_device().device_type().unwrap());
+        });
+        assert_eq!(before, get_default_device());
+    }
 }
@@ -0,0 +1,164 @@
+use std::cell::RefCell;
+use std::ffi::CStr;
+use std::fmt::{Display, Formatter};
+use std::sync::Once;
+
+use mlx_sys::mlx_set_error_handler;
+
+use crate::r#type::Dtype;
+
+/// Errors returned by the fallible (`try_*`) operations.
+#[derive(Debug)]
+pub enum MLXError {
+    /// The array holds a different dtype than the one requested.
+    DtypeMismatch { expected: Dtype, actual: Dtype },
+    /// Incompatible shapes, e.g. a bad reshape or arrays that cannot be broadcast.
+    Shape(String),
+    Io(std::io::Error),
+    InvalidArgument(String),
+    /// An exception raised inside mlx.
+    Backend(String),
+}
+
+/// The ops whose exceptions are all about incompatible shapes, as they prefix their messages.
+const SHAPE_ERROR_PREFIXES: &[&str] = &[
+    "[reshape]",
+    "[broadcast_shapes]",
+    "[broadcast_to]",
+    "[broadcast_arrays]",
+    "[expand_dims]",
+    "[squeeze]",
+    "[flatten]",
+    "[transpose]",
+    "[concatenate]",
+    "[stack]",
+];
+
+impl MLXError {
+    fn from_backend(msg: String) -> Self {
+        if SHAPE_ERROR_PREFIXES.iter().any(|p| msg.starts_with(p)) {
+            MLXError::Shape(msg)
+        } else {
+            MLXError::Backend(msg)
+        }
+    }
+}
+
+impl Display for MLXError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MLXError::DtypeMismatch { expected, actual } => {
+                write!(f, "dtype mismatch: expected {}, got {}", expected, actual)
+            }
+            MLXError::Shape(msg) => write!(f, "shape error: {}", msg),
+            MLXError::Io(e) => write!(f, "io error: {}", e),
+            MLXError::InvalidArgument(msg) => write!(f, "invalid argument: {}", msg),
+            MLXError::Backend(msg) => write!(f, "mlx error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for MLXError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            MLXError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for MLXError {
+    fn from(value: std::io::Error) -> Self {
+        MLXError::Io(value)
+    }
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+static INIT_ERROR_HANDLER: Once = Once::new();
+
+/// Called by mlx-c instead of aborting the process when an exception is raised.
+/// The message is kept for the calling thread and picked up by [`guard`].
+extern "C" fn error_handler(msg: *const ::std::os::raw::c_char, _data: *mut ::std::os::raw::c_void) {
+    let msg = if msg.is_null() {
+        "unknown error".to_string()
+    } else {
+        unsafe { CStr::from_ptr(msg) }.to_string_lossy().into_owned()
+    };
+    LAST_ERROR.with(|e| *e.borrow_mut() = Some(msg));
+}
+
+/// Installs the mlx-c error handler, only the first call has an effect.
+pub(crate) fn init_error_handler() {
+    INIT_ERROR_HANDLER.call_once(|| unsafe {
+        mlx_set_error_handler(Some(error_handler), ::std::ptr::null_mut(), None);
+    });
+}
+
+fn take_last_error() -> Option<String> {
+    LAST_ERROR.with(|e| e.borrow_mut().take())
+}
+
+/// Runs an mlx-c call, turning a reported exception or a null handle into an `MLXError`.
+pub(crate) fn guard<T>(f: impl FnOnce() -> *mut T) -> Result<*mut T, MLXError> {
+    init_error_handler();
+    take_last_error();
+    let handle = f();
+    match take_last_error() {
+        Some(msg) => Err(MLXError::from_backend(msg)),
+        None if handle.is_null() => Err(MLXError::Backend("mlx returned a null handle".into())),
+        None => Ok(handle),
+    }
+}
+
+/// Like [`guard`] for mlx-c calls without a returned handle.
+pub(crate) fn guard_unit(f: impl FnOnce()) -> Result<(), MLXError> {
+    init_error_handler();
+    take_last_error();
+    f();
+    match take_last_error() {
+        Some(msg) => Err(MLXError::from_backend(msg)),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::MLXError;
+    use crate::MLXArray;
+
+    #[test]
+    fn test_bad_reshape() {
+        let array = MLXArray::array(&[1.0f32, 2.0, 3.0], &[3]);
+        let r = array.try_reshape(&[2, 2]);
+        assert!(matches!(r, Err(MLXError::Shape(_))));
+    }
+
+    #[test]
+    fn test_bad_broadcast() {
+        let a = MLXArray::array(&[1.0f32, 2.0, 3.0], &[3]);
+        let b = MLXArray::array(&[1.0f32, 2.0], &[2]);
+        assert!(matches!(a.try_add(&b), Err(MLXError::Shape(_))));
+    }
+
+    #[test]
+    fn test_backend_error_kind() {
+        assert!(matches!(
+            MLXError::from_backend("[reshape] Cannot reshape array of size 3 into shape (2,2).".into()),
+            MLXError::Shape(_)
+        ));
+        assert!(matches!(
+            MLXError::from_backend("[take] Received invalid shape for indices.".into()),
+            MLXError::Backend(_)
+        ));
+    }
+
+    #[test]
+    fn test_dtype_mismatch() {
+        let array: MLXArray = 2.into();
+        let r = array.to_scalar::<f32>();
+        assert!(matches!(r, Err(MLXError::DtypeMismatch { .. })));
+    }
+}
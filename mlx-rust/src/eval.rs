@@ -0,0 +1,118 @@
+//! Evaluation of several arrays at once.
+//!
+//! Evaluating the outputs of a step together lets mlx schedule the whole graph at once instead of
+//! one [`MLXArray::eval`] after the other, and [`async_eval`] returns before the computation is
+//! done so the next graph can be built in the meantime.
+
+use mlx_sys::{mlx_array_is_available, mlx_async_eval, mlx_eval};
+
+use crate::array::MLXArray;
+use crate::error::{guard_unit, MLXError};
+use crate::VectorMLXArray;
+
+fn to_vector(arrays: &[&MLXArray]) -> VectorMLXArray {
+    let mut vec = VectorMLXArray::new();
+    for array in arrays {
+        vec.add((*array).clone());
+    }
+    vec
+}
+
+/// Evaluates all `arrays`, sharing the work of their common inputs.
+pub fn eval(arrays: &[&MLXArray]) {
+    try_eval(arrays).unwrap()
+}
+
+pub fn try_eval(arrays: &[&MLXArray]) -> Result<(), MLXError> {
+    try_eval_vector(&to_vector(arrays))
+}
+
+pub fn eval_vector(arrays: &VectorMLXArray) {
+    try_eval_vector(arrays).unwrap()
+}
+
+pub fn try_eval_vector(arrays: &VectorMLXArray) -> Result<(), MLXError> {
+    guard_unit(|| unsafe { mlx_eval(arrays.as_ptr()) })
+}
+
+/// Schedules the evaluation of `arrays` and returns without waiting for it.
+///
+/// ```
+/// use mlx_rust::MLXArray;
+/// use mlx_rust::eval::async_eval;
+/// let x = MLXArray::array(&[1.0f32, 2.0], &[2]);
+/// let y = &x * 2.0;
+/// let pending = async_eval(&[&y]);
+/// // ... build the next graph ...
+/// pending.wait();
+/// assert_eq!(&[2.0, 4.0], y.to_slice::<f32>().unwrap());
+/// ```
+pub fn async_eval(arrays: &[&MLXArray]) -> AsyncEval {
+    try_async_eval(arrays).unwrap()
+}
+
+pub fn try_async_eval(arrays: &[&MLXArray]) -> Result<AsyncEval, MLXError> {
+    let arrays = to_vector(arrays);
+    guard_unit(|| unsafe { mlx_async_eval(arrays.as_ptr()) })?;
+    Ok(AsyncEval { arrays })
+}
+
+/// A running [`async_eval`]. Dropping it does not cancel the computation.
+#[derive(Debug)]
+pub struct AsyncEval {
+    arrays: VectorMLXArray,
+}
+
+impl AsyncEval {
+    /// Whether every array has been computed, without blocking.
+    pub fn is_ready(&self) -> bool {
+        self.arrays
+            .to_vec()
+            .iter()
+            .all(|a| unsafe { mlx_array_is_available(a.as_ptr()) })
+    }
+
+    /// Blocks until every array has been computed.
+    pub fn wait(self) {
+        self.try_wait().unwrap()
+    }
+
+    pub fn try_wait(self) -> Result<(), MLXError> {
+        try_eval_vector(&self.arrays)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::array::MLXArray;
+    use crate::eval::{async_eval, eval, eval_vector};
+    use crate::VectorMLXArray;
+
+    #[test]
+    fn test_eval_many() {
+        let x = MLXArray::array(&[1i32, 2, 3], &[3]);
+        let a = &x + 1;
+        let b = &x * 2;
+        eval(&[&a, &b]);
+        assert_eq!(&[2, 3, 4], a.to_slice::<i32>().unwrap());
+        assert_eq!(&[2, 4, 6], b.to_slice::<i32>().unwrap());
+
+        let c = &a - &b;
+        eval_vector(&VectorMLXArray::from(std::slice::from_ref(&c)));
+        assert_eq!(&[0, -1, -2], c.to_slice::<i32>().unwrap());
+    }
+
+    #[test]
+    fn test_async_eval() {
+        let x = MLXArray::ones::<f32>(&[256, 256]);
+        let y = x.matmul(&x);
+        let pending = async_eval(&[&y]);
+        pending.wait();
+        assert_eq!(256.0, y.to_slice::<f32>().unwrap()[0]);
+
+        let pending = async_eval(&[&y]);
+        while !pending.is_ready() {
+            std::thread::yield_now();
+        }
+    }
+}
@@ -5,17 +5,53 @@ use mlx_sys::mlx_fast_rms_norm;
 use mlx_sys::mlx_fast_rope;
 
 use crate::{MLXArray, stream::MLXStream};
+use crate::stream::get_default_stream;
+use crate::error::{guard, MLXError};
 
 pub fn fast_RoPE(
-    array: MLXArray,
+    array: &MLXArray,
     dim: i32,
     traditional: bool,
     base: f32,
     scale: f32,
     offset: i32,
-    stream: MLXStream,
 ) -> MLXArray {
-    let handle = unsafe {
+    try_fast_RoPE(array, dim, traditional, base, scale, offset).unwrap()
+}
+
+pub fn fast_RoPE_on(
+    array: &MLXArray,
+    dim: i32,
+    traditional: bool,
+    base: f32,
+    scale: f32,
+    offset: i32,
+    stream: &MLXStream,
+) -> MLXArray {
+    try_fast_RoPE_on(array, dim, traditional, base, scale, offset, stream).unwrap()
+}
+
+pub fn try_fast_RoPE(
+    array: &MLXArray,
+    dim: i32,
+    traditional: bool,
+    base: f32,
+    scale: f32,
+    offset: i32,
+) -> Result<MLXArray, MLXError> {
+    try_fast_RoPE_on(array, dim, traditional, base, scale, offset, &get_default_stream())
+}
+
+pub fn try_fast_RoPE_on(
+    array: &MLXArray,
+    dim: i32,
+    traditional: bool,
+    base: f32,
+    scale: f32,
+    offset: i32,
+    stream: &MLXStream,
+) -> Result<MLXArray, MLXError> {
+    let handle = guard(|| unsafe {
         mlx_fast_rope(
             array.as_ptr(),
             dim,
@@ -25,8 +61,8 @@ pub fn fast_RoPE(
             offset,
             stream.as_ptr(),
         )
-    };
-    MLXArray::from_raw(handle)
+    })?;
+    Ok(MLXArray::from_raw(handle))
 }
 
 /// A fast implementation of multi-head attention: `O = softmax(Q @ K.T, dim=-1) @ V`
@@ -40,14 +76,45 @@ pub fn fast_RoPE(
 /// > Note: For Grouped Query Attention and Multi-Query Attention, the input arrays for `key` and `value` should not be pre-tiled to match the `query` array.
 ///
 pub fn fast_scaled_dot_product_attention(
-    queries: MLXArray,
-    keys: MLXArray,
-    values: MLXArray,
+    queries: &MLXArray,
+    keys: &MLXArray,
+    values: &MLXArray,
     scale: f32,
-    mask: Option<MLXArray>,
-    stream: MLXStream,
+    mask: Option<&MLXArray>,
 ) -> MLXArray {
-    let handle = unsafe {
+    try_fast_scaled_dot_product_attention(queries, keys, values, scale, mask).unwrap()
+}
+
+pub fn fast_scaled_dot_product_attention_on(
+    queries: &MLXArray,
+    keys: &MLXArray,
+    values: &MLXArray,
+    scale: f32,
+    mask: Option<&MLXArray>,
+    stream: &MLXStream,
+) -> MLXArray {
+    try_fast_scaled_dot_product_attention_on(queries, keys, values, scale, mask, stream).unwrap()
+}
+
+pub fn try_fast_scaled_dot_product_attention(
+    queries: &MLXArray,
+    keys: &MLXArray,
+    values: &MLXArray,
+    scale: f32,
+    mask: Option<&MLXArray>,
+) -> Result<MLXArray, MLXError> {
+    try_fast_scaled_dot_product_attention_on(queries, keys, values, scale, mask, &get_default_stream())
+}
+
+pub fn try_fast_scaled_dot_product_attention_on(
+    queries: &MLXArray,
+    keys: &MLXArray,
+    values: &MLXArray,
+    scale: f32,
+    mask: Option<&MLXArray>,
+    stream: &MLXStream,
+) -> Result<MLXArray, MLXError> {
+    let handle = guard(|| unsafe {
         mlx_fast_scaled_dot_product_attention(
             queries.as_ptr(),
             keys.as_ptr(),
@@ -56,15 +123,27 @@ pub fn fast_scaled_dot_product_attention(
             mask.map(|b|b.as_ptr()).unwrap_or(ptr::null_mut()),
             stream.as_ptr(),
         )
-    };
-    MLXArray::from_raw(handle)
+    })?;
+    Ok(MLXArray::from_raw(handle))
 }
 
 /// Layer normalization.
 ///
 /// The normalization is with respect to the last axis of the input `x`.
-pub fn fast_layer_norm(x: MLXArray, weight: Option<MLXArray>, bias: Option<MLXArray>, eps: f32, stream: MLXStream) -> MLXArray {
-    let handle = unsafe {
+pub fn fast_layer_norm(x: &MLXArray, weight: Option<&MLXArray>, bias: Option<&MLXArray>, eps: f32) -> MLXArray {
+    try_fast_layer_norm(x, weight, bias, eps).unwrap()
+}
+
+pub fn fast_layer_norm_on(x: &MLXArray, weight: Option<&MLXArray>, bias: Option<&MLXArray>, eps: f32, stream: &MLXStream) -> MLXArray {
+    try_fast_layer_norm_on(x, weight, bias, eps, stream).unwrap()
+}
+
+pub fn try_fast_layer_norm(x: &MLXArray, weight: Option<&MLXArray>, bias: Option<&MLXArray>, eps: f32) -> Result<MLXArray, MLXError> {
+    try_fast_layer_norm_on(x, weight, bias, eps, &get_default_stream())
+}
+
+pub fn try_fast_layer_norm_on(x: &MLXArray, weight: Option<&MLXArray>, bias: Option<&MLXArray>, eps: f32, stream: &MLXStream) -> Result<MLXArray, MLXError> {
+    let handle = guard(|| unsafe {
         mlx_fast_layer_norm(
             x.as_ptr(),
             weight.map(|b|b.as_ptr()).unwrap_or(ptr::null_mut()),
@@ -72,15 +151,26 @@ pub fn fast_layer_norm(x: MLXArray, weight: Option<MLXArray>, bias: Option<MLXAr
             eps,
             stream.as_ptr(),
         )
-    };
-    MLXArray::from_raw(handle)
+    })?;
+    Ok(MLXArray::from_raw(handle))
 }
 
 /// Root Mean Square normalization (RMS norm).
 ///
 /// The normalization is with respect to the last axis of the input `x`.
-pub fn fast_rms_norm(x: MLXArray, weight: MLXArray, eps: f32, stream: MLXStream) -> MLXArray {
-    let handle = unsafe { mlx_fast_rms_norm(x.as_ptr(), weight.as_ptr(), eps, stream.as_ptr()) };
-    MLXArray::from_raw(handle)
+pub fn fast_rms_norm(x: &MLXArray, weight: &MLXArray, eps: f32) -> MLXArray {
+    try_fast_rms_norm(x, weight, eps).unwrap()
+}
+
+pub fn fast_rms_norm_on(x: &MLXArray, weight: &MLXArray, eps: f32, stream: &MLXStream) -> MLXArray {
+    try_fast_rms_norm_on(x, weight, eps, stream).unwrap()
+}
+
+pub fn try_fast_rms_norm(x: &MLXArray, weight: &MLXArray, eps: f32) -> Result<MLXArray, MLXError> {
+    try_fast_rms_norm_on(x, weight, eps, &get_default_stream())
+}
 
+pub fn try_fast_rms_norm_on(x: &MLXArray, weight: &MLXArray, eps: f32, stream: &MLXStream) -> Result<MLXArray, MLXError> {
+    let handle = guard(|| unsafe { mlx_fast_rms_norm(x.as_ptr(), weight.as_ptr(), eps, stream.as_ptr()) })?;
+    Ok(MLXArray::from_raw(handle))
 }
@@ -0,0 +1,224 @@
+//! Discrete Fourier transforms.
+//!
+//! The complex transforms return `complex64` arrays, read them with
+//! `to_slice::<num_complex::Complex32>()`. When the transform size `n` (or `s` for the
+//! multi-dimensional forms) is larger than the input, the input is zero padded, when smaller it
+//! is truncated.
+
+use mlx_sys::{
+    mlx_array, mlx_fft_fft, mlx_fft_fftn, mlx_fft_ifft, mlx_fft_ifftn, mlx_fft_irfft,
+    mlx_fft_irfftn, mlx_fft_rfft, mlx_fft_rfftn, mlx_stream,
+};
+
+use crate::array::MLXArray;
+use crate::error::{guard, MLXError};
+use crate::stream::{get_default_stream, MLXStream};
+
+fn resolve_axis(x: &MLXArray, axis: i32) -> Result<usize, MLXError> {
+    let ndim = x.ndim() as i32;
+    if axis < -ndim || axis >= ndim {
+        return Err(MLXError::InvalidArgument(format!(
+            "axis {} is out of bounds for an array with {} dimensions",
+            axis, ndim
+        )));
+    }
+    Ok(if axis < 0 { axis + ndim } else { axis } as usize)
+}
+
+/// The default transform size along `axis`. For inverse real transforms the last axis holds
+/// `n / 2 + 1` frequencies, so the output has `2 * (len - 1)` elements.
+fn default_size(x: &MLXArray, axis: usize, inverse_real: bool) -> i32 {
+    let len = x.shape()[axis];
+    if inverse_real {
+        2 * (len - 1)
+    } else {
+        len
+    }
+}
+
+type Fft1Fn = unsafe extern "C" fn(mlx_array, i32, i32, mlx_stream) -> mlx_array;
+type FftNFn = unsafe extern "C" fn(mlx_array, *const i32, usize, *const i32, usize, mlx_stream) -> mlx_array;
+
+fn fft_1d(
+    f: Fft1Fn,
+    inverse_real: bool,
+    x: &MLXArray,
+    n: Option<i32>,
+    axis: i32,
+    stream: &MLXStream,
+) -> Result<MLXArray, MLXError> {
+    let n = match n {
+        Some(n) => n,
+        None => default_size(x, resolve_axis(x, axis)?, inverse_real),
+    };
+    let handle = guard(|| unsafe { f(x.as_ptr(), n, axis, stream.as_ptr()) })?;
+    Ok(MLXArray::from_raw(handle))
+}
+
+fn fft_nd(
+    f: FftNFn,
+    inverse_real: bool,
+    x: &MLXArray,
+    s: Option<&[i32]>,
+    axes: Option<&[i32]>,
+    stream: &MLXStream,
+) -> Result<MLXArray, MLXError> {
+    let ndim = x.ndim() as i32;
+    let axes: Vec<i32> = match (axes, s) {
+        (Some(axes), _) => axes.to_vec(),
+        (None, Some(s)) => (ndim - s.len() as i32..ndim).collect(),
+        (None, None) => (0..ndim).collect(),
+    };
+    let s: Vec<i32> = match s {
+        Some(s) => s.to_vec(),
+        None => {
+            let last = axes.len().saturating_sub(1);
+            let mut sizes = Vec::with_capacity(axes.len());
+            for (i, axis) in axes.iter().enumerate() {
+                sizes.push(default_size(x, resolve_axis(x, *axis)?, inverse_real && i == last));
+            }
+            sizes
+        }
+    };
+    if s.len() != axes.len() {
+        return Err(MLXError::InvalidArgument(format!(
+            "got {} sizes for {} axes",
+            s.len(),
+            axes.len()
+        )));
+    }
+    let handle = guard(|| unsafe {
+        f(
+            x.as_ptr(),
+            s.as_ptr(),
+            s.len(),
+            axes.as_ptr(),
+            axes.len(),
+            stream.as_ptr(),
+        )
+    })?;
+    Ok(MLXArray::from_raw(handle))
+}
+
+macro_rules! impl_fft {
+    ($name:ident, $mlx_func:ident, $inverse_real:expr, $doc:literal) => {
+        paste::paste! {
+            #[doc = $doc]
+            pub fn $name(x: &MLXArray, n: Option<i32>, axis: i32) -> MLXArray {
+                [<try_ $name>](x, n, axis).unwrap()
+            }
+
+            pub fn [<$name _on>](x: &MLXArray, n: Option<i32>, axis: i32, stream: &MLXStream) -> MLXArray {
+                [<try_ $name _on>](x, n, axis, stream).unwrap()
+            }
+
+            pub fn [<try_ $name>](x: &MLXArray, n: Option<i32>, axis: i32) -> Result<MLXArray, MLXError> {
+                [<try_ $name _on>](x, n, axis, &get_default_stream())
+            }
+
+            pub fn [<try_ $name _on>](x: &MLXArray, n: Option<i32>, axis: i32, stream: &MLXStream) -> Result<MLXArray, MLXError> {
+                fft_1d($mlx_func, $inverse_real, x, n, axis, stream)
+            }
+        }
+    };
+}
+
+macro_rules! impl_fftn {
+    ($name:ident, $name2:ident, $mlx_func:ident, $inverse_real:expr, $doc:literal) => {
+        paste::paste! {
+            #[doc = $doc]
+            ///
+            /// Transforms over `axes`, all axes if `None` (the last `s.len()` ones if `s` is given).
+            pub fn $name(x: &MLXArray, s: Option<&[i32]>, axes: Option<&[i32]>) -> MLXArray {
+                [<try_ $name>](x, s, axes).unwrap()
+            }
+
+            pub fn [<$name _on>](x: &MLXArray, s: Option<&[i32]>, axes: Option<&[i32]>, stream: &MLXStream) -> MLXArray {
+                [<try_ $name _on>](x, s, axes, stream).unwrap()
+            }
+
+            pub fn [<try_ $name>](x: &MLXArray, s: Option<&[i32]>, axes: Option<&[i32]>) -> Result<MLXArray, MLXError> {
+                [<try_ $name _on>](x, s, axes, &get_default_stream())
+            }
+
+            pub fn [<try_ $name _on>](x: &MLXArray, s: Option<&[i32]>, axes: Option<&[i32]>, stream: &MLXStream) -> Result<MLXArray, MLXError> {
+                fft_nd($mlx_func, $inverse_real, x, s, axes, stream)
+            }
+
+            #[doc = $doc]
+            ///
+            /// Transforms over the last two axes.
+            pub fn $name2(x: &MLXArray, s: Option<&[i32]>) -> MLXArray {
+                [<try_ $name2>](x, s).unwrap()
+            }
+
+            pub fn [<$name2 _on>](x: &MLXArray, s: Option<&[i32]>, stream: &MLXStream) -> MLXArray {
+                [<try_ $name2 _on>](x, s, stream).unwrap()
+            }
+
+            pub fn [<try_ $name2>](x: &MLXArray, s: Option<&[i32]>) -> Result<MLXArray, MLXError> {
+                [<try_ $name2 _on>](x, s, &get_default_stream())
+            }
+
+            pub fn [<try_ $name2 _on>](x: &MLXArray, s: Option<&[i32]>, stream: &MLXStream) -> Result<MLXArray, MLXError> {
+                fft_nd($mlx_func, $inverse_real, x, s, Some(&[-2, -1]), stream)
+            }
+        }
+    };
+}
+
+impl_fft!(fft, mlx_fft_fft, false, "One dimensional discrete Fourier transform along `axis`.");
+impl_fft!(ifft, mlx_fft_ifft, false, "Inverse of [`fft`].");
+impl_fft!(rfft, mlx_fft_rfft, false, "Fourier transform of a real input, only the `n / 2 + 1` non negative frequencies are returned.");
+impl_fft!(irfft, mlx_fft_irfft, true, "Inverse of [`rfft`], returns a real array of size `n`, `2 * (len - 1)` by default.");
+
+impl_fftn!(fftn, fft2, mlx_fft_fftn, false, "Multi dimensional discrete Fourier transform.");
+impl_fftn!(ifftn, ifft2, mlx_fft_ifftn, false, "Inverse of [`fftn`].");
+impl_fftn!(rfftn, rfft2, mlx_fft_rfftn, false, "Multi dimensional Fourier transform of a real input, the last axis keeps the non negative frequencies only.");
+impl_fftn!(irfftn, irfft2, mlx_fft_irfftn, true, "Inverse of [`rfftn`].");
+
+#[cfg(test)]
+mod tests {
+    use num_complex::Complex32;
+
+    use crate::array::MLXArray;
+    use crate::fft::{fft, fft2, fftn, ifft, irfft, irfftn, rfft, rfftn, try_fftn};
+
+    #[test]
+    fn test_fft_of_impulse() {
+        let x = MLXArray::array(&[1.0f32, 0.0, 0.0, 0.0], &[4]);
+        let y = fft(&x, None, -1);
+        assert_eq!(&[Complex32::new(1.0, 0.0); 4], y.to_slice::<Complex32>().unwrap());
+        let back = ifft(&y, None, -1);
+        assert_eq!(Complex32::new(1.0, 0.0), back.to_slice::<Complex32>().unwrap()[0]);
+        assert_eq!(&[8], fft(&x, Some(8), 0).shape());
+    }
+
+    #[test]
+    fn test_rfft_round_trip() {
+        let x = MLXArray::array(&[1.0f32, 2.0, 3.0, 4.0, 5.0, 6.0], &[6]);
+        let y = rfft(&x, None, 0);
+        assert_eq!(&[4], y.shape());
+        assert_eq!(Complex32::new(21.0, 0.0), y.to_slice::<Complex32>().unwrap()[0]);
+        let back = irfft(&y, None, 0);
+        for (a, b) in back.to_slice::<f32>().unwrap().iter().zip([1.0f32, 2.0, 3.0, 4.0, 5.0, 6.0]) {
+            assert!((a - b).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn test_fftn() {
+        let x = MLXArray::array(&[1.0f32; 12], &[2, 2, 3]);
+        let y = fft2(&x, None);
+        assert_eq!(&[2, 2, 3], y.shape());
+        assert_eq!(Complex32::new(6.0, 0.0), y.to_slice::<Complex32>().unwrap()[0]);
+        assert_eq!(Complex32::new(12.0, 0.0), fftn(&x, None, None).to_slice::<Complex32>().unwrap()[0]);
+
+        let r = rfftn(&x, None, None);
+        assert_eq!(&[2, 2, 2], r.shape());
+        let back = irfftn(&r, Some(&[2, 2, 3]), None);
+        assert_eq!(&[2, 2, 3], back.shape());
+
+        assert!(try_fftn(&x, Some(&[2, 2]), Some(&[0])).is_err());
+    }
+}
@@ -1,26 +1,81 @@
-use crate::{array::MLXArray, r#type::ScalarMlxType};
+use crate::{array::MLXArray, error::MLXError, r#type::{Dtype, ScalarMlxType}};
 use core::slice;
 
 impl MLXArray {
-    pub fn to_scalar<T: ScalarMlxType>(&self) -> Result<T, ()> {
-        assert!(<T as ScalarMlxType>::mlx_array_dtype == self.dtype());
-        self.eval();
+    fn check_dtype<T: ScalarMlxType>(&self) -> Result<(), MLXError> {
+        let expected = Dtype::of::<T>();
+        let actual = self.dtype();
+        if expected != actual {
+            return Err(MLXError::DtypeMismatch { expected, actual });
+        }
+        Ok(())
+    }
+
+    pub fn to_scalar<T: ScalarMlxType>(&self) -> Result<T, MLXError> {
+        self.check_dtype::<T>()?;
+        if self.size() != 1 {
+            return Err(MLXError::InvalidArgument(format!(
+                "to_scalar requires an array with one element, got shape {:?}",
+                self.shape()
+            )));
+        }
+        self.try_eval()?;
         let r = unsafe { <T as ScalarMlxType>::to_scalar(self.as_ptr()) };
         Ok(r)
     }
 
-    pub fn to_slice<T: ScalarMlxType>(&self) -> Result<&[T], ()> {
-        assert!(<T as ScalarMlxType>::mlx_array_dtype == self.dtype());
-        self.eval();
+    pub fn to_slice<T: ScalarMlxType>(&self) -> Result<&[T], MLXError> {
+        self.check_dtype::<T>()?;
+        self.try_eval()?;
         unsafe {
             let ptr = <T as ScalarMlxType>::to_slice(self.as_ptr());
             let len = self.size();
-            if ptr.is_null() || len == 0 {
-                return Err(());
+            if len == 0 {
+                return Ok(&[]);
+            }
+            if ptr.is_null() {
+                return Err(MLXError::Backend("array data is not available".into()));
             }
             Ok(slice::from_raw_parts(ptr, len))
         }
     }
+
+    /// The array with its elements laid out in row-major order, copying it if it is strided.
+    pub(crate) fn to_row_major(&self) -> Result<MLXArray, MLXError> {
+        self.try_eval()?;
+        let mut expected = 1;
+        for (dim, stride) in self.shape().iter().zip(self.strides()).rev() {
+            if *dim != 1 && *stride != expected {
+                // reshaping a strided array copies it
+                return self.try_reshape(&[-1])?.try_reshape(self.shape());
+            }
+            expected *= *dim as usize;
+        }
+        Ok(self.clone())
+    }
+
+    /// The raw bytes of a row-major array, see [`MLXArray::to_row_major`].
+    pub(crate) fn as_bytes(&self) -> Result<&[u8], MLXError> {
+        fn bytes_of<T: ScalarMlxType>(array: &MLXArray) -> Result<&[u8], MLXError> {
+            let data = array.to_slice::<T>()?;
+            Ok(unsafe { slice::from_raw_parts(data.as_ptr() as *const u8, std::mem::size_of_val(data)) })
+        }
+        match self.dtype() {
+            Dtype::Bool => bytes_of::<bool>(self),
+            Dtype::U8 => bytes_of::<u8>(self),
+            Dtype::U16 => bytes_of::<u16>(self),
+            Dtype::U32 => bytes_of::<u32>(self),
+            Dtype::U64 => bytes_of::<u64>(self),
+            Dtype::I8 => bytes_of::<i8>(self),
+            Dtype::I16 => bytes_of::<i16>(self),
+            Dtype::I32 => bytes_of::<i32>(self),
+            Dtype::I64 => bytes_of::<i64>(self),
+            Dtype::F16 => bytes_of::<half::f16>(self),
+            Dtype::BF16 => bytes_of::<half::bf16>(self),
+            Dtype::F32 => bytes_of::<f32>(self),
+            Dtype::Complex64 => bytes_of::<num_complex::Complex32>(self),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,690 @@
+use std::ops::{Range, RangeFrom, RangeFull, RangeInclusive, RangeTo, RangeToInclusive};
+
+use mlx_sys::{mlx_broadcast_to, mlx_slice, mlx_slice_update};
+
+use crate::array::MLXArray;
+use crate::array_op::try_where_condition_on;
+use crate::error::{guard, MLXError};
+use crate::stream::{get_default_stream, MLXStream};
+
+/// One component of a NumPy style index expression, see [`s!`](crate::s).
+#[derive(Clone, Debug)]
+pub enum ArrayIndex {
+    /// A single position, the axis is removed. Negative values count from the end.
+    Index(i32),
+    /// `start..stop` with a step, following Python slice semantics.
+    Slice {
+        start: Option<i32>,
+        stop: Option<i32>,
+        step: i32,
+    },
+    /// Inserts a new axis of size one.
+    NewAxis,
+    /// Expands to as many full slices as needed to index every axis.
+    Ellipsis,
+    /// Gathers along the axis with an integer array, the axis is replaced by the
+    /// shape of the array. Several array indices (and integers next to them) are
+    /// broadcast together and pick elements pointwise, like NumPy advanced indexing.
+    Array(MLXArray),
+}
+
+/// Inserts a new axis of size one, like `None` / `np.newaxis` in Python.
+#[derive(Clone, Copy, Debug)]
+pub struct NewAxis;
+
+/// Stands for `...` in Python.
+#[derive(Clone, Copy, Debug)]
+pub struct Ellipsis;
+
+impl ArrayIndex {
+    /// Sets the step of a slice, e.g. `ArrayIndex::from(1..5).step_by(2)` is `1:5:2`. Indexing
+    /// with a step of zero is an error.
+    pub fn step_by(self, step: i32) -> ArrayIndex {
+        match self {
+            ArrayIndex::Slice { start, stop, .. } => ArrayIndex::Slice { start, stop, step },
+            other => panic!("a step requires a range, got {:?}", other),
+        }
+    }
+
+    fn full() -> ArrayIndex {
+        ArrayIndex::Slice {
+            start: None,
+            stop: None,
+            step: 1,
+        }
+    }
+
+    fn consumes_axis(&self) -> bool {
+        !matches!(self, ArrayIndex::NewAxis | ArrayIndex::Ellipsis)
+    }
+}
+
+impl From<i32> for ArrayIndex {
+    fn from(value: i32) -> Self {
+        ArrayIndex::Index(value)
+    }
+}
+
+impl From<Range<i32>> for ArrayIndex {
+    fn from(value: Range<i32>) -> Self {
+        ArrayIndex::Slice {
+            start: Some(value.start),
+            stop: Some(value.end),
+            step: 1,
+        }
+    }
+}
+
+impl From<RangeFrom<i32>> for ArrayIndex {
+    fn from(value: RangeFrom<i32>) -> Self {
+        ArrayIndex::Slice {
+            start: Some(value.start),
+            stop: None,
+            step: 1,
+        }
+    }
+}
+
+impl From<RangeTo<i32>> for ArrayIndex {
+    fn from(value: RangeTo<i32>) -> Self {
+        ArrayIndex::Slice {
+            start: None,
+            stop: Some(value.end),
+            step: 1,
+        }
+    }
+}
+
+impl From<RangeInclusive<i32>> for ArrayIndex {
+    fn from(value: RangeInclusive<i32>) -> Self {
+        // an inclusive end of -1 means up to and including the last element
+        let stop = if *value.end() == -1 { None } else { Some(value.end() + 1) };
+        ArrayIndex::Slice {
+            start: Some(*value.start()),
+            stop,
+            step: 1,
+        }
+    }
+}
+
+impl From<RangeToInclusive<i32>> for ArrayIndex {
+    fn from(value: RangeToInclusive<i32>) -> Self {
+        let stop = if value.end == -1 { None } else { Some(value.end + 1) };
+        ArrayIndex::Slice {
+            start: None,
+            stop,
+            step: 1,
+        }
+    }
+}
+
+impl From<RangeFull> for ArrayIndex {
+    fn from(_: RangeFull) -> Self {
+        ArrayIndex::full()
+    }
+}
+
+impl From<NewAxis> for ArrayIndex {
+    fn from(_: NewAxis) -> Self {
+        ArrayIndex::NewAxis
+    }
+}
+
+impl From<Ellipsis> for ArrayIndex {
+    fn from(_: Ellipsis) -> Self {
+        ArrayIndex::Ellipsis
+    }
+}
+
+impl From<MLXArray> for ArrayIndex {
+    fn from(value: MLXArray) -> Self {
+        ArrayIndex::Array(value)
+    }
+}
+
+impl From<&MLXArray> for ArrayIndex {
+    fn from(value: &MLXArray) -> Self {
+        ArrayIndex::Array(value.clone())
+    }
+}
+
+/// Builds an array of [`ArrayIndex`] from a NumPy like index expression.
+///
+/// Each component is anything convertible into [`ArrayIndex`], a range can be
+/// followed by `;step`.
+///
+/// ```ignore
+/// use mlx_rust::index::{Ellipsis, IndexOp, NewAxis};
+/// use mlx_rust::s;
+/// // x[..., 1:5:2, None]
+/// let y = x.i(s![Ellipsis, 1..5;2, NewAxis]);
+/// // x[::-1, -1]
+/// let z = x.i(s![..;-1, -1]);
+/// ```
+#[macro_export]
+macro_rules! s {
+    (@parse [$($acc:expr,)*]) => {
+        [$($acc,)*]
+    };
+    (@parse [$($acc:expr,)*] $r:expr ; $step:expr , $($rest:tt)*) => {
+        $crate::s!(@parse [$($acc,)* $crate::index::ArrayIndex::from($r).step_by($step),] $($rest)*)
+    };
+    (@parse [$($acc:expr,)*] $r:expr ; $step:expr) => {
+        $crate::s!(@parse [$($acc,)* $crate::index::ArrayIndex::from($r).step_by($step),])
+    };
+    (@parse [$($acc:expr,)*] $r:expr , $($rest:tt)*) => {
+        $crate::s!(@parse [$($acc,)* $crate::index::ArrayIndex::from($r),] $($rest)*)
+    };
+    (@parse [$($acc:expr,)*] $r:expr) => {
+        $crate::s!(@parse [$($acc,)* $crate::index::ArrayIndex::from($r),])
+    };
+    ($($t:tt)*) => {
+        $crate::s!(@parse [] $($t)*)
+    };
+}
+
+/// Python `slice.indices`: clamps `start` / `stop` for an axis of size `dim`
+/// and returns `(start, stop, len)`.
+fn slice_bounds(start: Option<i32>, stop: Option<i32>, step: i32, dim: i32) -> (i32, i32, i32) {
+    let (lower, upper) = if step > 0 { (0, dim) } else { (-1, dim - 1) };
+    let clamp = |v: i32| {
+        if v < 0 {
+            (v + dim).max(lower)
+        } else {
+            v.min(upper)
+        }
+    };
+    let start = start.map(clamp).unwrap_or(if step > 0 { lower } else { upper });
+    let stop = stop.map(clamp).unwrap_or(if step > 0 { upper } else { lower });
+    let len = if step > 0 && stop > start {
+        (stop - start + step - 1) / step
+    } else if step < 0 && start > stop {
+        (start - stop - step - 1) / -step
+    } else {
+        0
+    };
+    (start, stop, len)
+}
+
+fn normalize_index(index: i32, dim: i32, axis: usize) -> Result<i32, MLXError> {
+    let i = if index < 0 { index + dim } else { index };
+    if i < 0 || i >= dim {
+        return Err(MLXError::InvalidArgument(format!(
+            "index {} is out of bounds for axis {} with size {}",
+            index, axis, dim
+        )));
+    }
+    Ok(i)
+}
+
+/// Replaces the ellipsis (or the missing tail) with full slices so that every axis
+/// of an array with `ndim` dimensions is indexed exactly once.
+fn expand_ellipsis(indices: &[ArrayIndex], ndim: usize) -> Result<Vec<ArrayIndex>, MLXError> {
+    let consumed = indices.iter().filter(|i| i.consumes_axis()).count();
+    if consumed > ndim {
+        return Err(MLXError::InvalidArgument(format!(
+            "too many indices for array: array is {}-dimensional, but {} were indexed",
+            ndim, consumed
+        )));
+    }
+    let ellipsis_count = indices.iter().filter(|i| matches!(i, ArrayIndex::Ellipsis)).count();
+    if ellipsis_count > 1 {
+        return Err(MLXError::InvalidArgument(
+            "an index can only have a single ellipsis".into(),
+        ));
+    }
+    let fill = || (0..ndim - consumed).map(|_| ArrayIndex::full());
+    let mut expanded = Vec::with_capacity(ndim);
+    for index in indices {
+        match index {
+            ArrayIndex::Slice { step: 0, .. } => {
+                return Err(MLXError::InvalidArgument("slice step cannot be zero".into()))
+            }
+            ArrayIndex::Ellipsis => expanded.extend(fill()),
+            other => expanded.push(other.clone()),
+        }
+    }
+    if ellipsis_count == 0 {
+        expanded.extend(fill());
+    }
+    Ok(expanded)
+}
+
+fn slice_raw(
+    x: &MLXArray,
+    start: &[i32],
+    stop: &[i32],
+    strides: &[i32],
+    stream: &MLXStream,
+) -> Result<MLXArray, MLXError> {
+    let handle = guard(|| unsafe {
+        mlx_slice(
+            x.as_ptr(),
+            start.as_ptr(),
+            start.len(),
+            stop.as_ptr(),
+            stop.len(),
+            strides.as_ptr(),
+            strides.len(),
+            stream.as_ptr(),
+        )
+    })?;
+    Ok(MLXArray::from_raw(handle))
+}
+
+pub(crate) fn slice_axis(
+    x: &MLXArray,
+    axis: usize,
+    start: i32,
+    stop: i32,
+    step: i32,
+    stream: &MLXStream,
+) -> Result<MLXArray, MLXError> {
+    let shape = x.shape();
+    let mut starts = vec![0; shape.len()];
+    let mut stops = shape.to_vec();
+    let mut strides = vec![1; shape.len()];
+    starts[axis] = start;
+    stops[axis] = stop;
+    strides[axis] = step;
+    slice_raw(x, &starts, &stops, &strides, stream)
+}
+
+/// NumPy advanced indexing: `advanced` pairs an axis of `x` with its index array.
+/// The arrays are broadcast together and their shape replaces the indexed axes, in
+/// place if the axes are adjacent and at the front otherwise.
+fn gather(x: &MLXArray, advanced: Vec<(usize, MLXArray)>, stream: &MLXStream) -> Result<MLXArray, MLXError> {
+    let (axes, arrays): (Vec<usize>, Vec<MLXArray>) = advanced.into_iter().unzip();
+    let arrays = MLXArray::try_broadcast_arrays_on(&arrays, stream)?;
+    let shape = x.shape().to_vec();
+    let rest: Vec<usize> = (0..shape.len()).filter(|a| !axes.contains(a)).collect();
+
+    // move the indexed axes to the front and flatten them into one
+    let perm: Vec<i32> = axes.iter().chain(&rest).map(|&a| a as i32).collect();
+    let mut flat_shape = vec![axes.iter().map(|&a| shape[a]).product::<i32>()];
+    flat_shape.extend(rest.iter().map(|&a| shape[a]));
+    let x = x.try_transpose_on(&perm, stream)?.try_reshape_on(&flat_shape, stream)?;
+
+    // row major position in the flattened axes, negative indices count from the end
+    let zero = MLXArray::from(0);
+    let mut flat: Option<MLXArray> = None;
+    let mut in_range: Option<MLXArray> = None;
+    for (&axis, index) in axes.iter().zip(&arrays) {
+        let dim = MLXArray::from(shape[axis]);
+        let negative = index.try_less_on(&zero, stream)?;
+        let index = try_where_condition_on(&negative, &index.try_add_on(&dim, stream)?, index, stream)?;
+        let valid = index
+            .try_greater_equal_on(&zero, stream)?
+            .try_logical_and_on(&index.try_less_on(&dim, stream)?, stream)?;
+        (flat, in_range) = match (flat, in_range) {
+            (Some(flat), Some(in_range)) => (
+                Some(flat.try_mul_on(&dim, stream)?.try_add_on(&index, stream)?),
+                Some(in_range.try_logical_and_on(&valid, stream)?),
+            ),
+            _ => (Some(index), Some(valid)),
+        };
+    }
+    let (Some(mut flat), Some(in_range)) = (flat, in_range) else {
+        unreachable!("at least one array index")
+    };
+    if axes.len() > 1 {
+        // an out of range index must not land on another element of the flattened axes
+        flat = try_where_condition_on(&in_range, &flat, &MLXArray::from(x.dim(0)), stream)?;
+    }
+    let out = x.try_index_select_on(0, &flat, stream)?;
+
+    let adjacent = axes.windows(2).all(|w| w[1] == w[0] + 1);
+    if !adjacent || axes[0] == 0 {
+        return Ok(out);
+    }
+    let gathered = flat.ndim();
+    let front = axes[0];
+    let perm: Vec<i32> = (gathered..gathered + front)
+        .chain(0..gathered)
+        .chain(gathered + front..out.ndim())
+        .map(|a| a as i32)
+        .collect();
+    out.try_transpose_on(&perm, stream)
+}
+
+impl MLXArray {
+    /// Indexes the array like `x[...]` in NumPy, see [`s!`](crate::s).
+    pub fn index(&self, indices: &[ArrayIndex]) -> MLXArray {
+        self.try_index(indices).unwrap()
+    }
+
+    pub fn index_on(&self, indices: &[ArrayIndex], stream: &MLXStream) -> MLXArray {
+        self.try_index_on(indices, stream).unwrap()
+    }
+
+    pub fn try_index(&self, indices: &[ArrayIndex]) -> Result<MLXArray, MLXError> {
+        self.try_index_on(indices, &get_default_stream())
+    }
+
+    pub fn try_index_on(&self, indices: &[ArrayIndex], stream: &MLXStream) -> Result<MLXArray, MLXError> {
+        let indices = expand_ellipsis(indices, self.ndim())?;
+        // with an array index, integers become 0-d index arrays and take part in the gather
+        let gathering = indices.iter().any(|i| matches!(i, ArrayIndex::Array(_)));
+        let mut advanced = Vec::new();
+        let mut x = self.clone();
+        let mut axis = 0;
+        for index in indices {
+            match index {
+                ArrayIndex::Index(i) => {
+                    let i = normalize_index(i, x.dim(axis as i32), axis)?;
+                    if gathering {
+                        advanced.push((axis, MLXArray::from(i)));
+                        axis += 1;
+                    } else {
+                        x = slice_axis(&x, axis, i, i + 1, 1, stream)?;
+                        let mut shape = x.shape().to_vec();
+                        shape.remove(axis);
+                        x = x.try_reshape_on(&shape, stream)?;
+                    }
+                }
+                ArrayIndex::Slice { start, stop, step } => {
+                    let (start, stop, len) = slice_bounds(start, stop, step, x.dim(axis as i32));
+                    x = if step > 0 {
+                        slice_axis(&x, axis, start, stop, step, stream)?
+                    } else {
+                        let positions: Vec<i32> = (0..len).map(|k| start + k * step).collect();
+                        let positions = MLXArray::array(&positions, &[len]);
+                        x.try_index_select_on(axis as i32, &positions, stream)?
+                    };
+                    axis += 1;
+                }
+                ArrayIndex::NewAxis => {
+                    x = x.try_expand_dims_on(&[axis as i32], stream)?;
+                    axis += 1;
+                }
+                ArrayIndex::Array(positions) => {
+                    advanced.push((axis, positions));
+                    axis += 1;
+                }
+                ArrayIndex::Ellipsis => unreachable!("ellipsis is expanded above"),
+            }
+        }
+        if advanced.is_empty() {
+            Ok(x)
+        } else {
+            gather(&x, advanced, stream)
+        }
+    }
+
+    /// Returns a copy of the array with the region selected by `indices` replaced by
+    /// `update`, like `x[...] = update` in NumPy. `update` is broadcast to the shape
+    /// of the region. Only integer, slice (with a positive step), new axis and
+    /// ellipsis components are supported.
+    pub fn slice_update(&self, update: &MLXArray, indices: &[ArrayIndex]) -> MLXArray {
+        self.try_slice_update(update, indices).unwrap()
+    }
+
+    pub fn slice_update_on(&self, update: &MLXArray, indices: &[ArrayIndex], stream: &MLXStream) -> MLXArray {
+        self.try_slice_update_on(update, indices, stream).unwrap()
+    }
+
+    pub fn try_slice_update(&self, update: &MLXArray, indices: &[ArrayIndex]) -> Result<MLXArray, MLXError> {
+        self.try_slice_update_on(update, indices, &get_default_stream())
+    }
+
+    pub fn try_slice_update_on(
+        &self,
+        update: &MLXArray,
+        indices: &[ArrayIndex],
+        stream: &MLXStream,
+    ) -> Result<MLXArray, MLXError> {
+        let indices = expand_ellipsis(indices, self.ndim())?;
+        let shape = self.shape();
+        let mut starts = Vec::with_capacity(shape.len());
+        let mut stops = Vec::with_capacity(shape.len());
+        let mut strides = Vec::with_capacity(shape.len());
+        // shape of `self.index(indices)` and the same region with indexed axes kept
+        let mut indexed_shape = Vec::new();
+        let mut region_shape = Vec::with_capacity(shape.len());
+        let mut axis = 0;
+        for index in indices {
+            match index {
+                ArrayIndex::Index(i) => {
+                    let i = normalize_index(i, shape[axis], axis)?;
+                    starts.push(i);
+                    stops.push(i + 1);
+                    strides.push(1);
+                    region_shape.push(1);
+                    axis += 1;
+                }
+                ArrayIndex::Slice { start, stop, step } => {
+                    if step < 0 {
+                        return Err(MLXError::InvalidArgument(
+                            "slice_update does not support negative steps".into(),
+                        ));
+                    }
+                    let (start, stop, len) = slice_bounds(start, stop, step, shape[axis]);
+                    starts.push(start);
+                    stops.push(stop.max(start));
+                    strides.push(step);
+                    indexed_shape.push(len);
+                    region_shape.push(len);
+                    axis += 1;
+                }
+                ArrayIndex::NewAxis => indexed_shape.push(1),
+                ArrayIndex::Array(_) => {
+                    return Err(MLXError::InvalidArgument(
+                        "slice_update does not support array indices".into(),
+                    ))
+                }
+                ArrayIndex::Ellipsis => unreachable!("ellipsis is expanded above"),
+            }
+        }
+
+        let handle = guard(|| unsafe {
+            mlx_broadcast_to(
+                update.as_ptr(),
+                indexed_shape.as_ptr(),
+                indexed_shape.len(),
+                stream.as_ptr(),
+            )
+        })?;
+        let update = MLXArray::from_raw(handle).try_reshape_on(&region_shape, stream)?;
+        let handle = guard(|| unsafe {
+            mlx_slice_update(
+                self.as_ptr(),
+                update.as_ptr(),
+                starts.as_ptr(),
+                starts.len(),
+                stops.as_ptr(),
+                stops.len(),
+                strides.as_ptr(),
+                strides.len(),
+                stream.as_ptr(),
+            )
+        })?;
+        Ok(MLXArray::from_raw(handle))
+    }
+}
+
+/// `x.i(...)` indexing, accepting a single component, a tuple of components or the
+/// output of [`s!`](crate::s).
+pub trait IndexOp<I> {
+    fn i(&self, index: I) -> MLXArray {
+        self.try_i(index).unwrap()
+    }
+
+    fn try_i(&self, index: I) -> Result<MLXArray, MLXError>;
+}
+
+impl<T: Into<ArrayIndex>> IndexOp<T> for MLXArray {
+    fn try_i(&self, index: T) -> Result<MLXArray, MLXError> {
+        self.try_index(&[index.into()])
+    }
+}
+
+impl IndexOp<&[ArrayIndex]> for MLXArray {
+    fn try_i(&self, index: &[ArrayIndex]) -> Result<MLXArray, MLXError> {
+        self.try_index(index)
+    }
+}
+
+impl<const N: usize> IndexOp<[ArrayIndex; N]> for MLXArray {
+    fn try_i(&self, index: [ArrayIndex; N]) -> Result<MLXArray, MLXError> {
+        self.try_index(&index)
+    }
+}
+
+impl IndexOp<Vec<ArrayIndex>> for MLXArray {
+    fn try_i(&self, index: Vec<ArrayIndex>) -> Result<MLXArray, MLXError> {
+        self.try_index(&index)
+    }
+}
+
+macro_rules! impl_index_op_for_tuple {
+    ($($T:ident),*) => {
+        paste::paste! {
+            impl<$( $T: Into<ArrayIndex> ),*> IndexOp<($( $T ),*)> for MLXArray {
+                fn try_i(&self, index: ($( $T ),*)) -> Result<MLXArray, MLXError> {
+                    let ($([<$T:lower>],)*) = index;
+                    self.try_index(&[$([<$T:lower>].into(),)*])
+                }
+            }
+        }
+    };
+}
+
+impl_index_op_for_tuple!(T1, T2);
+impl_index_op_for_tuple!(T1, T2, T3);
+impl_index_op_for_tuple!(T1, T2, T3, T4);
+impl_index_op_for_tuple!(T1, T2, T3, T4, T5);
+
+#[cfg(test)]
+mod tests {
+    use crate::array::MLXArray;
+    use crate::device::MLXDevice;
+    use crate::index::{Ellipsis, IndexOp, NewAxis};
+    use crate::stream::MLXStream;
+
+    fn arange_2d() -> MLXArray {
+        // [[0, 1, 2, 3], [4, 5, 6, 7], [8, 9, 10, 11]]
+        let data: Vec<i32> = (0..12).collect();
+        MLXArray::array(&data, &[3, 4])
+    }
+
+    #[test]
+    fn test_index_and_slice() {
+        let x = arange_2d();
+        let r = x.i((1, 1..3));
+        assert_eq!(&[2], r.shape());
+        assert_eq!(&[5, 6], r.to_slice::<i32>().unwrap());
+
+        let r = x.i(-1);
+        assert_eq!(&[8, 9, 10, 11], r.to_slice::<i32>().unwrap());
+    }
+
+    #[test]
+    fn test_step_and_negative_step() {
+        let x = arange_2d();
+        let r = x.i(s![0, 0..4;2]);
+        assert_eq!(&[0, 2], r.to_slice::<i32>().unwrap());
+
+        let r = x.i(s![..;-1, 0]);
+        assert_eq!(&[8, 4, 0], r.to_slice::<i32>().unwrap());
+    }
+
+    #[test]
+    fn test_new_axis_and_ellipsis() {
+        let x = arange_2d();
+        let r = x.i(s![Ellipsis, 1..3, NewAxis]);
+        assert_eq!(&[3, 2, 1], r.shape());
+        let r = x.i((NewAxis, Ellipsis, 0));
+        assert_eq!(&[1, 3], r.shape());
+        assert_eq!(&[0, 4, 8], r.to_slice::<i32>().unwrap());
+    }
+
+    #[test]
+    fn test_array_index() {
+        let x = arange_2d();
+        let idx = MLXArray::array(&[2, 0], &[2]);
+        let r = x.i((.., &idx));
+        assert_eq!(&[3, 2], r.shape());
+        assert_eq!(&[2, 0, 6, 4, 10, 8], r.to_slice::<i32>().unwrap());
+    }
+
+    #[test]
+    fn test_advanced_index() {
+        let x = arange_2d();
+        // x[[0, 2], [1, -1]] picks (0, 1) and (2, 3)
+        let rows = MLXArray::array(&[0, 2], &[2]);
+        let cols = MLXArray::array(&[1, -1], &[2]);
+        let r = x.i((&rows, &cols));
+        assert_eq!(&[2], r.shape());
+        assert_eq!(&[1, 11], r.to_slice::<i32>().unwrap());
+
+        // x[[[0], [2]], [1, 3]] broadcasts to a 2 x 2 result
+        let rows = MLXArray::array(&[0, 2], &[2, 1]);
+        let cols = MLXArray::array(&[1, 3], &[2]);
+        let r = x.i((&rows, &cols));
+        assert_eq!(&[2, 2], r.shape());
+        assert_eq!(&[1, 3, 9, 11], r.to_slice::<i32>().unwrap());
+
+        // an integer next to an array index takes part in the gather
+        let r = x.i((&rows, 1));
+        assert_eq!(&[2, 1], r.shape());
+        assert_eq!(&[1, 9], r.to_slice::<i32>().unwrap());
+    }
+
+    #[test]
+    fn test_advanced_index_placement() {
+        let data: Vec<i32> = (0..24).collect();
+        let x = MLXArray::array(&data, &[2, 3, 4]);
+        let idx = MLXArray::array(&[0, 1], &[2]);
+
+        // adjacent array indices keep their place
+        let r = x.i((.., &idx, &idx));
+        assert_eq!(&[2, 2], r.shape());
+        assert_eq!(&[0, 5, 12, 17], r.to_slice::<i32>().unwrap());
+
+        // separated ones move to the front
+        let r = x.i((&idx, .., &idx));
+        assert_eq!(&[2, 3], r.shape());
+        assert_eq!(&[0, 4, 8, 13, 17, 21], r.to_slice::<i32>().unwrap());
+    }
+
+    #[test]
+    fn test_index_on_stream() {
+        let cpu = MLXStream::default_stream(MLXDevice::cpu());
+        let x = arange_2d();
+        let r = x.index_on(&s![1.., ..;2], &cpu);
+        assert_eq!(&[4, 6, 8, 10], r.to_slice::<i32>().unwrap());
+        let r = x.slice_update_on(&MLXArray::from(0), &s![0], &cpu);
+        assert_eq!(&[0, 0, 0, 0], r.i(0).to_slice::<i32>().unwrap());
+    }
+
+    #[test]
+    fn test_negative_array_index() {
+        let x = arange_2d();
+        let r = x.i((&MLXArray::array(&[-1, 0], &[2]), &MLXArray::array(&[-4, -1], &[2])));
+        assert_eq!(&[8, 3], r.to_slice::<i32>().unwrap());
+        let r = x.i((.., &MLXArray::array(&[-1], &[1])));
+        assert_eq!(&[3, 7, 11], r.to_slice::<i32>().unwrap());
+    }
+
+    #[test]
+    fn test_out_of_bounds() {
+        let x = arange_2d();
+        assert!(x.try_i(s![0..4;0]).is_err());
+        assert!(x.try_slice_update(&MLXArray::from(0), &s![..;0]).is_err());
+        assert!(x.try_i(3).is_err());
+        assert!(x.try_i((0, 0, 0)).is_err());
+    }
+
+    #[test]
+    fn test_slice_update() {
+        let x = arange_2d();
+        let r = x.slice_update(&MLXArray::from(-1), &s![.., 1]);
+        assert_eq!(
+            &[0, -1, 2, 3, 4, -1, 6, 7, 8, -1, 10, 11],
+            r.to_slice::<i32>().unwrap()
+        );
+        let update = MLXArray::array(&[20, 30], &[2]);
+        let r = x.slice_update(&update, &s![2, 2..]);
+        assert_eq!(&[8, 9, 20, 30], r.i(2).to_slice::<i32>().unwrap());
+    }
+}
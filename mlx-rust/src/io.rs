@@ -1,8 +1,16 @@
-use std::{ffi::CString, os::unix::ffi::OsStrExt, path::Path};
+use std::{collections::HashMap, ffi::CString, os::unix::ffi::OsStrExt, path::Path};
 
-use mlx_sys::{fclose, FILE, fopen, mlx_load_safetensors, mlx_map_string_to_array, mlx_map_string_to_array_, mlx_map_string_to_array_get, mlx_map_string_to_array_iterate, mlx_map_string_to_array_iterator, mlx_map_string_to_array_iterator_, mlx_map_string_to_array_iterator_end, mlx_map_string_to_array_iterator_key, mlx_map_string_to_array_iterator_next, mlx_map_string_to_array_iterator_value, mlx_map_string_to_string, mlx_map_string_to_string_, mlx_map_string_to_string_get, mlx_map_string_to_string_iterate, mlx_map_string_to_string_iterator, mlx_map_string_to_string_iterator_, mlx_map_string_to_string_iterator_end, mlx_map_string_to_string_iterator_key, mlx_map_string_to_string_iterator_next, mlx_map_string_to_string_iterator_value, mlx_safetensors, mlx_safetensors_, mlx_safetensors_data, mlx_safetensors_metadata};
+use mlx_sys::{fclose, FILE, fopen, mlx_load_safetensors, mlx_map_string_to_array, mlx_map_string_to_array_, mlx_map_string_to_array_get, mlx_map_string_to_array_iterate, mlx_map_string_to_array_iterator, mlx_map_string_to_array_iterator_, mlx_map_string_to_array_iterator_end, mlx_map_string_to_array_iterator_key, mlx_map_string_to_array_iterator_next, mlx_map_string_to_array_iterator_value, mlx_map_string_to_array_insert, mlx_map_string_to_array_new, mlx_map_string_to_string, mlx_map_string_to_string_, mlx_map_string_to_string_get, mlx_map_string_to_string_iterate, mlx_map_string_to_string_iterator, mlx_map_string_to_string_iterator_, mlx_map_string_to_string_iterator_end, mlx_map_string_to_string_iterator_key, mlx_map_string_to_string_iterator_next, mlx_map_string_to_string_iterator_value, mlx_map_string_to_string_insert, mlx_map_string_to_string_new, mlx_safetensors, mlx_safetensors_, mlx_safetensors_data, mlx_safetensors_metadata, mlx_save_safetensors};
 
-use crate::{MLXArray, object::MLXObject, stream::MLXStream, string::MLXString};
+use crate::{MLXArray, error::{guard, guard_unit, MLXError}, object::MLXObject, stream::{get_default_stream, MLXStream}, string::MLXString};
+
+pub mod gguf;
+pub mod mmap;
+pub mod shard;
+
+pub use crate::npy::{
+    load_npy, load_npz, save_npy, save_npz, try_load_npy, try_load_npz, try_save_npy, try_save_npz,
+};
 
 pub struct SafeTensors {
     handle: MLXObject<mlx_safetensors_>,
@@ -14,6 +22,16 @@ impl SafeTensorMetadata {
         Self(MLXObject::from_raw(handle))
     }
 
+    fn new() -> Self {
+        Self::from_raw(unsafe { mlx_map_string_to_string_new() })
+    }
+
+    fn insert(&mut self, key: &str, value: &str) {
+        let key = MLXString::new(key);
+        let value = MLXString::new(value);
+        unsafe { mlx_map_string_to_string_insert(self.0.as_ptr(), key.as_ptr(), value.as_ptr()) };
+    }
+
     pub fn get(&self, key: &str) -> Option<MLXString> {
         let key = MLXString::new(key);
         let handle = unsafe { mlx_map_string_to_string_get(self.0.as_ptr(), key.as_ptr()) };
@@ -69,6 +87,15 @@ impl SafeTensorData {
         Self(MLXObject::from_raw(handle))
     }
 
+    fn new() -> Self {
+        Self::from_raw(unsafe { mlx_map_string_to_array_new() })
+    }
+
+    fn insert(&mut self, key: &str, value: &MLXArray) {
+        let key = MLXString::new(key);
+        unsafe { mlx_map_string_to_array_insert(self.0.as_ptr(), key.as_ptr(), value.as_ptr()) };
+    }
+
     pub fn get(&self, key: &str) -> Option<MLXArray> {
         let key = MLXString::new(key);
         let handle = unsafe { mlx_map_string_to_array_get(self.0.as_ptr(), key.as_ptr()) };
@@ -142,11 +169,26 @@ impl Drop for CFile {
 }
 
 impl SafeTensors {
-    pub fn new(path: &str, stream: MLXStream) -> Self {
+    pub fn new(path: &str) -> Self {
+        Self::try_new(path).unwrap()
+    }
+
+    pub fn new_on(path: &str, stream: &MLXStream) -> Self {
+        Self::try_new_on(path, stream).unwrap()
+    }
+
+    /// Loads a safetensors file, returning an error for a missing or malformed file
+    /// instead of aborting.
+    pub fn try_new(path: &str) -> Result<Self, MLXError> {
+        Self::try_new_on(path, &get_default_stream())
+    }
+
+    pub fn try_new_on(path: &str, stream: &MLXStream) -> Result<Self, MLXError> {
         // let file = CFile::open(path);
+        std::fs::metadata(path)?;
         let path = MLXString::new(path);
-        let handle = unsafe { mlx_load_safetensors(path.as_ptr(), stream.as_ptr()) };
-        SafeTensors::from_raw(handle)
+        let handle = guard(|| unsafe { mlx_load_safetensors(path.as_ptr(), stream.as_ptr()) })?;
+        Ok(SafeTensors::from_raw(handle))
     }
 
     fn from_raw(handle: mlx_safetensors) -> Self {
@@ -172,15 +214,54 @@ impl SafeTensors {
     }
 }
 
+/// Writes `arrays` to a safetensors file, keeping their dtypes (including `f16` and `bf16`).
+///
+/// `metadata` is stored in the header, with a `format: mlx` entry added unless it sets a format
+/// itself.
+pub fn save_safetensors<P: AsRef<Path>>(
+    path: P,
+    arrays: &HashMap<String, MLXArray>,
+    metadata: &HashMap<String, String>,
+) {
+    try_save_safetensors(path, arrays, metadata).unwrap()
+}
+
+pub fn try_save_safetensors<P: AsRef<Path>>(
+    path: P,
+    arrays: &HashMap<String, MLXArray>,
+    metadata: &HashMap<String, String>,
+) -> Result<(), MLXError> {
+    let path = path.as_ref().to_str().ok_or_else(|| {
+        MLXError::InvalidArgument(format!("non utf-8 path {:?}", path.as_ref()))
+    })?;
+    let mut data = SafeTensorData::new();
+    for (name, array) in arrays {
+        data.insert(name, array);
+    }
+    let mut header = SafeTensorMetadata::new();
+    if !metadata.contains_key("format") {
+        header.insert("format", "mlx");
+    }
+    for (key, value) in metadata {
+        header.insert(key, value);
+    }
+    let path = MLXString::new(path);
+    guard_unit(|| unsafe { mlx_save_safetensors(path.as_ptr(), data.0.as_ptr(), header.0.as_ptr()) })
+}
+
 #[cfg(test)]
 mod tests {
     use std::{collections::HashMap, fs, slice::from_raw_parts};
 
     use safetensors::{Dtype, serialize, tensor::TensorView};
 
-    use crate::stream::get_default_stream;
+    use half::{bf16, f16};
 
-    use super::SafeTensors;
+    use crate::error::MLXError;
+    use crate::r#type::Dtype as MLXDtype;
+    use crate::MLXArray;
+
+    use super::{save_safetensors, SafeTensors};
 
     #[test]
     fn test_load_safetensor() {
@@ -202,7 +283,7 @@ mod tests {
 
         let data = fs::read(file_path).unwrap();
         let raw_st = safetensors::SafeTensors::deserialize(&data).unwrap();
-        let st = SafeTensors::new(file_path.to_str().unwrap(), get_default_stream());
+        let st = SafeTensors::new(file_path.to_str().unwrap());
 
         for (i, (key, value)) in st.data().into_iter().enumerate() {
             let raw_data = raw_st.tensor(&key).unwrap();
@@ -212,6 +293,54 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_load_missing_safetensor() {
+        let dir = tempfile::tempdir().unwrap();
+        let file_path = dir.path().join("missing.safetensors");
+        let r = SafeTensors::try_new(file_path.to_str().unwrap());
+        assert!(matches!(r, Err(MLXError::Io(_))));
+    }
+
+    #[test]
+    fn test_load_malformed_safetensor() {
+        let dir = tempfile::tempdir().unwrap();
+        let file_path = dir.path().join("malformed.safetensors");
+        fs::write(&file_path, b"not a safetensors file").unwrap();
+        let r = SafeTensors::try_new(file_path.to_str().unwrap());
+        assert!(r.is_err());
+    }
+
+    #[test]
+    fn test_save_safetensors() {
+        let arrays: HashMap<String, MLXArray> = [
+            ("a.weight".to_string(), MLXArray::array(&[1.0f32, 2.0, 3.0, 4.0], &[2, 2])),
+            ("a.half".to_string(), MLXArray::array(&[f16::from_f32(0.5)], &[1])),
+            ("b.0.bf".to_string(), MLXArray::array(&[bf16::from_f32(-1.5), bf16::ONE], &[2])),
+        ]
+        .into_iter()
+        .collect();
+        let metadata = [("step".to_string(), "10".to_string())].into_iter().collect();
+        let dir = tempfile::tempdir().unwrap();
+        let file_path = dir.path().join("arrays.safetensors");
+        save_safetensors(&file_path, &arrays, &metadata);
+
+        let st = SafeTensors::new(file_path.to_str().unwrap());
+        let metadata = st.metadata();
+        assert_eq!("mlx", metadata.get("format").unwrap().to_string().unwrap());
+        assert_eq!("10", metadata.get("step").unwrap().to_string().unwrap());
+
+        let data = st.data();
+        let weight = data.get("a.weight").unwrap();
+        assert_eq!(&[2, 2], weight.shape());
+        assert_eq!(&[1.0, 2.0, 3.0, 4.0], weight.to_slice::<f32>().unwrap());
+        let half = data.get("a.half").unwrap();
+        assert_eq!(MLXDtype::F16, half.dtype());
+        assert_eq!(&[f16::from_f32(0.5)], half.to_slice::<f16>().unwrap());
+        let bf = data.get("b.0.bf").unwrap();
+        assert_eq!(MLXDtype::BF16, bf.dtype());
+        assert_eq!(&[bf16::from_f32(-1.5), bf16::ONE], bf.to_slice::<bf16>().unwrap());
+    }
+
     fn convert_slice<T: Clone>(data: &[u8]) -> Vec<T> {
         let size_in_bytes = std::mem::size_of::<T>();
         let elem_count = data.len() / size_in_bytes;
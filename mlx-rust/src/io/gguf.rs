@@ -0,0 +1,959 @@
+//! Reading and writing GGUF (v2 and v3), the checkpoint format of llama.cpp.
+//!
+//! `F32`, `F16`, `BF16` and integer tensors load as plain arrays. `Q4_0`, `Q4_1` and `Q8_0` load
+//! in the affine quantized layout of MLX with groups of 32 elements: `uint32` words packing the
+//! quantized values (lowest bits first), plus one scale and one bias per group, so that
+//! `w = scale * q + bias`.
+
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use half::f16;
+
+use crate::error::MLXError;
+use crate::r#type::Dtype;
+use crate::MLXArray;
+
+const MAGIC: &[u8; 4] = b"GGUF";
+const VERSION: u32 = 3;
+const DEFAULT_ALIGNMENT: u64 = 32;
+const ALIGNMENT_KEY: &str = "general.alignment";
+/// Elements per block of the supported quantized types, also the MLX group size.
+const BLOCK_SIZE: usize = 32;
+/// How deep metadata arrays may nest, so a crafted file cannot overflow the stack.
+const MAX_ARRAY_DEPTH: usize = 16;
+
+fn invalid(msg: impl Into<String>) -> MLXError {
+    MLXError::InvalidArgument(msg.into())
+}
+
+/// A metadata value.
+#[derive(Clone, Debug, PartialEq)]
+pub enum GgufValue {
+    U8(u8),
+    I8(i8),
+    U16(u16),
+    I16(i16),
+    U32(u32),
+    I32(i32),
+    F32(f32),
+    Bool(bool),
+    String(String),
+    /// The elements all have the same type.
+    Array(Vec<GgufValue>),
+    U64(u64),
+    I64(i64),
+    F64(f64),
+}
+
+impl GgufValue {
+    fn type_id(&self) -> u32 {
+        match self {
+            GgufValue::U8(_) => 0,
+            GgufValue::I8(_) => 1,
+            GgufValue::U16(_) => 2,
+            GgufValue::I16(_) => 3,
+            GgufValue::U32(_) => 4,
+            GgufValue::I32(_) => 5,
+            GgufValue::F32(_) => 6,
+            GgufValue::Bool(_) => 7,
+            GgufValue::String(_) => 8,
+            GgufValue::Array(_) => 9,
+            GgufValue::U64(_) => 10,
+            GgufValue::I64(_) => 11,
+            GgufValue::F64(_) => 12,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            GgufValue::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// The value of any integer type that fits in a `u64`.
+    pub fn as_u64(&self) -> Option<u64> {
+        match *self {
+            GgufValue::U8(v) => Some(v as u64),
+            GgufValue::U16(v) => Some(v as u64),
+            GgufValue::U32(v) => Some(v as u64),
+            GgufValue::U64(v) => Some(v),
+            _ => self.as_i64().and_then(|v| u64::try_from(v).ok()),
+        }
+    }
+
+    /// The value of any integer type that fits in an `i64`.
+    pub fn as_i64(&self) -> Option<i64> {
+        match *self {
+            GgufValue::I8(v) => Some(v as i64),
+            GgufValue::I16(v) => Some(v as i64),
+            GgufValue::I32(v) => Some(v as i64),
+            GgufValue::I64(v) => Some(v),
+            GgufValue::U8(_) | GgufValue::U16(_) | GgufValue::U32(_) | GgufValue::U64(_) => {
+                self.as_u64().and_then(|v| i64::try_from(v).ok())
+            }
+            _ => None,
+        }
+    }
+
+    /// The value of a float or an integer.
+    pub fn as_f64(&self) -> Option<f64> {
+        match *self {
+            GgufValue::F32(v) => Some(v as f64),
+            GgufValue::F64(v) => Some(v),
+            _ => self.as_i64().map(|v| v as f64).or_else(|| self.as_u64().map(|v| v as f64)),
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            GgufValue::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[GgufValue]> {
+        match self {
+            GgufValue::Array(a) => Some(a),
+            _ => None,
+        }
+    }
+
+    /// The elements of an array, if all of them convert with `f`.
+    fn to_vec<T>(&self, f: impl Fn(&GgufValue) -> Option<T>) -> Option<Vec<T>> {
+        self.as_array()?.iter().map(f).collect()
+    }
+}
+
+macro_rules! impl_from_value {
+    ($($type:ty => $variant:ident),*) => {
+        $(
+            impl From<$type> for GgufValue {
+                fn from(value: $type) -> Self {
+                    GgufValue::$variant(value)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_value!(u8 => U8, i8 => I8, u16 => U16, i16 => I16, u32 => U32, i32 => I32, f32 => F32,
+    bool => Bool, String => String, u64 => U64, i64 => I64, f64 => F64);
+
+impl From<&str> for GgufValue {
+    fn from(value: &str) -> Self {
+        GgufValue::String(value.to_string())
+    }
+}
+
+impl<T: Into<GgufValue>> From<Vec<T>> for GgufValue {
+    fn from(value: Vec<T>) -> Self {
+        GgufValue::Array(value.into_iter().map(Into::into).collect())
+    }
+}
+
+/// The element type of a tensor in the file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(non_camel_case_types)]
+pub enum GgmlType {
+    F32,
+    F16,
+    Q4_0,
+    Q4_1,
+    Q8_0,
+    I8,
+    I16,
+    I32,
+    I64,
+    BF16,
+    /// A type that cannot be loaded, such as the k-quants.
+    Other(u32),
+}
+
+impl GgmlType {
+    fn from_raw(value: u32) -> Self {
+        match value {
+            0 => GgmlType::F32,
+            1 => GgmlType::F16,
+            2 => GgmlType::Q4_0,
+            3 => GgmlType::Q4_1,
+            8 => GgmlType::Q8_0,
+            24 => GgmlType::I8,
+            25 => GgmlType::I16,
+            26 => GgmlType::I32,
+            27 => GgmlType::I64,
+            30 => GgmlType::BF16,
+            v => GgmlType::Other(v),
+        }
+    }
+
+    fn to_raw(self) -> u32 {
+        match self {
+            GgmlType::F32 => 0,
+            GgmlType::F16 => 1,
+            GgmlType::Q4_0 => 2,
+            GgmlType::Q4_1 => 3,
+            GgmlType::Q8_0 => 8,
+            GgmlType::I8 => 24,
+            GgmlType::I16 => 25,
+            GgmlType::I32 => 26,
+            GgmlType::I64 => 27,
+            GgmlType::BF16 => 30,
+            GgmlType::Other(v) => v,
+        }
+    }
+
+    /// The dtype of the types stored unquantized.
+    fn dtype(self) -> Option<Dtype> {
+        match self {
+            GgmlType::F32 => Some(Dtype::F32),
+            GgmlType::F16 => Some(Dtype::F16),
+            GgmlType::BF16 => Some(Dtype::BF16),
+            GgmlType::I8 => Some(Dtype::I8),
+            GgmlType::I16 => Some(Dtype::I16),
+            GgmlType::I32 => Some(Dtype::I32),
+            GgmlType::I64 => Some(Dtype::I64),
+            _ => None,
+        }
+    }
+
+    fn from_dtype(dtype: Dtype) -> Option<Self> {
+        [GgmlType::F32, GgmlType::F16, GgmlType::BF16, GgmlType::I8, GgmlType::I16, GgmlType::I32, GgmlType::I64]
+            .into_iter()
+            .find(|t| t.dtype() == Some(dtype))
+    }
+
+    /// The bits per quantized value and bytes per block of the quantized types.
+    fn quantization(self) -> Option<(i32, usize)> {
+        match self {
+            GgmlType::Q4_0 => Some((4, 18)),
+            GgmlType::Q4_1 => Some((4, 20)),
+            GgmlType::Q8_0 => Some((8, 34)),
+            _ => None,
+        }
+    }
+}
+
+impl Display for GgmlType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GgmlType::Other(v) => write!(f, "ggml type {}", v),
+            t => write!(f, "{:?}", t),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct GgufTensorInfo {
+    pub name: String,
+    /// The shape in MLX order, the reverse of the GGUF dimensions.
+    pub shape: Vec<i32>,
+    pub ggml_type: GgmlType,
+    /// Offset of the data from the start of the data section.
+    offset: u64,
+}
+
+/// A tensor, either plain or in the MLX affine quantized layout.
+#[derive(Clone, Debug)]
+pub enum GgufTensor {
+    Array(MLXArray),
+    /// `weights` are `uint32` of shape `[..., n * bits / 32]`, `scales` and `biases` have shape
+    /// `[..., n / group_size]`. Loaded scales and biases are `float32`, the biases of `Q4_0` and
+    /// `Q8_0` are `-8` and `-128` times the `float16` scales and may not fit a `float16`.
+    Quantized {
+        weights: MLXArray,
+        scales: MLXArray,
+        biases: MLXArray,
+        group_size: i32,
+        bits: i32,
+    },
+}
+
+/// The tokenizer stored under the `tokenizer.ggml.*` keys.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GgufTokenizer {
+    /// e.g. `llama` or `gpt2`.
+    pub model: String,
+    pub tokens: Vec<String>,
+    pub scores: Option<Vec<f32>>,
+    pub token_types: Option<Vec<i32>>,
+    pub merges: Option<Vec<String>>,
+    pub bos_token_id: Option<u32>,
+    pub eos_token_id: Option<u32>,
+    pub unknown_token_id: Option<u32>,
+    pub padding_token_id: Option<u32>,
+}
+
+/// The header of a GGUF file, tensors are read on demand.
+#[derive(Clone, Debug)]
+pub struct Gguf {
+    pub version: u32,
+    pub metadata: HashMap<String, GgufValue>,
+    pub tensors: Vec<GgufTensorInfo>,
+    path: PathBuf,
+    data_offset: u64,
+}
+
+struct Reader<R> {
+    inner: R,
+    position: u64,
+}
+
+impl<R: Read> Reader<R> {
+    fn bytes<const N: usize>(&mut self) -> Result<[u8; N], MLXError> {
+        let mut buf = [0u8; N];
+        self.inner.read_exact(&mut buf)?;
+        self.position += N as u64;
+        Ok(buf)
+    }
+
+    fn u32(&mut self) -> Result<u32, MLXError> {
+        Ok(u32::from_le_bytes(self.bytes()?))
+    }
+
+    fn u64(&mut self) -> Result<u64, MLXError> {
+        Ok(u64::from_le_bytes(self.bytes()?))
+    }
+
+    fn string(&mut self) -> Result<String, MLXError> {
+        let len = self.u64()?;
+        let mut buf = Vec::new();
+        (&mut self.inner).take(len).read_to_end(&mut buf)?;
+        if buf.len() as u64 != len {
+            return Err(MLXError::Io(std::io::ErrorKind::UnexpectedEof.into()));
+        }
+        self.position += len;
+        String::from_utf8(buf).map_err(|e| invalid(format!("invalid gguf string: {}", e)))
+    }
+
+    /// Reads a value of `type_id`, nested in `depth` arrays.
+    fn value(&mut self, type_id: u32, depth: usize) -> Result<GgufValue, MLXError> {
+        let value = match type_id {
+            0 => GgufValue::U8(u8::from_le_bytes(self.bytes()?)),
+            1 => GgufValue::I8(i8::from_le_bytes(self.bytes()?)),
+            2 => GgufValue::U16(u16::from_le_bytes(self.bytes()?)),
+            3 => GgufValue::I16(i16::from_le_bytes(self.bytes()?)),
+            4 => GgufValue::U32(self.u32()?),
+            5 => GgufValue::I32(i32::from_le_bytes(self.bytes()?)),
+            6 => GgufValue::F32(f32::from_le_bytes(self.bytes()?)),
+            7 => GgufValue::Bool(self.bytes::<1>()?[0] != 0),
+            8 => GgufValue::String(self.string()?),
+            9 => {
+                if depth == MAX_ARRAY_DEPTH {
+                    return Err(invalid(format!("gguf arrays nested deeper than {}", MAX_ARRAY_DEPTH)));
+                }
+                let element_type = self.u32()?;
+                let len = self.u64()?;
+                let values = (0..len).map(|_| self.value(element_type, depth + 1)).collect::<Result<_, _>>()?;
+                GgufValue::Array(values)
+            }
+            10 => GgufValue::U64(self.u64()?),
+            11 => GgufValue::I64(i64::from_le_bytes(self.bytes()?)),
+            12 => GgufValue::F64(f64::from_le_bytes(self.bytes()?)),
+            t => return Err(invalid(format!("unknown gguf value type {}", t))),
+        };
+        Ok(value)
+    }
+}
+
+fn align(offset: u64, alignment: u64) -> u64 {
+    offset.div_ceil(alignment) * alignment
+}
+
+fn alignment(metadata: &HashMap<String, GgufValue>) -> Result<u64, MLXError> {
+    match metadata.get(ALIGNMENT_KEY) {
+        None => Ok(DEFAULT_ALIGNMENT),
+        Some(GgufValue::U32(a)) if *a > 0 => Ok(*a as u64),
+        Some(a) => Err(invalid(format!("invalid {}: {:?}", ALIGNMENT_KEY, a))),
+    }
+}
+
+/// The name of the scales and biases of a quantized tensor, `q_proj.weight` has `q_proj.scales`.
+fn quantized_name(name: &str, suffix: &str) -> String {
+    format!("{}.{}", name.strip_suffix(".weight").unwrap_or(name), suffix)
+}
+
+impl Gguf {
+    pub fn open<P: AsRef<Path>>(path: P) -> Self {
+        Self::try_open(path).unwrap()
+    }
+
+    /// Reads the metadata and tensor infos of a GGUF file.
+    pub fn try_open<P: AsRef<Path>>(path: P) -> Result<Self, MLXError> {
+        let path = path.as_ref().to_path_buf();
+        let mut reader = Reader { inner: BufReader::new(File::open(&path)?), position: 0 };
+        if &reader.bytes::<4>()? != MAGIC {
+            return Err(invalid(format!("{:?} is not a gguf file", path)));
+        }
+        let version = reader.u32()?;
+        if version != 2 && version != 3 {
+            return Err(invalid(format!("unsupported gguf version {}", version)));
+        }
+        let tensor_count = reader.u64()?;
+        let metadata_count = reader.u64()?;
+
+        let mut metadata = HashMap::new();
+        for _ in 0..metadata_count {
+            let key = reader.string()?;
+            let type_id = reader.u32()?;
+            metadata.insert(key, reader.value(type_id, 0)?);
+        }
+
+        let mut tensors = Vec::new();
+        for _ in 0..tensor_count {
+            let name = reader.string()?;
+            let n_dims = reader.u32()?;
+            let mut shape = (0..n_dims)
+                .map(|_| {
+                    let dim = reader.u64()?;
+                    i32::try_from(dim).map_err(|_| invalid(format!("dimension {} of {} is too large", dim, name)))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            shape.reverse();
+            let ggml_type = GgmlType::from_raw(reader.u32()?);
+            let offset = reader.u64()?;
+            tensors.push(GgufTensorInfo { name, shape, ggml_type, offset });
+        }
+
+        let data_offset = align(reader.position, alignment(&metadata)?);
+        Ok(Gguf { version, metadata, tensors, path, data_offset })
+    }
+
+    pub fn get(&self, key: &str) -> Option<&GgufValue> {
+        self.metadata.get(key)
+    }
+
+    /// The `general.architecture`, e.g. `llama`.
+    pub fn architecture(&self) -> Option<&str> {
+        self.get("general.architecture")?.as_str()
+    }
+
+    pub fn tokenizer(&self) -> Option<GgufTokenizer> {
+        let key = |name: &str| self.get(&format!("tokenizer.ggml.{}", name));
+        let id = |name: &str| key(name)?.as_u64().and_then(|v| u32::try_from(v).ok());
+        let strings = |name: &str| key(name)?.to_vec(|v| v.as_str().map(str::to_string));
+        Some(GgufTokenizer {
+            model: key("model")?.as_str()?.to_string(),
+            tokens: strings("tokens")?,
+            scores: key("scores").and_then(|v| v.to_vec(|s| s.as_f64().map(|s| s as f32))),
+            token_types: key("token_type").and_then(|v| v.to_vec(|t| t.as_i64().map(|t| t as i32))),
+            merges: strings("merges"),
+            bos_token_id: id("bos_token_id"),
+            eos_token_id: id("eos_token_id"),
+            unknown_token_id: id("unknown_token_id"),
+            padding_token_id: id("padding_token_id"),
+        })
+    }
+
+    pub fn load(&self, name: &str) -> GgufTensor {
+        self.try_load(name).unwrap()
+    }
+
+    pub fn try_load(&self, name: &str) -> Result<GgufTensor, MLXError> {
+        let info = self
+            .tensors
+            .iter()
+            .find(|t| t.name == name)
+            .ok_or_else(|| invalid(format!("tensor {} not found", name)))?;
+        self.read_tensor(&mut File::open(&self.path)?, info)
+    }
+
+    /// All the tensors, with the scales and biases of a quantized `name.weight` under
+    /// `name.scales` and `name.biases`.
+    pub fn arrays(&self) -> HashMap<String, MLXArray> {
+        self.try_arrays().unwrap()
+    }
+
+    pub fn try_arrays(&self) -> Result<HashMap<String, MLXArray>, MLXError> {
+        let mut file = File::open(&self.path)?;
+        let mut arrays = HashMap::new();
+        for info in &self.tensors {
+            match self.read_tensor(&mut file, info)? {
+                GgufTensor::Array(array) => {
+                    arrays.insert(info.name.clone(), array);
+                }
+                GgufTensor::Quantized { weights, scales, biases, .. } => {
+                    arrays.insert(quantized_name(&info.name, "scales"), scales);
+                    arrays.insert(quantized_name(&info.name, "biases"), biases);
+                    arrays.insert(info.name.clone(), weights);
+                }
+            }
+        }
+        Ok(arrays)
+    }
+
+    fn read_tensor(&self, file: &mut File, info: &GgufTensorInfo) -> Result<GgufTensor, MLXError> {
+        let too_large = || invalid(format!("{} of shape {:?} is too large", info.name, info.shape));
+        let size = info.shape.iter().try_fold(1usize, |size, d| size.checked_mul(*d as usize)).ok_or_else(too_large)?;
+        let nbytes = match (info.ggml_type.dtype(), info.ggml_type.quantization()) {
+            (Some(dtype), _) => size.checked_mul(dtype.size_of()).ok_or_else(too_large)?,
+            (None, Some((_, block_bytes))) => {
+                if info.shape.last().is_none_or(|n| !(*n as usize).is_multiple_of(BLOCK_SIZE)) {
+                    return Err(invalid(format!(
+                        "{} of shape {:?} is not made of blocks of {}",
+                        info.name, info.shape, BLOCK_SIZE
+                    )));
+                }
+                size / BLOCK_SIZE * block_bytes
+            }
+            (None, None) => {
+                return Err(invalid(format!("{} has the unsupported {}", info.name, info.ggml_type)))
+            }
+        };
+        // the header is untrusted, check the tensor lies within the file before allocating for it
+        let start = self.data_offset.checked_add(info.offset);
+        let end = start.and_then(|start| start.checked_add(nbytes as u64));
+        let (Some(start), Some(end)) = (start, end) else {
+            return Err(too_large());
+        };
+        let file_len = file.metadata()?.len();
+        if end > file_len {
+            return Err(invalid(format!("{} needs bytes {}..{} but the file has {}", info.name, start, end, file_len)));
+        }
+        let mut data = vec![0u8; nbytes];
+        file.seek(SeekFrom::Start(start))?;
+        file.read_exact(&mut data)?;
+
+        match info.ggml_type.dtype() {
+            Some(dtype) => Ok(GgufTensor::Array(MLXArray::from_bytes(&data, &info.shape, dtype)?)),
+            None => Ok(to_affine(info.ggml_type, &data, &info.shape)),
+        }
+    }
+}
+
+/// The value of element `e` of a 4-bit block, the first 16 are in the low nibbles.
+fn ggml_nibble(qs: &[u8], e: usize) -> u32 {
+    if e < BLOCK_SIZE / 2 {
+        (qs[e] & 0xf) as u32
+    } else {
+        (qs[e - BLOCK_SIZE / 2] >> 4) as u32
+    }
+}
+
+fn read_f16(bytes: &[u8]) -> f16 {
+    f16::from_le_bytes([bytes[0], bytes[1]])
+}
+
+/// Converts blocks of `Q4_0`, `Q4_1` or `Q8_0` to MLX weights, scales and biases. The scales and
+/// biases are `float32` since `-8 * d` and `-128 * d` overflow a `float16` for large `d`.
+fn to_affine(ggml_type: GgmlType, data: &[u8], shape: &[i32]) -> GgufTensor {
+    let (bits, block_bytes) = ggml_type.quantization().unwrap();
+    let groups = data.len() / block_bytes;
+    let mut words = Vec::with_capacity(groups * bits as usize);
+    let mut scales = Vec::with_capacity(groups);
+    let mut biases = Vec::with_capacity(groups);
+    for block in data.chunks_exact(block_bytes) {
+        let d = read_f16(block).to_f32();
+        match ggml_type {
+            GgmlType::Q4_0 | GgmlType::Q4_1 => {
+                // Q4_0 stores q - 8, Q4_1 stores the minimum as bias
+                let (bias, qs) = if ggml_type == GgmlType::Q4_0 {
+                    (-8.0 * d, &block[2..])
+                } else {
+                    (read_f16(&block[2..]).to_f32(), &block[4..])
+                };
+                for w in 0..BLOCK_SIZE / 8 {
+                    words.push((0..8).fold(0, |word, k| word | ggml_nibble(qs, 8 * w + k) << (4 * k)));
+                }
+                scales.push(d);
+                biases.push(bias);
+            }
+            _ => {
+                // signed bytes, flipping the sign bit gives q + 128
+                for q in block[2..].chunks_exact(4) {
+                    words.push(u32::from_le_bytes([q[0], q[1], q[2], q[3]]) ^ 0x8080_8080);
+                }
+                scales.push(d);
+                biases.push(-128.0 * d);
+            }
+        }
+    }
+
+    let (n, leading) = shape.split_last().unwrap();
+    let with_last = |last: i32| leading.iter().copied().chain([last]).collect::<Vec<_>>();
+    GgufTensor::Quantized {
+        weights: MLXArray::array(&words, &with_last(n * bits / 32)),
+        scales: MLXArray::array(&scales, &with_last(n / BLOCK_SIZE as i32)),
+        biases: MLXArray::array(&biases, &with_last(n / BLOCK_SIZE as i32)),
+        group_size: BLOCK_SIZE as i32,
+        bits,
+    }
+}
+
+/// Converts MLX 4-bit weights to `Q4_1` blocks and 8-bit weights to `Q8_0` blocks.
+fn from_affine(
+    name: &str,
+    weights: &MLXArray,
+    scales: &MLXArray,
+    biases: &MLXArray,
+    group_size: i32,
+    bits: i32,
+) -> Result<(GgmlType, Vec<i32>, Vec<u8>), MLXError> {
+    let ggml_type = match (group_size, bits) {
+        (32, 4) => GgmlType::Q4_1,
+        (32, 8) => GgmlType::Q8_0,
+        _ => {
+            return Err(invalid(format!(
+                "{} has {} bits in groups of {}, gguf stores 4 or 8 bits in groups of 32",
+                name, bits, group_size
+            )))
+        }
+    };
+    let weights = weights.to_row_major()?;
+    let scales = scales.try_as_type::<f32>()?.to_row_major()?;
+    let biases = biases.try_as_type::<f32>()?.to_row_major()?;
+    let words = weights.to_slice::<u32>()?;
+    let scales = scales.to_slice::<f32>()?;
+    let biases = biases.to_slice::<f32>()?;
+    let words_per_group = (group_size * bits / 32) as usize;
+    if words.len() != scales.len() * words_per_group || scales.len() != biases.len() {
+        return Err(invalid(format!("{} has mismatched weights, scales and biases", name)));
+    }
+
+    let mut data = Vec::with_capacity(scales.len() * ggml_type.quantization().unwrap().1);
+    let to_f16 = |v: f32, what: &str| {
+        let h = f16::from_f32(v);
+        if h.is_finite() {
+            Ok(h)
+        } else {
+            Err(invalid(format!("{} has a {} of {} that does not fit a float16", name, what, v)))
+        }
+    };
+    for ((group, d), m) in words.chunks_exact(words_per_group).zip(scales).zip(biases) {
+        data.extend_from_slice(&to_f16(*d, "scale")?.to_le_bytes());
+        if ggml_type == GgmlType::Q4_1 {
+            data.extend_from_slice(&to_f16(*m, "bias")?.to_le_bytes());
+            let q = |e: usize| (group[e / 8] >> (4 * (e % 8))) & 0xf;
+            data.extend((0..BLOCK_SIZE / 2).map(|i| (q(i) | q(i + BLOCK_SIZE / 2) << 4) as u8));
+        } else {
+            if *m != -128.0 * d {
+                return Err(invalid(format!("{} is not symmetric, Q8_0 needs biases of -128 * scales", name)));
+            }
+            data.extend(group.iter().flat_map(|w| (w ^ 0x8080_8080).to_le_bytes()));
+        }
+    }
+
+    let mut shape = weights.shape().to_vec();
+    if let Some(last) = shape.last_mut() {
+        *last = *last * 32 / bits;
+    }
+    Ok((ggml_type, shape, data))
+}
+
+fn write_string(writer: &mut impl Write, s: &str) -> Result<(), MLXError> {
+    writer.write_all(&(s.len() as u64).to_le_bytes())?;
+    writer.write_all(s.as_bytes())?;
+    Ok(())
+}
+
+fn write_value(writer: &mut impl Write, value: &GgufValue) -> Result<(), MLXError> {
+    match value {
+        GgufValue::U8(v) => writer.write_all(&v.to_le_bytes())?,
+        GgufValue::I8(v) => writer.write_all(&v.to_le_bytes())?,
+        GgufValue::U16(v) => writer.write_all(&v.to_le_bytes())?,
+        GgufValue::I16(v) => writer.write_all(&v.to_le_bytes())?,
+        GgufValue::U32(v) => writer.write_all(&v.to_le_bytes())?,
+        GgufValue::I32(v) => writer.write_all(&v.to_le_bytes())?,
+        GgufValue::F32(v) => writer.write_all(&v.to_le_bytes())?,
+        GgufValue::Bool(v) => writer.write_all(&[*v as u8])?,
+        GgufValue::String(s) => write_string(writer, s)?,
+        GgufValue::Array(values) => {
+            // an empty array needs some element type
+            let element_type = values.first().map_or(0, GgufValue::type_id);
+            if values.iter().any(|v| v.type_id() != element_type) {
+                return Err(invalid("gguf arrays hold elements of a single type"));
+            }
+            writer.write_all(&element_type.to_le_bytes())?;
+            writer.write_all(&(values.len() as u64).to_le_bytes())?;
+            for v in values {
+                write_value(writer, v)?;
+            }
+        }
+        GgufValue::U64(v) => writer.write_all(&v.to_le_bytes())?,
+        GgufValue::I64(v) => writer.write_all(&v.to_le_bytes())?,
+        GgufValue::F64(v) => writer.write_all(&v.to_le_bytes())?,
+    }
+    Ok(())
+}
+
+pub fn save_gguf<P: AsRef<Path>>(
+    path: P,
+    tensors: &HashMap<String, GgufTensor>,
+    metadata: &HashMap<String, GgufValue>,
+) {
+    try_save_gguf(path, tensors, metadata).unwrap()
+}
+
+/// Writes a GGUF v3 file.
+///
+/// Arrays are stored in their dtype, which must be a float or signed integer type. Quantized
+/// tensors are stored as `Q4_1` for 4 bits and as `Q8_0` for 8 bits, the latter only when the
+/// biases are `-128 * scales` as they are for tensors loaded from `Q8_0`.
+pub fn try_save_gguf<P: AsRef<Path>>(
+    path: P,
+    tensors: &HashMap<String, GgufTensor>,
+    metadata: &HashMap<String, GgufValue>,
+) -> Result<(), MLXError> {
+    let alignment = alignment(metadata)?;
+    let mut names: Vec<_> = tensors.keys().collect();
+    names.sort();
+    let mut infos = Vec::with_capacity(names.len());
+    let mut offset = 0;
+    for name in names {
+        let (ggml_type, shape, data) = match &tensors[name] {
+            GgufTensor::Array(array) => {
+                let ggml_type = GgmlType::from_dtype(array.dtype())
+                    .ok_or_else(|| invalid(format!("gguf cannot store {} of dtype {}", name, array.dtype())))?;
+                let array = array.to_row_major()?;
+                (ggml_type, array.shape().to_vec(), array.as_bytes()?.to_vec())
+            }
+            GgufTensor::Quantized { weights, scales, biases, group_size, bits } => {
+                from_affine(name, weights, scales, biases, *group_size, *bits)?
+            }
+        };
+        let info = GgufTensorInfo { name: name.clone(), shape, ggml_type, offset };
+        offset = align(offset + data.len() as u64, alignment);
+        infos.push((info, data));
+    }
+
+    let mut writer = BufWriter::new(File::create(path)?);
+    let mut header = Vec::new();
+    header.extend_from_slice(MAGIC);
+    header.extend_from_slice(&VERSION.to_le_bytes());
+    header.extend_from_slice(&(infos.len() as u64).to_le_bytes());
+    header.extend_from_slice(&(metadata.len() as u64).to_le_bytes());
+    let mut keys: Vec<_> = metadata.keys().collect();
+    keys.sort();
+    for key in keys {
+        write_string(&mut header, key)?;
+        header.extend_from_slice(&metadata[key].type_id().to_le_bytes());
+        write_value(&mut header, &metadata[key])?;
+    }
+    for (info, _) in &infos {
+        write_string(&mut header, &info.name)?;
+        header.extend_from_slice(&(info.shape.len() as u32).to_le_bytes());
+        for dim in info.shape.iter().rev() {
+            header.extend_from_slice(&(*dim as u64).to_le_bytes());
+        }
+        header.extend_from_slice(&info.ggml_type.to_raw().to_le_bytes());
+        header.extend_from_slice(&info.offset.to_le_bytes());
+    }
+    header.resize(align(header.len() as u64, alignment) as usize, 0);
+    writer.write_all(&header)?;
+
+    for (_, data) in &infos {
+        writer.write_all(data)?;
+        let padding = align(data.len() as u64, alignment) as usize - data.len();
+        writer.write_all(&vec![0u8; padding])?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::path::{Path, PathBuf};
+
+    use half::{bf16, f16};
+
+    use crate::error::MLXError;
+    use crate::r#type::Dtype;
+    use crate::MLXArray;
+
+    use super::{save_gguf, try_save_gguf, GgmlType, Gguf, GgufTensor, GgufValue, ALIGNMENT_KEY};
+
+    /// A file with a single tensor of `ggml_type` holding `data`.
+    fn write_raw(dir: &Path, name: &str, dims: &[u64], ggml_type: u32, data: &[u8]) -> PathBuf {
+        let mut buf = b"GGUF".to_vec();
+        buf.extend_from_slice(&3u32.to_le_bytes());
+        buf.extend_from_slice(&1u64.to_le_bytes());
+        buf.extend_from_slice(&0u64.to_le_bytes());
+        buf.extend_from_slice(&1u64.to_le_bytes());
+        buf.extend_from_slice(b"t");
+        buf.extend_from_slice(&(dims.len() as u32).to_le_bytes());
+        dims.iter().for_each(|d| buf.extend_from_slice(&d.to_le_bytes()));
+        buf.extend_from_slice(&ggml_type.to_le_bytes());
+        buf.extend_from_slice(&0u64.to_le_bytes());
+        buf.resize(buf.len().div_ceil(32) * 32, 0);
+        buf.extend_from_slice(data);
+        let path = dir.join(name);
+        std::fs::write(&path, buf).unwrap();
+        path
+    }
+
+    fn dequantize(tensor: &GgufTensor) -> Vec<f32> {
+        let GgufTensor::Quantized { weights, scales, biases, group_size, bits } = tensor else {
+            panic!("not quantized")
+        };
+        let per_word = 32 / *bits as usize;
+        let mask = (1u32 << bits) - 1;
+        let words = weights.to_slice::<u32>().unwrap();
+        let q = (0..words.len() * per_word).map(|i| (words[i / per_word] >> (*bits as usize * (i % per_word))) & mask);
+        q.enumerate()
+            .map(|(i, q)| {
+                let g = i / *group_size as usize;
+                scales.to_slice::<f32>().unwrap()[g] * q as f32 + biases.to_slice::<f32>().unwrap()[g]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_load_q4_0_and_q8_0() {
+        let dir = tempfile::tempdir().unwrap();
+        // two rows of one block with d = 0.5 and q = e % 16 for element e
+        let mut data = Vec::new();
+        for _ in 0..2 {
+            data.extend_from_slice(&f16::from_f32(0.5).to_le_bytes());
+            data.extend((0..16u8).map(|i| (i % 16) | ((i + 16) % 16) << 4));
+        }
+        let path = write_raw(dir.path(), "q4_0.gguf", &[32, 2], 2, &data);
+        let gguf = Gguf::open(&path);
+        assert_eq!(GgmlType::Q4_0, gguf.tensors[0].ggml_type);
+        assert_eq!(vec![2, 32], gguf.tensors[0].shape);
+        let tensor = gguf.load("t");
+        let GgufTensor::Quantized { weights, scales, .. } = &tensor else { panic!() };
+        assert_eq!(&[2, 4], weights.shape());
+        assert_eq!(&[2, 1], scales.shape());
+        let expected: Vec<f32> = (0..64).map(|e| 0.5 * ((e % 16) as f32 - 8.0)).collect();
+        assert_eq!(expected, dequantize(&tensor));
+
+        let mut data = f16::from_f32(0.25).to_le_bytes().to_vec();
+        data.extend((0..32).map(|i| (i as i8 - 16) as u8));
+        let path = write_raw(dir.path(), "q8_0.gguf", &[32], 8, &data);
+        let tensor = Gguf::open(&path).load("t");
+        let expected: Vec<f32> = (0..32).map(|i| 0.25 * (i as f32 - 16.0)).collect();
+        assert_eq!(expected, dequantize(&tensor));
+
+        // -128 * d is beyond the float16 range
+        let mut data = f16::from_f32(1000.0).to_le_bytes().to_vec();
+        data.extend((0..32).map(|i| (i as i8 - 16) as u8));
+        let path = write_raw(dir.path(), "q8_0_large.gguf", &[32], 8, &data);
+        let tensor = Gguf::open(&path).load("t");
+        let expected: Vec<f32> = (0..32).map(|i| 1000.0 * (i as f32 - 16.0)).collect();
+        assert_eq!(expected, dequantize(&tensor));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let tokenizer: HashMap<String, GgufValue> = [
+            ("general.architecture", GgufValue::from("llama")),
+            ("llama.context_length", GgufValue::from(4096u32)),
+            ("tokenizer.ggml.model", GgufValue::from("llama")),
+            ("tokenizer.ggml.tokens", GgufValue::from(vec!["<s>", "</s>", "hi"])),
+            ("tokenizer.ggml.scores", GgufValue::from(vec![0.0f32, 0.0, -1.5])),
+            ("tokenizer.ggml.token_type", GgufValue::from(vec![3i32, 3, 1])),
+            ("tokenizer.ggml.bos_token_id", GgufValue::from(0u32)),
+            ("tokenizer.ggml.eos_token_id", GgufValue::from(1u32)),
+            ("general.tags", GgufValue::from(Vec::<String>::new())),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v))
+        .collect();
+
+        let dir = tempfile::tempdir().unwrap();
+        let q4 = Gguf::open(write_raw(dir.path(), "q4.gguf", &[32], 2, &[[0u8, 60].as_slice(), &[0x9a; 16]].concat()))
+            .load("t");
+        let q8 = Gguf::open(write_raw(dir.path(), "q8.gguf", &[32], 8, &[[0u8, 56].as_slice(), &[0xfe; 32]].concat()))
+            .load("t");
+        let tensors: HashMap<String, GgufTensor> = [
+            ("a.weight", GgufTensor::Array(MLXArray::array(&[1.0f32, 2.0, 3.0, 4.0, 5.0, 6.0], &[2, 3]))),
+            ("b", GgufTensor::Array(MLXArray::array(&[f16::ONE, f16::NEG_ONE], &[2]))),
+            ("c", GgufTensor::Array(MLXArray::array(&[bf16::ONE, bf16::MAX], &[2, 1]))),
+            ("d", GgufTensor::Array(MLXArray::array(&[7i32, -7], &[2]))),
+            ("q4.weight", q4.clone()),
+            ("q8.weight", q8.clone()),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v))
+        .collect();
+
+        let path = dir.path().join("round_trip.gguf");
+        save_gguf(&path, &tensors, &tokenizer);
+        let gguf = Gguf::open(&path);
+        assert_eq!(3, gguf.version);
+        assert_eq!(Some("llama"), gguf.architecture());
+        assert_eq!(Some(4096), gguf.get("llama.context_length").and_then(GgufValue::as_u64));
+        assert_eq!(Some(&[][..]), gguf.get("general.tags").and_then(GgufValue::as_array));
+        let tok = gguf.tokenizer().unwrap();
+        assert_eq!("llama", tok.model);
+        assert_eq!(vec!["<s>", "</s>", "hi"], tok.tokens);
+        assert_eq!(Some(vec![0.0, 0.0, -1.5]), tok.scores);
+        assert_eq!(Some(vec![3, 3, 1]), tok.token_types);
+        assert_eq!((Some(0), Some(1), None), (tok.bos_token_id, tok.eos_token_id, tok.unknown_token_id));
+
+        let info = gguf.tensors.iter().find(|t| t.name == "q4.weight").unwrap();
+        assert_eq!((GgmlType::Q4_1, vec![32]), (info.ggml_type, info.shape.clone()));
+        let info = gguf.tensors.iter().find(|t| t.name == "c").unwrap();
+        assert_eq!((GgmlType::BF16, vec![2, 1]), (info.ggml_type, info.shape.clone()));
+
+        let arrays = gguf.arrays();
+        assert_eq!(10, arrays.len());
+        assert_eq!(&[2, 3], arrays["a.weight"].shape());
+        assert_eq!(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0], arrays["a.weight"].to_slice::<f32>().unwrap());
+        assert_eq!(&[f16::ONE, f16::NEG_ONE], arrays["b"].to_slice::<f16>().unwrap());
+        assert_eq!(Dtype::BF16, arrays["c"].dtype());
+        assert_eq!(&[7, -7], arrays["d"].to_slice::<i32>().unwrap());
+        assert_eq!(&[1], arrays["q4.scales"].shape());
+        assert_eq!(dequantize(&q4), dequantize(&gguf.load("q4.weight")));
+        assert_eq!(dequantize(&q8), dequantize(&gguf.load("q8.weight")));
+    }
+
+    #[test]
+    fn test_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_raw(dir.path(), "q4_k.gguf", &[256], 12, &[0; 144]);
+        let r = Gguf::open(&path).try_load("t");
+        assert!(matches!(r, Err(MLXError::InvalidArgument(msg)) if msg.contains("ggml type 12")));
+
+        // a header promising more data than the file holds
+        let path = write_raw(dir.path(), "truncated.gguf", &[1 << 30, 1 << 30], 0, &[0; 16]);
+        let r = Gguf::open(&path).try_load("t");
+        assert!(matches!(r, Err(MLXError::InvalidArgument(_))));
+        let path = write_raw(dir.path(), "short.gguf", &[8], 0, &[0; 16]);
+        let r = Gguf::open(&path).try_load("t");
+        assert!(matches!(r, Err(MLXError::InvalidArgument(msg)) if msg.contains("file has")));
+
+        let path = dir.path().join("errors.gguf");
+        let tensors = [("u".to_string(), GgufTensor::Array(MLXArray::array(&[1u32], &[1])))].into_iter().collect();
+        assert!(try_save_gguf(&path, &tensors, &HashMap::new()).is_err());
+        let metadata = [(ALIGNMENT_KEY.to_string(), GgufValue::from(0u32))].into_iter().collect();
+        assert!(try_save_gguf(&path, &HashMap::new(), &metadata).is_err());
+        let metadata = [("mixed".to_string(), GgufValue::Array(vec![1u8.into(), "a".into()]))].into_iter().collect();
+        assert!(try_save_gguf(&path, &HashMap::new(), &metadata).is_err());
+
+        let weights = MLXArray::array(&[0u32; 4], &[4]);
+        let large = GgufTensor::Quantized {
+            weights,
+            scales: MLXArray::array(&[1e6f32], &[1]),
+            biases: MLXArray::array(&[0.0f32], &[1]),
+            group_size: 32,
+            bits: 4,
+        };
+        let tensors = [("l".to_string(), large)].into_iter().collect();
+        assert!(try_save_gguf(&path, &tensors, &HashMap::new()).is_err());
+
+        std::fs::write(&path, b"GGUF\x01\x00\x00\x00").unwrap();
+        assert!(matches!(Gguf::try_open(&path), Err(MLXError::InvalidArgument(_))));
+
+        // a metadata value of arrays nested 1000 deep
+        let mut buf = b"GGUF".to_vec();
+        buf.extend_from_slice(&3u32.to_le_bytes());
+        buf.extend_from_slice(&0u64.to_le_bytes());
+        buf.extend_from_slice(&1u64.to_le_bytes());
+        buf.extend_from_slice(&1u64.to_le_bytes());
+        buf.extend_from_slice(b"k");
+        buf.extend_from_slice(&9u32.to_le_bytes());
+        for _ in 0..1000 {
+            buf.extend_from_slice(&9u32.to_le_bytes());
+            buf.extend_from_slice(&1u64.to_le_bytes());
+        }
+        std::fs::write(&path, buf).unwrap();
+        let r = Gguf::try_open(&path);
+        assert!(matches!(r, Err(MLXError::InvalidArgument(msg)) if msg.contains("nested")));
+    }
+}
@@ -0,0 +1,215 @@
+//! Loading safetensors files without copying the tensor data.
+//!
+//! The file is memory-mapped and every array points into the mapping, which stays alive until
+//! the last array is freed. Pages are only read from disk when an array is used. The mapping is
+//! copy-on-write so that mlx reusing an input buffer for an output never writes to the file.
+//!
+//! A tensor whose data is not aligned for its dtype is copied. On Metal, mlx also copies the
+//! tensors whose data does not start on a page boundary.
+
+use std::collections::HashMap;
+use std::ffi::c_void;
+use std::fs::File;
+use std::path::Path;
+use std::sync::Arc;
+
+use memmap2::{MmapMut, MmapOptions};
+use safetensors::tensor::{Metadata, TensorInfo};
+use safetensors::{Dtype as SafeTensorDtype, SafeTensors as SafeTensorsFile};
+
+use mlx_sys::mlx_array_from_data_managed;
+
+use crate::error::MLXError;
+use crate::io::{SafeTensorData, SafeTensorMetadata};
+use crate::r#type::Dtype;
+use crate::MLXArray;
+
+fn to_dtype(dtype: SafeTensorDtype) -> Option<Dtype> {
+    let dtype = match dtype {
+        SafeTensorDtype::BOOL => Dtype::Bool,
+        SafeTensorDtype::U8 => Dtype::U8,
+        SafeTensorDtype::I8 => Dtype::I8,
+        SafeTensorDtype::I16 => Dtype::I16,
+        SafeTensorDtype::U16 => Dtype::U16,
+        SafeTensorDtype::F16 => Dtype::F16,
+        SafeTensorDtype::BF16 => Dtype::BF16,
+        SafeTensorDtype::I32 => Dtype::I32,
+        SafeTensorDtype::U32 => Dtype::U32,
+        SafeTensorDtype::F32 => Dtype::F32,
+        SafeTensorDtype::I64 => Dtype::I64,
+        SafeTensorDtype::U64 => Dtype::U64,
+        _ => return None,
+    };
+    Some(dtype)
+}
+
+/// Called by mlx when an array backed by the mapping is freed.
+extern "C" fn release_mapping(payload: *mut c_void) {
+    drop(unsafe { Box::from_raw(payload as *mut Arc<MmapMut>) });
+}
+
+/// A memory-mapped safetensors file, see the [module](self) documentation.
+pub struct MmapSafeTensors {
+    mmap: Arc<MmapMut>,
+    data_start: usize,
+    header: Metadata,
+}
+
+impl MmapSafeTensors {
+    pub fn open<P: AsRef<Path>>(path: P) -> Self {
+        Self::try_open(path).unwrap()
+    }
+
+    pub fn try_open<P: AsRef<Path>>(path: P) -> Result<Self, MLXError> {
+        let path = path.as_ref();
+        let file = File::open(path)?;
+        let mmap = unsafe { MmapOptions::new().map_copy(&file)? };
+        let invalid = |msg: String| MLXError::InvalidArgument(format!("{:?}: {}", path, msg));
+        let (header_len, header) = SafeTensorsFile::read_metadata(&mmap).map_err(|e| invalid(e.to_string()))?;
+        for (name, info) in header.tensors() {
+            if to_dtype(info.dtype).is_none() {
+                return Err(invalid(format!("{} has the unsupported dtype {:?}", name, info.dtype)));
+            }
+        }
+        Ok(MmapSafeTensors { mmap: Arc::new(mmap), data_start: 8 + header_len, header })
+    }
+
+    /// The tensor names, in no particular order.
+    pub fn names(&self) -> Vec<String> {
+        self.header.tensors().into_keys().collect()
+    }
+
+    pub fn get(&self, name: &str) -> Option<MLXArray> {
+        self.header.info(name).map(|info| self.array(info))
+    }
+
+    pub fn data(&self) -> SafeTensorData {
+        let mut data = SafeTensorData::new();
+        for (name, info) in self.header.tensors() {
+            data.insert(&name, &self.array(info));
+        }
+        data
+    }
+
+    pub fn metadata(&self) -> SafeTensorMetadata {
+        let mut metadata = SafeTensorMetadata::new();
+        for (key, value) in self.header.metadata().iter().flatten() {
+            metadata.insert(key, value);
+        }
+        metadata
+    }
+
+    fn array(&self, info: &TensorInfo) -> MLXArray {
+        let dtype = to_dtype(info.dtype).unwrap();
+        let shape: Vec<i32> = info.shape.iter().map(|d| *d as i32).collect();
+        let (start, end) = info.data_offsets;
+        let data = &self.mmap[self.data_start + start..self.data_start + end];
+        if !(data.as_ptr() as usize).is_multiple_of(dtype.size_of()) {
+            return MLXArray::from_bytes(data, &shape, dtype).unwrap();
+        }
+        let payload = Box::into_raw(Box::new(self.mmap.clone()));
+        let handle = unsafe {
+            mlx_array_from_data_managed(
+                data.as_ptr() as *mut c_void,
+                shape.as_ptr() as *const ::std::os::raw::c_int,
+                shape.len() as ::std::os::raw::c_int,
+                dtype.as_raw(),
+                payload as *mut c_void,
+                Some(release_mapping),
+            )
+        };
+        MLXArray::from_raw(handle)
+    }
+}
+
+/// The arrays of several files, e.g. for [`crate::module::Module::update_by_mmap_safetensors`].
+pub(crate) fn try_load_mmap_safetensors<P: AsRef<Path>>(
+    filenames: &[P],
+) -> Result<HashMap<String, MLXArray>, MLXError> {
+    let mut arrays = HashMap::new();
+    for filename in filenames {
+        let st = MmapSafeTensors::try_open(filename)?;
+        for name in st.names() {
+            let array = st.get(&name).unwrap();
+            arrays.insert(name, array);
+        }
+    }
+    Ok(arrays)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::fs;
+
+    use half::bf16;
+    use safetensors::{serialize, tensor::TensorView, Dtype};
+
+    use crate::error::MLXError;
+    use crate::r#type::Dtype as MLXDtype;
+
+    use super::MmapSafeTensors;
+
+    #[test]
+    fn test_mmap_safetensors() {
+        let floats: Vec<u8> = [1.0f32, 2.0, 3.0, 4.0].iter().flat_map(|f| f.to_le_bytes()).collect();
+        let halves: Vec<u8> = [bf16::ONE, bf16::NEG_ONE].iter().flat_map(|f| f.to_le_bytes()).collect();
+        let tensors: HashMap<String, TensorView> = [
+            ("w".to_string(), TensorView::new(Dtype::F32, vec![2, 2], &floats).unwrap()),
+            ("h".to_string(), TensorView::new(Dtype::BF16, vec![2], &halves).unwrap()),
+        ]
+        .into_iter()
+        .collect();
+        let metadata = Some([("format".to_string(), "mlx".to_string())].into_iter().collect());
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("model.safetensors");
+        fs::write(&path, serialize(&tensors, &metadata).unwrap()).unwrap();
+
+        let st = MmapSafeTensors::open(&path);
+        assert_eq!("mlx", st.metadata().get("format").unwrap().to_string().unwrap());
+        let w = st.get("w").unwrap();
+        let data = st.data();
+        // the arrays keep the mapping alive
+        drop(st);
+        assert_eq!(&[2, 2], w.shape());
+        assert_eq!(&[1.0, 2.0, 3.0, 4.0], w.to_slice::<f32>().unwrap());
+        let h = data.get("h").unwrap();
+        assert_eq!(MLXDtype::BF16, h.dtype());
+        assert_eq!(&[bf16::ONE, bf16::NEG_ONE], h.to_slice::<bf16>().unwrap());
+
+        // ops writing their output into an input buffer leave the file untouched
+        let before = fs::read(&path).unwrap();
+        let y = w + 1.0f32;
+        assert_eq!(&[2.0, 3.0, 4.0, 5.0], y.to_slice::<f32>().unwrap());
+        assert_eq!(before, fs::read(&path).unwrap());
+    }
+
+    #[test]
+    fn test_mmap_unaligned_and_unsupported() {
+        fn file(header: &str, data: &[u8]) -> Vec<u8> {
+            let mut header = header.as_bytes().to_vec();
+            header.resize(header.len().div_ceil(8) * 8, b' ');
+            let mut buf = (header.len() as u64).to_le_bytes().to_vec();
+            buf.extend(header);
+            buf.extend_from_slice(data);
+            buf
+        }
+
+        let mut data = vec![7u8];
+        data.extend_from_slice(&2.5f32.to_le_bytes());
+        let header = r#"{"a":{"dtype":"U8","shape":[1],"data_offsets":[0,1]},"b":{"dtype":"F32","shape":[1],"data_offsets":[1,5]}}"#;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("unaligned.safetensors");
+        fs::write(&path, file(header, &data)).unwrap();
+        let st = MmapSafeTensors::open(&path);
+        assert_eq!(&[7], st.get("a").unwrap().to_slice::<u8>().unwrap());
+        assert_eq!(&[2.5], st.get("b").unwrap().to_slice::<f32>().unwrap());
+        assert!(st.get("c").is_none());
+
+        let header = r#"{"d":{"dtype":"F64","shape":[1],"data_offsets":[0,8]}}"#;
+        let path = dir.path().join("f64.safetensors");
+        fs::write(&path, file(header, &[0; 8])).unwrap();
+        let r = MmapSafeTensors::try_open(&path);
+        assert!(matches!(r, Err(MLXError::InvalidArgument(msg)) if msg.contains("F64")));
+    }
+}
@@ -0,0 +1,257 @@
+//! Checkpoints split over several safetensors files, as written by Hugging Face.
+//!
+//! A `model.safetensors.index.json` next to the shards maps every tensor name to the shard
+//! holding it:
+//!
+//! ```json
+//! {"metadata": {"total_size": 1024}, "weight_map": {"lm_head.weight": "model-00002-of-00002.safetensors"}}
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::MLXError;
+use crate::eval::try_eval;
+use crate::io::{try_save_safetensors, SafeTensors};
+use crate::MLXArray;
+
+/// The name of the index file in a sharded checkpoint directory.
+pub const INDEX_FILE: &str = "model.safetensors.index.json";
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ShardIndexMetadata {
+    /// The bytes of all the tensors.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total_size: Option<u64>,
+}
+
+/// The content of a `model.safetensors.index.json`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ShardIndex {
+    #[serde(default)]
+    pub metadata: ShardIndexMetadata,
+    /// Tensor name to shard file name, relative to the index.
+    pub weight_map: BTreeMap<String, String>,
+}
+
+impl ShardIndex {
+    pub fn open<P: AsRef<Path>>(path: P) -> Self {
+        Self::try_open(path).unwrap()
+    }
+
+    pub fn try_open<P: AsRef<Path>>(path: P) -> Result<Self, MLXError> {
+        let path = path.as_ref();
+        let json = fs::read_to_string(path)?;
+        serde_json::from_str(&json)
+            .map_err(|e| MLXError::InvalidArgument(format!("invalid shard index {:?}: {}", path, e)))
+    }
+
+    /// The shard file names in order, each once.
+    pub fn shards(&self) -> Vec<&str> {
+        let mut shards: Vec<&str> = self.weight_map.values().map(String::as_str).collect();
+        shards.sort();
+        shards.dedup();
+        shards
+    }
+}
+
+/// Reported once a shard has been loaded.
+#[derive(Clone, Debug)]
+pub struct LoadProgress {
+    /// The shard just loaded, starting at 1.
+    pub shard: usize,
+    pub shards: usize,
+    pub path: PathBuf,
+    /// The tensors loaded so far, in all shards.
+    pub tensors: usize,
+    /// The bytes of the tensors loaded so far.
+    pub bytes: usize,
+}
+
+pub fn load_sharded_safetensors<P: AsRef<Path>>(
+    index: P,
+    filter: impl Fn(&str) -> bool,
+    progress: impl FnMut(&LoadProgress),
+) -> HashMap<String, MLXArray> {
+    try_load_sharded_safetensors(index, filter, progress).unwrap()
+}
+
+/// Loads the tensors accepted by `filter` from the shards listed in the `index` file.
+///
+/// The shards are read one after the other and only the accepted tensors are evaluated, so the
+/// peak memory is that of the accepted tensors rather than of the whole checkpoint. Shards
+/// holding no accepted tensor are not opened.
+pub fn try_load_sharded_safetensors<P: AsRef<Path>>(
+    index: P,
+    filter: impl Fn(&str) -> bool,
+    mut progress: impl FnMut(&LoadProgress),
+) -> Result<HashMap<String, MLXArray>, MLXError> {
+    let index_path = index.as_ref();
+    let index = ShardIndex::try_open(index_path)?;
+    let dir = index_path.parent().unwrap_or(Path::new(""));
+
+    let mut names_by_shard: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    for (name, shard) in &index.weight_map {
+        if filter(name) {
+            names_by_shard.entry(shard).or_default().push(name);
+        }
+    }
+
+    let mut arrays = HashMap::new();
+    let mut bytes = 0;
+    let shards = names_by_shard.len();
+    for (i, (shard, names)) in names_by_shard.into_iter().enumerate() {
+        let path = dir.join(shard);
+        let path_str = path.to_str().ok_or_else(|| {
+            MLXError::InvalidArgument(format!("non utf-8 path {:?}", path))
+        })?;
+        let data = SafeTensors::try_new(path_str)?.data();
+        let mut loaded = Vec::with_capacity(names.len());
+        for name in names {
+            let array = data.get(name).ok_or_else(|| {
+                MLXError::InvalidArgument(format!("{} is not in shard {:?}", name, path))
+            })?;
+            loaded.push((name, array));
+        }
+        // read the tensors now, before the next shard is opened
+        try_eval(&loaded.iter().map(|(_, a)| a).collect::<Vec<_>>())?;
+        for (name, array) in loaded {
+            bytes += array.nbytes();
+            arrays.insert(name.to_string(), array);
+        }
+        progress(&LoadProgress { shard: i + 1, shards, path, tensors: arrays.len(), bytes });
+    }
+    Ok(arrays)
+}
+
+pub fn save_sharded_safetensors<P: AsRef<Path>>(
+    dir: P,
+    arrays: &HashMap<String, MLXArray>,
+    max_shard_size: usize,
+) -> ShardIndex {
+    try_save_sharded_safetensors(dir, arrays, max_shard_size).unwrap()
+}
+
+/// Writes `arrays` to `dir` as `model-00001-of-0000N.safetensors` shards of at most
+/// `max_shard_size` bytes of tensor data, plus the [`INDEX_FILE`] listing them.
+///
+/// Tensors are assigned to shards in name order, a tensor larger than `max_shard_size` gets a
+/// shard of its own.
+pub fn try_save_sharded_safetensors<P: AsRef<Path>>(
+    dir: P,
+    arrays: &HashMap<String, MLXArray>,
+    max_shard_size: usize,
+) -> Result<ShardIndex, MLXError> {
+    let dir = dir.as_ref();
+    fs::create_dir_all(dir)?;
+
+    let mut names: Vec<&String> = arrays.keys().collect();
+    names.sort();
+    let mut shards: Vec<Vec<&String>> = Vec::new();
+    let mut shard_size = 0;
+    for name in names {
+        let nbytes = arrays[name].nbytes();
+        match shards.last_mut() {
+            Some(shard) if shard_size + nbytes <= max_shard_size => shard.push(name),
+            _ => {
+                shards.push(vec![name]);
+                shard_size = 0;
+            }
+        }
+        shard_size += nbytes;
+    }
+
+    let mut index = ShardIndex {
+        metadata: ShardIndexMetadata {
+            total_size: Some(arrays.values().map(|a| a.nbytes() as u64).sum()),
+        },
+        weight_map: BTreeMap::new(),
+    };
+    let count = shards.len();
+    for (i, names) in shards.into_iter().enumerate() {
+        let file = format!("model-{:05}-of-{:05}.safetensors", i + 1, count);
+        let shard: HashMap<String, MLXArray> =
+            names.iter().map(|name| ((*name).clone(), arrays[*name].clone())).collect();
+        try_save_safetensors(dir.join(&file), &shard, &HashMap::new())?;
+        for name in names {
+            index.weight_map.insert(name.clone(), file.clone());
+        }
+    }
+
+    let json = serde_json::to_string_pretty(&index)
+        .map_err(|e| MLXError::InvalidArgument(format!("cannot serialize shard index: {}", e)))?;
+    fs::write(dir.join(INDEX_FILE), json)?;
+    Ok(index)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::error::MLXError;
+    use crate::MLXArray;
+
+    use super::{
+        load_sharded_safetensors, save_sharded_safetensors, try_load_sharded_safetensors, ShardIndex, INDEX_FILE,
+    };
+
+    fn arrays() -> HashMap<String, MLXArray> {
+        [
+            ("a.weight", MLXArray::array(&[1.0f32, 2.0, 3.0, 4.0], &[2, 2])),
+            ("b.weight", MLXArray::array(&[5.0f32, 6.0, 7.0, 8.0], &[4])),
+            ("c.bias", MLXArray::array(&[9.0f32, 10.0], &[2])),
+            ("d.weight", MLXArray::ones::<f32>(&[16])),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v))
+        .collect()
+    }
+
+    #[test]
+    fn test_save_sharded() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().join("checkpoint");
+        let index = save_sharded_safetensors(&dir, &arrays(), 32);
+        assert_eq!(Some(26 * 4), index.metadata.total_size);
+        // a and b fill the first shard, c starts the second, d is too large to join it
+        assert_eq!(
+            vec![
+                "model-00001-of-00003.safetensors",
+                "model-00002-of-00003.safetensors",
+                "model-00003-of-00003.safetensors"
+            ],
+            index.shards()
+        );
+        assert_eq!("model-00001-of-00003.safetensors", index.weight_map["b.weight"]);
+        assert_eq!("model-00002-of-00003.safetensors", index.weight_map["c.bias"]);
+        assert_eq!(index, ShardIndex::open(dir.join(INDEX_FILE)));
+    }
+
+    #[test]
+    fn test_load_sharded() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().join("checkpoint");
+        save_sharded_safetensors(&dir, &arrays(), 32);
+
+        let mut reports = Vec::new();
+        let loaded = load_sharded_safetensors(dir.join(INDEX_FILE), |_| true, |p| reports.push(p.clone()));
+        assert_eq!(4, loaded.len());
+        assert_eq!(&[2, 2], loaded["a.weight"].shape());
+        assert_eq!(&[9.0, 10.0], loaded["c.bias"].to_slice::<f32>().unwrap());
+        assert_eq!(vec![(1, 3, 2), (2, 3, 3), (3, 3, 4)],
+            reports.iter().map(|p| (p.shard, p.shards, p.tensors)).collect::<Vec<_>>());
+        assert_eq!(26 * 4, reports[2].bytes);
+
+        // only the shards holding a requested tensor are read
+        let mut shards = 0;
+        let loaded = load_sharded_safetensors(dir.join(INDEX_FILE), |n| n.ends_with(".weight"), |_| shards += 1);
+        assert_eq!(3, loaded.len());
+        assert_eq!(2, shards);
+
+        let r = try_load_sharded_safetensors(dir.join("missing.json"), |_| true, |_| {});
+        assert!(matches!(r, Err(MLXError::Io(_))));
+    }
+}
@@ -1,19 +1,36 @@
+//! Rust bindings for [MLX](https://github.com/ml-explore/mlx).
+//!
+//! Ops take their array inputs by reference and run on the default stream of the current thread
+//! (see [`stream::with_stream`]), each has an `_on` form taking an explicit `&MLXStream` instead.
+//! Both have a `try_` twin that returns the mlx error instead of panicking.
+
 #![allow(non_upper_case_globals)]
 #![allow(non_camel_case_types)]
 #![allow(non_snake_case)]
 
 pub use array::*;
+pub use error::MLXError;
 pub use vector_array::*;
 
 pub mod array;
 pub mod array_op;
 pub mod closure;
 pub mod compile;
+pub mod conv;
 pub mod device;
+pub mod error;
+pub mod eval;
+pub mod fft;
+pub mod index;
 pub mod from_array;
 pub mod io;
+pub mod linalg;
+pub mod memory;
+mod npy;
 mod object;
 pub mod random;
+pub mod scatter;
+pub mod shape;
 pub mod stream;
 mod string;
 pub mod to_array;
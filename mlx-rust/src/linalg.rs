@@ -0,0 +1,328 @@
+//! Linear algebra routines.
+//!
+//! The factorizations, inverses and solvers are only implemented on the CPU in MLX, so their forms
+//! without an explicit stream run on the default CPU stream instead of the default stream.
+//! [`norm`] runs on the default stream like any other op.
+
+use std::ffi::CString;
+
+use mlx_sys::{
+    mlx_linalg_cholesky, mlx_linalg_cholesky_inv, mlx_linalg_eigh, mlx_linalg_inv,
+    mlx_linalg_norm, mlx_linalg_norm_l2, mlx_linalg_norm_matrix, mlx_linalg_pinv, mlx_linalg_qr,
+    mlx_linalg_solve, mlx_linalg_svd, mlx_linalg_tri_inv,
+};
+
+use crate::array::MLXArray;
+use crate::device::MLXDevice;
+use crate::error::{guard, MLXError};
+use crate::stream::{get_default_stream, MLXStream};
+use crate::VectorMLXArray;
+
+/// The order of a norm, see [`norm`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NormOrd {
+    /// `sum(abs(x)^p)^(1/p)` for vectors, for matrices `1`, `-1`, `2`, `-2`, `inf` and `-inf`
+    /// are supported like in NumPy.
+    P(f64),
+    /// The Frobenius norm of a matrix.
+    Fro,
+    /// The nuclear norm of a matrix, the sum of its singular values.
+    Nuc,
+}
+
+fn cpu_stream() -> MLXStream {
+    MLXStream::default_stream(MLXDevice::cpu())
+}
+
+fn unpack<const N: usize>(arrays: VectorMLXArray, name: &str) -> Result<[MLXArray; N], MLXError> {
+    arrays
+        .to_vec()
+        .try_into()
+        .map_err(|v: Vec<MLXArray>| MLXError::Backend(format!("{} returned {} arrays, expected {}", name, v.len(), N)))
+}
+
+/// Vector or matrix norm over `axes`, all of `x` if `None`.
+///
+/// Without `ord` this is the 2-norm of the flattened axes, otherwise `axes` must name one axis
+/// (vector norm) or two axes (matrix norm). The nuclear and the `2` / `-2` matrix norms take
+/// singular values, which needs a CPU stream.
+pub fn norm(x: &MLXArray, ord: Option<NormOrd>, axes: Option<&[i32]>, keep_dims: bool) -> MLXArray {
+    try_norm(x, ord, axes, keep_dims).unwrap()
+}
+
+pub fn norm_on(x: &MLXArray, ord: Option<NormOrd>, axes: Option<&[i32]>, keep_dims: bool, stream: &MLXStream) -> MLXArray {
+    try_norm_on(x, ord, axes, keep_dims, stream).unwrap()
+}
+
+pub fn try_norm(x: &MLXArray, ord: Option<NormOrd>, axes: Option<&[i32]>, keep_dims: bool) -> Result<MLXArray, MLXError> {
+    try_norm_on(x, ord, axes, keep_dims, &get_default_stream())
+}
+
+pub fn try_norm_on(x: &MLXArray, ord: Option<NormOrd>, axes: Option<&[i32]>, keep_dims: bool, stream: &MLXStream) -> Result<MLXArray, MLXError> {
+    let all: Vec<i32>;
+    let axes = match axes {
+        Some(axes) => axes,
+        None => {
+            all = (0..x.ndim() as i32).collect();
+            &all
+        }
+    };
+    if ord.is_some() && axes.len() != 1 && axes.len() != 2 {
+        return Err(MLXError::InvalidArgument(format!(
+            "a norm order needs one or two axes, got {}",
+            axes.len()
+        )));
+    }
+    let handle = match ord {
+        None => guard(|| unsafe {
+            mlx_linalg_norm_l2(x.as_ptr(), axes.as_ptr(), axes.len(), keep_dims, stream.as_ptr())
+        })?,
+        Some(NormOrd::P(p)) => guard(|| unsafe {
+            mlx_linalg_norm(x.as_ptr(), p, axes.as_ptr(), axes.len(), keep_dims, stream.as_ptr())
+        })?,
+        Some(ord) => {
+            if axes.len() != 2 {
+                return Err(MLXError::InvalidArgument(format!("{:?} is only defined for matrices", ord)));
+            }
+            let name = CString::new(if ord == NormOrd::Fro { "fro" } else { "nuc" }).unwrap();
+            guard(|| unsafe {
+                mlx_linalg_norm_matrix(
+                    x.as_ptr(),
+                    name.as_ptr(),
+                    axes.as_ptr(),
+                    axes.len(),
+                    keep_dims,
+                    stream.as_ptr(),
+                )
+            })?
+        }
+    };
+    Ok(MLXArray::from_raw(handle))
+}
+
+/// QR factorisation of a matrix, returns `(q, r)`.
+pub fn qr(x: &MLXArray) -> (MLXArray, MLXArray) {
+    try_qr(x).unwrap()
+}
+
+pub fn qr_on(x: &MLXArray, stream: &MLXStream) -> (MLXArray, MLXArray) {
+    try_qr_on(x, stream).unwrap()
+}
+
+pub fn try_qr(x: &MLXArray) -> Result<(MLXArray, MLXArray), MLXError> {
+    try_qr_on(x, &cpu_stream())
+}
+
+pub fn try_qr_on(x: &MLXArray, stream: &MLXStream) -> Result<(MLXArray, MLXArray), MLXError> {
+    let handle = guard(|| unsafe { mlx_linalg_qr(x.as_ptr(), stream.as_ptr()) })?;
+    let [q, r] = unpack(VectorMLXArray::from_raw(handle), "qr")?;
+    Ok((q, r))
+}
+
+/// Singular value decomposition, returns `(u, s, vt)` with `x = u @ diag(s) @ vt`.
+pub fn svd(x: &MLXArray) -> (MLXArray, MLXArray, MLXArray) {
+    try_svd(x).unwrap()
+}
+
+pub fn svd_on(x: &MLXArray, stream: &MLXStream) -> (MLXArray, MLXArray, MLXArray) {
+    try_svd_on(x, stream).unwrap()
+}
+
+pub fn try_svd(x: &MLXArray) -> Result<(MLXArray, MLXArray, MLXArray), MLXError> {
+    try_svd_on(x, &cpu_stream())
+}
+
+pub fn try_svd_on(x: &MLXArray, stream: &MLXStream) -> Result<(MLXArray, MLXArray, MLXArray), MLXError> {
+    let handle = guard(|| unsafe { mlx_linalg_svd(x.as_ptr(), stream.as_ptr()) })?;
+    let [u, s, vt] = unpack(VectorMLXArray::from_raw(handle), "svd")?;
+    Ok((u, s, vt))
+}
+
+pub fn inv(x: &MLXArray) -> MLXArray {
+    try_inv(x).unwrap()
+}
+
+pub fn inv_on(x: &MLXArray, stream: &MLXStream) -> MLXArray {
+    try_inv_on(x, stream).unwrap()
+}
+
+pub fn try_inv(x: &MLXArray) -> Result<MLXArray, MLXError> {
+    try_inv_on(x, &cpu_stream())
+}
+
+pub fn try_inv_on(x: &MLXArray, stream: &MLXStream) -> Result<MLXArray, MLXError> {
+    let handle = guard(|| unsafe { mlx_linalg_inv(x.as_ptr(), stream.as_ptr()) })?;
+    Ok(MLXArray::from_raw(handle))
+}
+
+/// Moore-Penrose pseudo inverse, also defined for singular and non square matrices.
+pub fn pinv(x: &MLXArray) -> MLXArray {
+    try_pinv(x).unwrap()
+}
+
+pub fn pinv_on(x: &MLXArray, stream: &MLXStream) -> MLXArray {
+    try_pinv_on(x, stream).unwrap()
+}
+
+pub fn try_pinv(x: &MLXArray) -> Result<MLXArray, MLXError> {
+    try_pinv_on(x, &cpu_stream())
+}
+
+pub fn try_pinv_on(x: &MLXArray, stream: &MLXStream) -> Result<MLXArray, MLXError> {
+    let handle = guard(|| unsafe { mlx_linalg_pinv(x.as_ptr(), stream.as_ptr()) })?;
+    Ok(MLXArray::from_raw(handle))
+}
+
+/// Cholesky factor of a symmetric positive definite matrix, lower triangular unless `upper`.
+pub fn cholesky(x: &MLXArray, upper: bool) -> MLXArray {
+    try_cholesky(x, upper).unwrap()
+}
+
+pub fn cholesky_on(x: &MLXArray, upper: bool, stream: &MLXStream) -> MLXArray {
+    try_cholesky_on(x, upper, stream).unwrap()
+}
+
+pub fn try_cholesky(x: &MLXArray, upper: bool) -> Result<MLXArray, MLXError> {
+    try_cholesky_on(x, upper, &cpu_stream())
+}
+
+pub fn try_cholesky_on(x: &MLXArray, upper: bool, stream: &MLXStream) -> Result<MLXArray, MLXError> {
+    let handle = guard(|| unsafe { mlx_linalg_cholesky(x.as_ptr(), upper, stream.as_ptr()) })?;
+    Ok(MLXArray::from_raw(handle))
+}
+
+/// Inverse of a matrix given its Cholesky factor `l`, see [`cholesky`].
+pub fn cholesky_inv(l: &MLXArray, upper: bool) -> MLXArray {
+    try_cholesky_inv(l, upper).unwrap()
+}
+
+pub fn cholesky_inv_on(l: &MLXArray, upper: bool, stream: &MLXStream) -> MLXArray {
+    try_cholesky_inv_on(l, upper, stream).unwrap()
+}
+
+pub fn try_cholesky_inv(l: &MLXArray, upper: bool) -> Result<MLXArray, MLXError> {
+    try_cholesky_inv_on(l, upper, &cpu_stream())
+}
+
+pub fn try_cholesky_inv_on(l: &MLXArray, upper: bool, stream: &MLXStream) -> Result<MLXArray, MLXError> {
+    let handle = guard(|| unsafe { mlx_linalg_cholesky_inv(l.as_ptr(), upper, stream.as_ptr()) })?;
+    Ok(MLXArray::from_raw(handle))
+}
+
+/// Solves `a @ x = b` for `x`.
+pub fn solve(a: &MLXArray, b: &MLXArray) -> MLXArray {
+    try_solve(a, b).unwrap()
+}
+
+pub fn solve_on(a: &MLXArray, b: &MLXArray, stream: &MLXStream) -> MLXArray {
+    try_solve_on(a, b, stream).unwrap()
+}
+
+pub fn try_solve(a: &MLXArray, b: &MLXArray) -> Result<MLXArray, MLXError> {
+    try_solve_on(a, b, &cpu_stream())
+}
+
+pub fn try_solve_on(a: &MLXArray, b: &MLXArray, stream: &MLXStream) -> Result<MLXArray, MLXError> {
+    let handle = guard(|| unsafe { mlx_linalg_solve(a.as_ptr(), b.as_ptr(), stream.as_ptr()) })?;
+    Ok(MLXArray::from_raw(handle))
+}
+
+/// Eigen decomposition of a symmetric matrix, returns the eigenvalues in ascending order and the
+/// eigenvectors as columns. Only the lower triangle is read, or the upper one with `upper`.
+pub fn eigh(x: &MLXArray, upper: bool) -> (MLXArray, MLXArray) {
+    try_eigh(x, upper).unwrap()
+}
+
+pub fn eigh_on(x: &MLXArray, upper: bool, stream: &MLXStream) -> (MLXArray, MLXArray) {
+    try_eigh_on(x, upper, stream).unwrap()
+}
+
+pub fn try_eigh(x: &MLXArray, upper: bool) -> Result<(MLXArray, MLXArray), MLXError> {
+    try_eigh_on(x, upper, &cpu_stream())
+}
+
+pub fn try_eigh_on(x: &MLXArray, upper: bool, stream: &MLXStream) -> Result<(MLXArray, MLXArray), MLXError> {
+    let uplo = CString::new(if upper { "U" } else { "L" }).unwrap();
+    let handle = guard(|| unsafe { mlx_linalg_eigh(x.as_ptr(), uplo.as_ptr(), stream.as_ptr()) })?;
+    let [w, v] = unpack(VectorMLXArray::from_raw(handle), "eigh")?;
+    Ok((w, v))
+}
+
+/// Inverse of a triangular matrix, lower triangular unless `upper`.
+pub fn tri_inv(x: &MLXArray, upper: bool) -> MLXArray {
+    try_tri_inv(x, upper).unwrap()
+}
+
+pub fn tri_inv_on(x: &MLXArray, upper: bool, stream: &MLXStream) -> MLXArray {
+    try_tri_inv_on(x, upper, stream).unwrap()
+}
+
+pub fn try_tri_inv(x: &MLXArray, upper: bool) -> Result<MLXArray, MLXError> {
+    try_tri_inv_on(x, upper, &cpu_stream())
+}
+
+pub fn try_tri_inv_on(x: &MLXArray, upper: bool, stream: &MLXStream) -> Result<MLXArray, MLXError> {
+    let handle = guard(|| unsafe { mlx_linalg_tri_inv(x.as_ptr(), upper, stream.as_ptr()) })?;
+    Ok(MLXArray::from_raw(handle))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::array::MLXArray;
+    use crate::linalg::{
+        cholesky, eigh, inv, norm, pinv, qr, solve, svd, tri_inv, try_norm, NormOrd,
+    };
+
+    fn assert_close(expected: &[f32], actual: &MLXArray) {
+        let actual = actual.to_slice::<f32>().unwrap();
+        assert_eq!(expected.len(), actual.len());
+        for (e, a) in expected.iter().zip(actual) {
+            assert!((e - a).abs() < 1e-4, "expected {:?}, got {:?}", expected, actual);
+        }
+    }
+
+    #[test]
+    fn test_norm() {
+        let x = MLXArray::array(&[3.0f32, 4.0], &[2]);
+        assert_close(&[5.0], &norm(&x, None, None, false).reshape(&[1]));
+        assert_close(&[7.0], &norm(&x, Some(NormOrd::P(1.0)), None, false).reshape(&[1]));
+        assert_close(&[4.0], &norm(&x, Some(NormOrd::P(f64::INFINITY)), None, false).reshape(&[1]));
+
+        let m = MLXArray::array(&[1.0f32, 2.0, 3.0, 4.0], &[2, 2]);
+        let fro = norm(&m, Some(NormOrd::Fro), None, false);
+        assert_close(&[30.0f32.sqrt()], &fro.reshape(&[1]));
+        assert!(try_norm(&x, Some(NormOrd::Nuc), None, false).is_err());
+    }
+
+    #[test]
+    fn test_qr_and_svd() {
+        let a = MLXArray::array(&[2.0f32, 1.0, 1.0, 3.0], &[2, 2]);
+        let (q, r) = qr(&a);
+        assert_close(&[2.0, 1.0, 1.0, 3.0], &q.matmul(&r));
+
+        let (u, s, vt) = svd(&a);
+        let us = &u * s.reshape(&[1, 2]);
+        assert_close(&[2.0, 1.0, 1.0, 3.0], &us.matmul(&vt));
+    }
+
+    #[test]
+    fn test_inverse_and_solve() {
+        let a = MLXArray::array(&[4.0f32, 7.0, 2.0, 6.0], &[2, 2]);
+        assert_close(&[0.6, -0.7, -0.2, 0.4], &inv(&a));
+        assert_close(&[0.6, -0.7, -0.2, 0.4], &pinv(&a));
+        let b = MLXArray::array(&[1.0f32, 2.0], &[2, 1]);
+        assert_close(&[-0.8, 0.6], &solve(&a, &b));
+    }
+
+    #[test]
+    fn test_cholesky_eigh_and_tri_inv() {
+        let a = MLXArray::array(&[4.0f32, 2.0, 2.0, 3.0], &[2, 2]);
+        let l = cholesky(&a, false);
+        assert_close(&[2.0, 0.0, 1.0, 2.0f32.sqrt()], &l);
+        assert_close(&[0.5, 0.0, -0.5 / 2.0f32.sqrt(), 1.0 / 2.0f32.sqrt()], &tri_inv(&l, false));
+
+        let (w, _v) = eigh(&a, false);
+        let disc = 17.0f32.sqrt();
+        assert_close(&[(7.0 - disc) / 2.0, (7.0 + disc) / 2.0], &w);
+    }
+}
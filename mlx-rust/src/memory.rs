@@ -0,0 +1,97 @@
+//! Introspection and limits of the mlx allocator.
+//!
+//! All sizes are in bytes. Memory freed by arrays goes to a cache first and is reused by later
+//! allocations, so [`active_memory`] only counts memory held by live arrays while
+//! [`cache_memory`] counts the memory kept for reuse.
+
+use mlx_sys::{
+    mlx_metal_clear_cache, mlx_metal_get_active_memory, mlx_metal_get_cache_memory,
+    mlx_metal_get_peak_memory, mlx_metal_reset_peak_memory, mlx_metal_set_cache_limit,
+    mlx_metal_set_memory_limit, mlx_metal_set_wired_limit,
+};
+
+use crate::error::{guard_unit, MLXError};
+
+/// Memory held by live arrays, not including the cache.
+pub fn active_memory() -> usize {
+    unsafe { mlx_metal_get_active_memory() }
+}
+
+/// The largest [`active_memory`] since the start of the program or the last
+/// [`reset_peak_memory`].
+pub fn peak_memory() -> usize {
+    unsafe { mlx_metal_get_peak_memory() }
+}
+
+pub fn reset_peak_memory() {
+    unsafe { mlx_metal_reset_peak_memory() }
+}
+
+/// Memory kept in the cache for reuse.
+pub fn cache_memory() -> usize {
+    unsafe { mlx_metal_get_cache_memory() }
+}
+
+/// Returns the cached memory to the system.
+pub fn clear_cache() {
+    unsafe { mlx_metal_clear_cache() }
+}
+
+/// Bounds the memory mlx tries to stay under, returns the previous limit.
+///
+/// When `relaxed`, allocations above the limit still succeed after the cache has been
+/// emptied, otherwise they fail.
+pub fn set_memory_limit(limit: usize, relaxed: bool) -> usize {
+    try_set_memory_limit(limit, relaxed).unwrap()
+}
+
+pub fn try_set_memory_limit(limit: usize, relaxed: bool) -> Result<usize, MLXError> {
+    let mut previous = 0;
+    guard_unit(|| unsafe { previous = mlx_metal_set_memory_limit(limit, relaxed) })?;
+    Ok(previous)
+}
+
+/// Bounds the size of the cache, `0` disables caching. Returns the previous limit.
+pub fn set_cache_limit(limit: usize) -> usize {
+    try_set_cache_limit(limit).unwrap()
+}
+
+pub fn try_set_cache_limit(limit: usize) -> Result<usize, MLXError> {
+    let mut previous = 0;
+    guard_unit(|| unsafe { previous = mlx_metal_set_cache_limit(limit) })?;
+    Ok(previous)
+}
+
+/// Keeps up to `limit` bytes wired (resident, never paged out), returns the previous limit.
+/// Fails if `limit` is larger than what the system allows to wire.
+pub fn set_wired_limit(limit: usize) -> usize {
+    try_set_wired_limit(limit).unwrap()
+}
+
+pub fn try_set_wired_limit(limit: usize) -> Result<usize, MLXError> {
+    let mut previous = 0;
+    guard_unit(|| unsafe { previous = mlx_metal_set_wired_limit(limit) })?;
+    Ok(previous)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::array::MLXArray;
+    use crate::memory::{active_memory, clear_cache, peak_memory};
+
+    // other tests allocate and free on the same allocator concurrently, so only bounds that
+    // hold regardless of them are checked: `x` stays allocated and the peak never decreases
+    #[test]
+    fn test_memory_counters() {
+        let x = MLXArray::ones::<f32>(&[1024, 1024]);
+        x.eval();
+        let active = active_memory();
+        assert!(active >= x.nbytes());
+        assert!(peak_memory() >= active);
+
+        let peak = peak_memory();
+        drop(x);
+        clear_cache();
+        assert!(peak_memory() >= peak);
+    }
+}
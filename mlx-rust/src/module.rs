@@ -2,26 +2,116 @@ use half::f16;
 use std::borrow::Cow;
 use std::collections::HashMap;
 
-use crate::io::SafeTensors;
-use crate::stream::get_default_stream;
+use crate::error::MLXError;
+use crate::io::mmap::try_load_mmap_safetensors;
+use crate::io::shard::{try_load_sharded_safetensors, try_save_sharded_safetensors, LoadProgress};
+use crate::io::{try_save_safetensors, SafeTensors};
 use crate::MLXArray;
 
 pub trait Module {
     type Input;
     fn forward(&self, value: Self::Input) -> MLXArray;
 
+    /// Inserts the parameters under their dotted names, e.g. `layers.0.self_attn.q_proj.weight`,
+    /// the names [`Module::update_named_params`] expects.
+    fn gather_named_params(&self, prefix: &str, params: &mut HashMap<String, MLXArray>);
+
     fn update_named_params(&mut self, prefix: &str, params: &mut HashMap<String, MLXArray>);
 
+    fn named_params(&self) -> HashMap<String, MLXArray> {
+        let mut params = HashMap::new();
+        self.gather_named_params("", &mut params);
+        params
+    }
+
+    /// The number of bytes held by the parameters.
+    fn nbytes(&self) -> usize {
+        self.named_params().values().map(|p| p.nbytes()).sum()
+    }
+
     fn update_by_safetensors<P: AsRef<std::path::Path>>(&mut self, filenames: &[P]) {
+        self.try_update_by_safetensors(filenames).unwrap()
+    }
+
+    fn try_update_by_safetensors<P: AsRef<std::path::Path>>(&mut self, filenames: &[P]) -> Result<(), MLXError> {
         let mut st_tensors: HashMap<String, MLXArray> = HashMap::new();
         for filename in filenames {
-            let st = SafeTensors::new(filename.as_ref().to_str().unwrap(), get_default_stream());
+            let path = filename.as_ref().to_str().ok_or_else(|| {
+                MLXError::InvalidArgument(format!("non utf-8 path {:?}", filename.as_ref()))
+            })?;
+            let st = SafeTensors::try_new(path)?;
             for (name, view) in st.data() {
                 st_tensors.insert(name, view);
             }
         }
         // println!("embedding weight: {}", st_tensors.get("model.embed_tokens.weight"));
         self.update_named_params("", &mut st_tensors);
+        Ok(())
+    }
+
+    fn update_by_mmap_safetensors<P: AsRef<std::path::Path>>(&mut self, filenames: &[P]) {
+        self.try_update_by_mmap_safetensors(filenames).unwrap()
+    }
+
+    /// Like [`Module::update_by_safetensors`], but the parameters point into memory-mapped files
+    /// instead of being copied, see [`crate::io::mmap`].
+    fn try_update_by_mmap_safetensors<P: AsRef<std::path::Path>>(&mut self, filenames: &[P]) -> Result<(), MLXError> {
+        let mut params = try_load_mmap_safetensors(filenames)?;
+        self.update_named_params("", &mut params);
+        Ok(())
+    }
+
+    /// Writes the parameters under their dotted names, so that [`Module::update_by_safetensors`]
+    /// can load them back.
+    fn save_safetensors<P: AsRef<std::path::Path>>(&self, path: P) {
+        self.try_save_safetensors(path).unwrap()
+    }
+
+    fn try_save_safetensors<P: AsRef<std::path::Path>>(&self, path: P) -> Result<(), MLXError> {
+        try_save_safetensors(path, &self.named_params(), &HashMap::new())
+    }
+
+    fn update_by_sharded_safetensors<P: AsRef<std::path::Path>>(
+        &mut self,
+        index: P,
+        progress: impl FnMut(&LoadProgress),
+    ) {
+        self.try_update_by_sharded_safetensors(index, progress).unwrap()
+    }
+
+    /// Loads the parameters from the shards listed in a `model.safetensors.index.json`, one shard
+    /// at a time and skipping the tensors the module does not have. `progress` is called after
+    /// each shard.
+    fn try_update_by_sharded_safetensors<P: AsRef<std::path::Path>>(
+        &mut self,
+        index: P,
+        progress: impl FnMut(&LoadProgress),
+    ) -> Result<(), MLXError> {
+        let names = self.named_params();
+        let mut params = try_load_sharded_safetensors(index, |name| names.contains_key(name), progress)?;
+        let mut missing: Vec<_> = names.keys().filter(|name| !params.contains_key(*name)).collect();
+        if !missing.is_empty() {
+            missing.sort();
+            return Err(MLXError::InvalidArgument(format!("parameters {:?} not found in the checkpoint", missing)));
+        }
+        drop(names);
+        self.update_named_params("", &mut params);
+        Ok(())
+    }
+
+    fn save_sharded_safetensors<P: AsRef<std::path::Path>>(&self, dir: P, max_shard_size: usize) {
+        self.try_save_sharded_safetensors(dir, max_shard_size).unwrap()
+    }
+
+    /// Writes the parameters to `dir` in shards of at most `max_shard_size` bytes, with the index
+    /// [`Module::update_by_sharded_safetensors`] reads.
+    fn try_save_sharded_safetensors<P: AsRef<std::path::Path>>(
+        &self,
+        dir: P,
+        max_shard_size: usize,
+    ) -> Result<(), MLXError> {
+        try_save_sharded_safetensors(dir, &self.named_params(), max_shard_size)?;
+        Ok(())
     }
 }
 
@@ -147,13 +237,12 @@ where
     // }
 
     fn gather_by_name(&self, params: &mut HashMap<String, MLXArray>, prefix: &str, name: &str) {
-        // let p: Cow<'_, str> = if prefix.is_empty() {
-        //     name.into()
-        // } else {
-        //     format!("{}.{}", prefix, name).into()
-        // };
-        // self.gather_named_params(&p, params)
-        todo!()
+        let p: Cow<'_, str> = if prefix.is_empty() {
+            name.into()
+        } else {
+            format!("{}.{}", prefix, name).into()
+        };
+        self.gather_named_params(&p, params)
     }
 
     fn update_by_name(&mut self, params: &mut HashMap<String, MLXArray>, prefix: &str, name: &str) {
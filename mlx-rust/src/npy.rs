@@ -0,0 +1,412 @@
+//! Reading and writing the NumPy `.npy` and `.npz` formats.
+//!
+//! `bfloat16` has no NumPy descr, it is stored as `<V2` like MLX does.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Seek, Write};
+use std::path::Path;
+
+use zip::result::ZipError;
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::error::MLXError;
+use crate::r#type::Dtype;
+use crate::MLXArray;
+
+const MAGIC: &[u8] = b"\x93NUMPY";
+
+fn invalid(msg: impl Into<String>) -> MLXError {
+    MLXError::InvalidArgument(msg.into())
+}
+
+fn zip_error(e: ZipError) -> MLXError {
+    match e {
+        ZipError::Io(e) => MLXError::Io(e),
+        e => invalid(format!("invalid npz file: {}", e)),
+    }
+}
+
+/// The dtype of a descr such as `<f4`, and whether its bytes need swapping.
+fn parse_descr(descr: &str) -> Result<(Dtype, bool), MLXError> {
+    let unsupported = || invalid(format!("unsupported npy descr {:?}", descr));
+    let (order, kind) = match descr.as_bytes().first() {
+        Some(b'<' | b'>' | b'|' | b'=') => descr.split_at(1),
+        _ => ("|", descr),
+    };
+    let dtype = match kind {
+        "b1" => Dtype::Bool,
+        "u1" => Dtype::U8,
+        "u2" => Dtype::U16,
+        "u4" => Dtype::U32,
+        "u8" => Dtype::U64,
+        "i1" => Dtype::I8,
+        "i2" => Dtype::I16,
+        "i4" => Dtype::I32,
+        "i8" => Dtype::I64,
+        "f2" => Dtype::F16,
+        "V2" => Dtype::BF16,
+        "f4" => Dtype::F32,
+        "c8" => Dtype::Complex64,
+        _ => return Err(unsupported()),
+    };
+    let swap = dtype.size_of() > 1 && order == ">";
+    Ok((dtype, swap))
+}
+
+fn descr(dtype: Dtype) -> &'static str {
+    match dtype {
+        Dtype::Bool => "|b1",
+        Dtype::U8 => "|u1",
+        Dtype::U16 => "<u2",
+        Dtype::U32 => "<u4",
+        Dtype::U64 => "<u8",
+        Dtype::I8 => "|i1",
+        Dtype::I16 => "<i2",
+        Dtype::I32 => "<i4",
+        Dtype::I64 => "<i8",
+        Dtype::F16 => "<f2",
+        Dtype::BF16 => "<V2",
+        Dtype::F32 => "<f4",
+        Dtype::Complex64 => "<c8",
+    }
+}
+
+struct Header {
+    dtype: Dtype,
+    swap: bool,
+    fortran_order: bool,
+    shape: Vec<i32>,
+}
+
+/// The text following `'key':` in the header dict.
+fn header_value<'a>(header: &'a str, key: &str) -> Result<&'a str, MLXError> {
+    [format!("'{}'", key), format!("\"{}\"", key)]
+        .iter()
+        .find_map(|k| header.find(k.as_str()).map(|i| &header[i + k.len()..]))
+        .and_then(|rest| rest.trim_start().strip_prefix(':'))
+        .map(str::trim_start)
+        .ok_or_else(|| invalid(format!("npy header {:?} has no {}", header, key)))
+}
+
+fn parse_header(header: &str) -> Result<Header, MLXError> {
+    let descr = header_value(header, "descr")?;
+    let quote = descr.chars().next().filter(|c| *c == '\'' || *c == '"');
+    let descr = quote
+        .and_then(|q| descr[1..].split(q).next())
+        .ok_or_else(|| invalid(format!("unsupported npy descr in {:?}", header)))?;
+    let (dtype, swap) = parse_descr(descr)?;
+
+    let fortran_order = header_value(header, "fortran_order")?;
+    let fortran_order = if fortran_order.starts_with("True") {
+        true
+    } else if fortran_order.starts_with("False") {
+        false
+    } else {
+        return Err(invalid(format!("invalid fortran_order in npy header {:?}", header)));
+    };
+
+    let shape = header_value(header, "shape")?;
+    let shape = shape
+        .strip_prefix('(')
+        .and_then(|s| s.split(')').next())
+        .ok_or_else(|| invalid(format!("invalid shape in npy header {:?}", header)))?;
+    let shape = shape
+        .split(',')
+        .map(str::trim)
+        .filter(|d| !d.is_empty())
+        .map(|d| {
+            d.trim_end_matches('L')
+                .parse::<i32>()
+                .ok()
+                .filter(|d| *d >= 0)
+                .ok_or_else(|| invalid(format!("invalid shape in npy header {:?}", header)))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(Header { dtype, swap, fortran_order, shape })
+}
+
+fn read_npy(reader: &mut impl Read) -> Result<MLXArray, MLXError> {
+    let mut magic = [0u8; 8];
+    reader.read_exact(&mut magic)?;
+    if &magic[..6] != MAGIC {
+        return Err(invalid("not a npy file"));
+    }
+    let header_len = match magic[6] {
+        1 => {
+            let mut len = [0u8; 2];
+            reader.read_exact(&mut len)?;
+            u16::from_le_bytes(len) as usize
+        }
+        2 | 3 => {
+            let mut len = [0u8; 4];
+            reader.read_exact(&mut len)?;
+            u32::from_le_bytes(len) as usize
+        }
+        v => return Err(invalid(format!("unsupported npy version {}", v))),
+    };
+    let mut header = vec![0u8; header_len];
+    reader.read_exact(&mut header)?;
+    let header = String::from_utf8_lossy(&header);
+    let Header { dtype, swap, fortran_order, mut shape } = parse_header(&header)?;
+
+    let nbytes = shape
+        .iter()
+        .try_fold(dtype.size_of(), |n, d| n.checked_mul(*d as usize))
+        .ok_or_else(|| invalid(format!("npy shape {:?} is too large", shape)))?;
+    // grow the buffer as data arrives instead of trusting the header with one large allocation
+    let mut data = Vec::new();
+    reader.take(nbytes as u64).read_to_end(&mut data)?;
+    if data.len() != nbytes {
+        return Err(MLXError::Io(std::io::ErrorKind::UnexpectedEof.into()));
+    }
+    if swap {
+        // complex numbers are two floats, each swapped on its own
+        let width = if dtype == Dtype::Complex64 { 4 } else { dtype.size_of() };
+        data.chunks_exact_mut(width).for_each(|c| c.reverse());
+    }
+
+    // fortran order is the row-major layout of the transposed array
+    if fortran_order {
+        shape.reverse();
+    }
+    let array = MLXArray::from_bytes(&data, &shape, dtype)?;
+    if fortran_order && shape.len() > 1 {
+        let axes: Vec<i32> = (0..shape.len() as i32).rev().collect();
+        array.try_transpose(&axes)
+    } else {
+        Ok(array)
+    }
+}
+
+fn write_npy(writer: &mut impl Write, array: &MLXArray) -> Result<(), MLXError> {
+    let array = array.to_row_major()?;
+
+    let shape = match array.shape() {
+        [d] => format!("({},)", d),
+        shape => format!("({})", shape.iter().map(|d| d.to_string()).collect::<Vec<_>>().join(", ")),
+    };
+    let mut header = format!(
+        "{{'descr': '{}', 'fortran_order': False, 'shape': {}, }}",
+        descr(array.dtype()),
+        shape
+    );
+    // the data starts at a multiple of 64 bytes, the header ends with a newline
+    let version = if header.len() + 11 > u16::MAX as usize { 2 } else { 1 };
+    let prefix_len = if version == 1 { 10 } else { 12 };
+    let padding = (64 - (prefix_len + header.len() + 1) % 64) % 64;
+    header.extend(std::iter::repeat_n(' ', padding));
+    header.push('\n');
+
+    writer.write_all(MAGIC)?;
+    writer.write_all(&[version, 0])?;
+    if version == 1 {
+        writer.write_all(&(header.len() as u16).to_le_bytes())?;
+    } else {
+        writer.write_all(&(header.len() as u32).to_le_bytes())?;
+    }
+    writer.write_all(header.as_bytes())?;
+
+    writer.write_all(array.as_bytes()?)?;
+    Ok(())
+}
+
+pub fn load_npy<P: AsRef<Path>>(path: P) -> MLXArray {
+    try_load_npy(path).unwrap()
+}
+
+/// Loads a `.npy` file, in C or Fortran order.
+pub fn try_load_npy<P: AsRef<Path>>(path: P) -> Result<MLXArray, MLXError> {
+    read_npy(&mut BufReader::new(File::open(path)?))
+}
+
+pub fn save_npy<P: AsRef<Path>>(path: P, array: &MLXArray) {
+    try_save_npy(path, array).unwrap()
+}
+
+/// Saves `array` as a `.npy` file in C order.
+pub fn try_save_npy<P: AsRef<Path>>(path: P, array: &MLXArray) -> Result<(), MLXError> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    write_npy(&mut writer, array)?;
+    writer.flush()?;
+    Ok(())
+}
+
+pub fn load_npz<P: AsRef<Path>>(path: P) -> HashMap<String, MLXArray> {
+    try_load_npz(path).unwrap()
+}
+
+/// Loads every array of a `.npz` file, compressed or not, keyed by name without the `.npy`
+/// extension.
+pub fn try_load_npz<P: AsRef<Path>>(path: P) -> Result<HashMap<String, MLXArray>, MLXError> {
+    read_npz(BufReader::new(File::open(path)?))
+}
+
+fn read_npz(reader: impl Read + Seek) -> Result<HashMap<String, MLXArray>, MLXError> {
+    let mut archive = ZipArchive::new(reader).map_err(zip_error)?;
+    let mut arrays = HashMap::new();
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i).map_err(zip_error)?;
+        let name = file.name();
+        let name = name.strip_suffix(".npy").unwrap_or(name).to_string();
+        let array = read_npy(&mut file)?;
+        arrays.insert(name, array);
+    }
+    Ok(arrays)
+}
+
+pub fn save_npz<P: AsRef<Path>>(path: P, arrays: &HashMap<String, MLXArray>, compressed: bool) {
+    try_save_npz(path, arrays, compressed).unwrap()
+}
+
+/// Saves `arrays` as a `.npz` file, deflating them when `compressed` like `numpy.savez_compressed`.
+pub fn try_save_npz<P: AsRef<Path>>(
+    path: P,
+    arrays: &HashMap<String, MLXArray>,
+    compressed: bool,
+) -> Result<(), MLXError> {
+    let mut zip = ZipWriter::new(BufWriter::new(File::create(path)?));
+    let method = if compressed {
+        CompressionMethod::Deflated
+    } else {
+        CompressionMethod::Stored
+    };
+    for (name, array) in arrays {
+        let options = FileOptions::default()
+            .compression_method(method)
+            .large_file(array.nbytes() >= u32::MAX as usize);
+        zip.start_file(format!("{}.npy", name), options).map_err(zip_error)?;
+        write_npy(&mut zip, array)?;
+    }
+    zip.finish().map_err(zip_error)?.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::io::Cursor;
+
+    use half::{bf16, f16};
+    use num_complex::Complex32;
+
+    use crate::error::MLXError;
+    use crate::r#type::Dtype;
+    use crate::MLXArray;
+
+    use super::{load_npy, load_npz, read_npy, save_npy, save_npz, try_load_npy, write_npy};
+
+    fn round_trip(array: &MLXArray) -> MLXArray {
+        let mut buf = Vec::new();
+        write_npy(&mut buf, array).unwrap();
+        assert_eq!(0, (buf.len() - array.nbytes()) % 64);
+        read_npy(&mut Cursor::new(buf)).unwrap()
+    }
+
+    #[test]
+    fn test_npy_round_trip_all_dtypes() {
+        let arrays = [
+            MLXArray::array(&[true, false, true, true], &[2, 2]),
+            MLXArray::array(&[1u8, 2, 3, 4], &[2, 2]),
+            MLXArray::array(&[1u16, 2, 3, 4], &[2, 2]),
+            MLXArray::array(&[1u32, 2, 3, 4], &[2, 2]),
+            MLXArray::array(&[1u64, 2, 3, u64::MAX], &[2, 2]),
+            MLXArray::array(&[1i8, -2, 3, 4], &[2, 2]),
+            MLXArray::array(&[1i16, -2, 3, 4], &[2, 2]),
+            MLXArray::array(&[1i32, -2, 3, 4], &[2, 2]),
+            MLXArray::array(&[1i64, -2, 3, i64::MIN], &[2, 2]),
+            MLXArray::array(&[f16::ONE, f16::NEG_ONE, f16::ZERO, f16::MAX], &[2, 2]),
+            MLXArray::array(&[bf16::ONE, bf16::NEG_ONE, bf16::ZERO, bf16::MAX], &[2, 2]),
+            MLXArray::array(&[1.5f32, -2.0, 0.0, f32::MAX], &[2, 2]),
+            MLXArray::array(&[Complex32::new(1.0, -1.0), Complex32::new(0.5, 2.0)], &[2]),
+        ];
+        for array in arrays {
+            let loaded = round_trip(&array);
+            assert_eq!(array.dtype(), loaded.dtype());
+            assert_eq!(array.shape(), loaded.shape());
+            assert!(array.equal(&loaded).all_all(false).to_scalar::<bool>().unwrap(), "{}", array.dtype());
+        }
+    }
+
+    #[test]
+    fn test_npy_scalar_and_strided() {
+        let scalar = round_trip(&MLXArray::from(3.0f32));
+        assert_eq!(0, scalar.ndim());
+        assert_eq!(3.0, scalar.to_scalar::<f32>().unwrap());
+
+        let x = MLXArray::array(&[1i32, 2, 3, 4, 5, 6], &[2, 3]).transpose(&[1, 0]);
+        let loaded = round_trip(&x);
+        assert_eq!(&[3, 2], loaded.shape());
+        assert_eq!(&[1, 4, 2, 5, 3, 6], loaded.to_slice::<i32>().unwrap());
+    }
+
+    #[test]
+    fn test_npy_fortran_order_and_big_endian() {
+        fn npy(header: &str, data: &[u8]) -> Vec<u8> {
+            let mut buf = b"\x93NUMPY\x01\x00".to_vec();
+            buf.extend_from_slice(&(header.len() as u16).to_le_bytes());
+            buf.extend_from_slice(header.as_bytes());
+            buf.extend_from_slice(data);
+            buf
+        }
+
+        let data: Vec<u8> = [1i16, 2, 3, 4, 5, 6].iter().flat_map(|v| v.to_le_bytes()).collect();
+        let buf = npy("{'descr': '<i2', 'fortran_order': True, 'shape': (2, 3), }\n", &data);
+        let x = read_npy(&mut Cursor::new(buf)).unwrap();
+        assert_eq!(&[2, 3], x.shape());
+        let x = x.reshape(&[-1]);
+        assert_eq!(&[1, 3, 5, 2, 4, 6], x.to_slice::<i16>().unwrap());
+
+        let data: Vec<u8> = [1.5f32, -2.0].iter().flat_map(|v| v.to_be_bytes()).collect();
+        let buf = npy("{'descr': '>f4', 'fortran_order': False, 'shape': (2,), }\n", &data);
+        let x = read_npy(&mut Cursor::new(buf)).unwrap();
+        assert_eq!(&[1.5, -2.0], x.to_slice::<f32>().unwrap());
+
+        let buf = npy("{'descr': '<f8', 'fortran_order': False, 'shape': (1,), }\n", &[0; 8]);
+        let r = read_npy(&mut Cursor::new(buf));
+        assert!(matches!(r, Err(MLXError::InvalidArgument(msg)) if msg.contains("<f8")));
+
+        let buf = npy("{'descr': '<f4', 'fortran_order': False, 'shape': (-1,), }\n", &[]);
+        assert!(matches!(read_npy(&mut Cursor::new(buf)), Err(MLXError::InvalidArgument(_))));
+        let huge = "{'descr': '<f4', 'fortran_order': False, 'shape': (2147483647, 2147483647, 2147483647), }\n";
+        let buf = npy(huge, &[]);
+        assert!(matches!(read_npy(&mut Cursor::new(buf)), Err(MLXError::InvalidArgument(_))));
+        let buf = npy("{'descr': '<f4', 'fortran_order': False, 'shape': (4,), }\n", &[0; 8]);
+        assert!(matches!(read_npy(&mut Cursor::new(buf)), Err(MLXError::Io(_))));
+    }
+
+    #[test]
+    fn test_npy_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("x.npy");
+        let x = MLXArray::array(&[1.0f32, 2.0, 3.0], &[3]);
+        save_npy(&path, &x);
+        assert_eq!(&[1.0, 2.0, 3.0], load_npy(&path).to_slice::<f32>().unwrap());
+
+        let r = try_load_npy(dir.path().join("missing.npy"));
+        assert!(matches!(r, Err(MLXError::Io(_))));
+    }
+
+    #[test]
+    fn test_npz() {
+        let arrays: HashMap<String, MLXArray> = [
+            ("weight".to_string(), MLXArray::array(&[1.0f32, 2.0, 3.0, 4.0], &[2, 2])),
+            ("ids".to_string(), MLXArray::array(&[7i64, 8], &[2])),
+            ("half".to_string(), MLXArray::array(&[bf16::ONE], &[1])),
+        ]
+        .into_iter()
+        .collect();
+        let dir = tempfile::tempdir().unwrap();
+        for compressed in [false, true] {
+            let path = dir.path().join(format!("arrays_{}.npz", compressed));
+            save_npz(&path, &arrays, compressed);
+            let loaded = load_npz(&path);
+            assert_eq!(3, loaded.len());
+            assert_eq!(&[1.0, 2.0, 3.0, 4.0], loaded["weight"].to_slice::<f32>().unwrap());
+            assert_eq!(&[2, 2], loaded["weight"].shape());
+            assert_eq!(&[7, 8], loaded["ids"].to_slice::<i64>().unwrap());
+            assert_eq!(Dtype::BF16, loaded["half"].dtype());
+        }
+    }
+}
@@ -3,18 +3,35 @@ use std::ops::RangeInclusive;
 use mlx_sys::{mlx_random_categorical, mlx_random_categorical_shape, mlx_random_key, mlx_random_normal, mlx_random_randint, mlx_random_split_equal_parts, mlx_random_uniform};
 
 use crate::{MLXArray, r#type::MlxType, stream::MLXStream};
+use crate::error::{guard, MLXError};
 use crate::stream::get_default_stream;
 
 pub fn key(seed: u64) -> MLXArray {
-    let handle = unsafe { mlx_random_key(seed) };
-    MLXArray::from_raw(handle)
+    try_key(seed).unwrap()
 }
 
-pub fn split(key: MLXArray, num: usize, stream: MLXStream) -> MLXArray {
-    let handle = unsafe {
+pub fn try_key(seed: u64) -> Result<MLXArray, MLXError> {
+    let handle = guard(|| unsafe { mlx_random_key(seed) })?;
+    Ok(MLXArray::from_raw(handle))
+}
+
+pub fn split(key: &MLXArray, num: usize) -> MLXArray {
+    try_split(key, num).unwrap()
+}
+
+pub fn split_on(key: &MLXArray, num: usize, stream: &MLXStream) -> MLXArray {
+    try_split_on(key, num, stream).unwrap()
+}
+
+pub fn try_split(key: &MLXArray, num: usize) -> Result<MLXArray, MLXError> {
+    try_split_on(key, num, &get_default_stream())
+}
+
+pub fn try_split_on(key: &MLXArray, num: usize, stream: &MLXStream) -> Result<MLXArray, MLXError> {
+    let handle = guard(|| unsafe {
         mlx_random_split_equal_parts(key.as_ptr(), num as ::std::os::raw::c_int, stream.as_ptr())
-    };
-    MLXArray::from_raw(handle)
+    })?;
+    Ok(MLXArray::from_raw(handle))
 }
 
 // pub fn split_two(key: MLXArray, stream: MLXStream) -> (MLXArray, MLXArray) {
@@ -25,12 +42,37 @@ pub fn split(key: MLXArray, num: usize, stream: MLXStream) -> MLXArray {
 pub fn uniform<T: MlxType>(
     range: RangeInclusive<f32>,
     shape: &[i32],
-    key: MLXArray,
-    stream: MLXStream,
+    key: &MLXArray,
 ) -> MLXArray {
+    try_uniform::<T>(range, shape, key).unwrap()
+}
+
+pub fn uniform_on<T: MlxType>(
+    range: RangeInclusive<f32>,
+    shape: &[i32],
+    key: &MLXArray,
+    stream: &MLXStream,
+) -> MLXArray {
+    try_uniform_on::<T>(range, shape, key, stream).unwrap()
+}
+
+pub fn try_uniform<T: MlxType>(
+    range: RangeInclusive<f32>,
+    shape: &[i32],
+    key: &MLXArray,
+) -> Result<MLXArray, MLXError> {
+    try_uniform_on::<T>(range, shape, key, &get_default_stream())
+}
+
+pub fn try_uniform_on<T: MlxType>(
+    range: RangeInclusive<f32>,
+    shape: &[i32],
+    key: &MLXArray,
+    stream: &MLXStream,
+) -> Result<MLXArray, MLXError> {
     let lb: MLXArray = range.start().clone().into();
     let ub: MLXArray = range.end().clone().into();
-    let handle = unsafe {
+    let handle = guard(|| unsafe {
         mlx_random_uniform(
             lb.as_ptr(),
             ub.as_ptr(),
@@ -40,18 +82,46 @@ pub fn uniform<T: MlxType>(
             key.as_ptr(),
             stream.as_ptr(),
         )
-    };
-    MLXArray::from_raw(handle)
+    })?;
+    Ok(MLXArray::from_raw(handle))
 }
 
 pub fn normal<T: MlxType>(
     shape: &[i32],
     mean: f32,
     std: f32,
-    key: MLXArray,
-    stream: MLXStream,
+    key: &MLXArray,
 ) -> MLXArray {
-    let handle = unsafe {
+    try_normal::<T>(shape, mean, std, key).unwrap()
+}
+
+pub fn normal_on<T: MlxType>(
+    shape: &[i32],
+    mean: f32,
+    std: f32,
+    key: &MLXArray,
+    stream: &MLXStream,
+) -> MLXArray {
+    try_normal_on::<T>(shape, mean, std, key, stream).unwrap()
+}
+
+pub fn try_normal<T: MlxType>(
+    shape: &[i32],
+    mean: f32,
+    std: f32,
+    key: &MLXArray,
+) -> Result<MLXArray, MLXError> {
+    try_normal_on::<T>(shape, mean, std, key, &get_default_stream())
+}
+
+pub fn try_normal_on<T: MlxType>(
+    shape: &[i32],
+    mean: f32,
+    std: f32,
+    key: &MLXArray,
+    stream: &MLXStream,
+) -> Result<MLXArray, MLXError> {
+    let handle = guard(|| unsafe {
         mlx_random_normal(
             shape.as_ptr() as *const ::std::os::raw::c_int,
             shape.len(),
@@ -61,19 +131,44 @@ pub fn normal<T: MlxType>(
             key.as_ptr(),
             stream.as_ptr(),
         )
-    };
-    MLXArray::from_raw(handle)
+    })?;
+    Ok(MLXArray::from_raw(handle))
 }
 
 pub fn randint<T: MlxType>(
     range: RangeInclusive<i32>,
     shape: &[i32],
-    key: MLXArray,
-    stream: MLXStream,
+    key: &MLXArray,
 ) -> MLXArray {
+    try_randint::<T>(range, shape, key).unwrap()
+}
+
+pub fn randint_on<T: MlxType>(
+    range: RangeInclusive<i32>,
+    shape: &[i32],
+    key: &MLXArray,
+    stream: &MLXStream,
+) -> MLXArray {
+    try_randint_on::<T>(range, shape, key, stream).unwrap()
+}
+
+pub fn try_randint<T: MlxType>(
+    range: RangeInclusive<i32>,
+    shape: &[i32],
+    key: &MLXArray,
+) -> Result<MLXArray, MLXError> {
+    try_randint_on::<T>(range, shape, key, &get_default_stream())
+}
+
+pub fn try_randint_on<T: MlxType>(
+    range: RangeInclusive<i32>,
+    shape: &[i32],
+    key: &MLXArray,
+    stream: &MLXStream,
+) -> Result<MLXArray, MLXError> {
     let lb: MLXArray = range.start().clone().into();
     let ub: MLXArray = range.end().clone().into();
-    let handle = unsafe {
+    let handle = guard(|| unsafe {
         mlx_random_randint(
             lb.as_ptr(),
             ub.as_ptr(),
@@ -83,21 +178,51 @@ pub fn randint<T: MlxType>(
             key.as_ptr(),
             stream.as_ptr(),
         )
-    };
-    MLXArray::from_raw(handle)
+    })?;
+    Ok(MLXArray::from_raw(handle))
 }
 
 pub fn categorical(
-    logits: MLXArray,
+    logits: &MLXArray,
     axis: i32,
     shape:  Option<&[i32]>,
-    key0: Option<MLXArray>,
-    stream: Option<MLXStream>,
+    key0: Option<&MLXArray>,
 ) -> MLXArray {
-    let key = key0.unwrap_or_else(|| key(0));
-    let stream = stream.unwrap_or_else(||get_default_stream());
+    try_categorical(logits, axis, shape, key0).unwrap()
+}
+
+pub fn categorical_on(
+    logits: &MLXArray,
+    axis: i32,
+    shape:  Option<&[i32]>,
+    key0: Option<&MLXArray>,
+    stream: &MLXStream,
+) -> MLXArray {
+    try_categorical_on(logits, axis, shape, key0, stream).unwrap()
+}
+
+pub fn try_categorical(
+    logits: &MLXArray,
+    axis: i32,
+    shape:  Option<&[i32]>,
+    key0: Option<&MLXArray>,
+) -> Result<MLXArray, MLXError> {
+    try_categorical_on(logits, axis, shape, key0, &get_default_stream())
+}
+
+pub fn try_categorical_on(
+    logits: &MLXArray,
+    axis: i32,
+    shape:  Option<&[i32]>,
+    key0: Option<&MLXArray>,
+    stream: &MLXStream,
+) -> Result<MLXArray, MLXError> {
+    let key = match key0 {
+        Some(key) => key.clone(),
+        None => try_key(0)?,
+    };
     let handle = if let Some(shape) =  shape {
-        unsafe {
+        guard(|| unsafe {
             mlx_random_categorical_shape(
                 logits.as_ptr(),
                 axis,
@@ -106,9 +231,9 @@ pub fn categorical(
                 key.as_ptr(),
                 stream.as_ptr()
             )
-        }
+        })?
     } else {
-        unsafe { mlx_random_categorical(logits.as_ptr(), axis, key.as_ptr(), stream.as_ptr()) }
+        guard(|| unsafe { mlx_random_categorical(logits.as_ptr(), axis, key.as_ptr(), stream.as_ptr()) })?
     };
-    MLXArray::from_raw(handle)
+    Ok(MLXArray::from_raw(handle))
 }
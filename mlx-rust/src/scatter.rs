@@ -0,0 +1,261 @@
+use mlx_sys::{
+    mlx_array, mlx_put_along_axis, mlx_scatter, mlx_scatter_add, mlx_scatter_max, mlx_scatter_min,
+    mlx_scatter_prod, mlx_stream, mlx_take_along_axis, mlx_vector_array,
+};
+
+use crate::array::MLXArray;
+use crate::error::{guard, MLXError};
+use crate::stream::{get_default_stream, MLXStream};
+use crate::VectorMLXArray;
+
+/// Gathers along `axis` with `indices` of the same rank as `x`, e.g. the indices returned by
+/// [`arg_sort`](crate::array_op::arg_sort).
+pub fn take_along_axis(x: &MLXArray, indices: &MLXArray, axis: i32) -> MLXArray {
+    try_take_along_axis(x, indices, axis).unwrap()
+}
+
+pub fn take_along_axis_on(x: &MLXArray, indices: &MLXArray, axis: i32, stream: &MLXStream) -> MLXArray {
+    try_take_along_axis_on(x, indices, axis, stream).unwrap()
+}
+
+pub fn try_take_along_axis(x: &MLXArray, indices: &MLXArray, axis: i32) -> Result<MLXArray, MLXError> {
+    try_take_along_axis_on(x, indices, axis, &get_default_stream())
+}
+
+pub fn try_take_along_axis_on(x: &MLXArray, indices: &MLXArray, axis: i32, stream: &MLXStream) -> Result<MLXArray, MLXError> {
+    let handle = guard(|| unsafe {
+        mlx_take_along_axis(x.as_ptr(), indices.as_ptr(), axis, stream.as_ptr())
+    })?;
+    Ok(MLXArray::from_raw(handle))
+}
+
+/// The inverse of [`take_along_axis`], returns a copy of `x` with `values` written at `indices`.
+pub fn put_along_axis(x: &MLXArray, indices: &MLXArray, values: &MLXArray, axis: i32) -> MLXArray {
+    try_put_along_axis(x, indices, values, axis).unwrap()
+}
+
+pub fn put_along_axis_on(x: &MLXArray, indices: &MLXArray, values: &MLXArray, axis: i32, stream: &MLXStream) -> MLXArray {
+    try_put_along_axis_on(x, indices, values, axis, stream).unwrap()
+}
+
+pub fn try_put_along_axis(x: &MLXArray, indices: &MLXArray, values: &MLXArray, axis: i32) -> Result<MLXArray, MLXError> {
+    try_put_along_axis_on(x, indices, values, axis, &get_default_stream())
+}
+
+pub fn try_put_along_axis_on(x: &MLXArray, indices: &MLXArray, values: &MLXArray, axis: i32, stream: &MLXStream) -> Result<MLXArray, MLXError> {
+    let handle = guard(|| unsafe {
+        mlx_put_along_axis(x.as_ptr(), indices.as_ptr(), values.as_ptr(), axis, stream.as_ptr())
+    })?;
+    Ok(MLXArray::from_raw(handle))
+}
+
+type ScatterFn = unsafe extern "C" fn(mlx_array, mlx_vector_array, mlx_array, *const i32, usize, mlx_stream) -> mlx_array;
+
+fn scatter_with(
+    f: ScatterFn,
+    x: &MLXArray,
+    indices: &[MLXArray],
+    updates: &MLXArray,
+    axes: &[i32],
+    stream: &MLXStream,
+) -> Result<MLXArray, MLXError> {
+    if indices.len() != axes.len() {
+        return Err(MLXError::InvalidArgument(format!(
+            "got {} index arrays for {} axes",
+            indices.len(),
+            axes.len()
+        )));
+    }
+    let indices = VectorMLXArray::from(indices);
+    let handle = guard(|| unsafe {
+        f(
+            x.as_ptr(),
+            indices.as_ptr(),
+            updates.as_ptr(),
+            axes.as_ptr(),
+            axes.len(),
+            stream.as_ptr(),
+        )
+    })?;
+    Ok(MLXArray::from_raw(handle))
+}
+
+macro_rules! impl_scatter_op {
+    ($name:ident, $mlx_func:ident, $doc:literal) => {
+        paste::paste! {
+            #[doc = $doc]
+            ///
+            /// `indices[i]` indexes `axes[i]`, all index arrays are broadcast together. `updates`
+            /// has the broadcast index shape followed by the shape of each updated slice.
+            pub fn $name(x: &MLXArray, indices: &[MLXArray], updates: &MLXArray, axes: &[i32]) -> MLXArray {
+                [<try_ $name>](x, indices, updates, axes).unwrap()
+            }
+
+            pub fn [<$name _on>](x: &MLXArray, indices: &[MLXArray], updates: &MLXArray, axes: &[i32], stream: &MLXStream) -> MLXArray {
+                [<try_ $name _on>](x, indices, updates, axes, stream).unwrap()
+            }
+
+            pub fn [<try_ $name>](x: &MLXArray, indices: &[MLXArray], updates: &MLXArray, axes: &[i32]) -> Result<MLXArray, MLXError> {
+                [<try_ $name _on>](x, indices, updates, axes, &get_default_stream())
+            }
+
+            pub fn [<try_ $name _on>](x: &MLXArray, indices: &[MLXArray], updates: &MLXArray, axes: &[i32], stream: &MLXStream) -> Result<MLXArray, MLXError> {
+                scatter_with($mlx_func, x, indices, updates, axes, stream)
+            }
+        }
+    };
+}
+
+impl_scatter_op!(scatter, mlx_scatter, "Writes `updates` at `indices`, with repeated indices one of the updates wins.");
+impl_scatter_op!(scatter_add, mlx_scatter_add, "Adds `updates` at `indices`, repeated indices accumulate.");
+impl_scatter_op!(scatter_prod, mlx_scatter_prod, "Multiplies by `updates` at `indices`, repeated indices accumulate.");
+impl_scatter_op!(scatter_max, mlx_scatter_max, "Keeps the maximum of the array and `updates` at `indices`.");
+impl_scatter_op!(scatter_min, mlx_scatter_min, "Keeps the minimum of the array and `updates` at `indices`.");
+
+/// Functional updates at positions along the first axis, created by [`MLXArray::at`].
+///
+/// Unlike indexing followed by an update, repeated indices accumulate:
+///
+/// ```
+/// use mlx_rust::MLXArray;
+/// let x = MLXArray::array(&[0.0f32; 3], &[3]);
+/// let idx = MLXArray::array(&[0i32, 0, 2], &[3]);
+/// let y = x.at(&idx).add(&1.0.into());
+/// assert_eq!(&[2.0, 0.0, 1.0], y.to_slice::<f32>().unwrap());
+/// ```
+pub struct ArrayAt<'a> {
+    array: &'a MLXArray,
+    indices: &'a MLXArray,
+    stream: MLXStream,
+}
+
+impl MLXArray {
+    pub fn at<'a>(&'a self, indices: &'a MLXArray) -> ArrayAt<'a> {
+        self.at_on(indices, &get_default_stream())
+    }
+
+    /// Like [`at`](MLXArray::at), the updates run on `stream`.
+    pub fn at_on<'a>(&'a self, indices: &'a MLXArray, stream: &MLXStream) -> ArrayAt<'a> {
+        ArrayAt { array: self, indices, stream: stream.clone() }
+    }
+}
+
+impl ArrayAt<'_> {
+    /// Broadcasts `values` to the shape of the indexed slices and adds the size one scatter axis.
+    fn updates(&self, values: &MLXArray) -> Result<MLXArray, MLXError> {
+        let mut shape = self.indices.shape().to_vec();
+        shape.extend_from_slice(&self.array.shape()[1..]);
+        let values = values.try_broadcast_to_on(&shape, &self.stream)?;
+        let mut update_shape = self.indices.shape().to_vec();
+        update_shape.push(1);
+        update_shape.extend_from_slice(&self.array.shape()[1..]);
+        values.try_reshape_on(&update_shape, &self.stream)
+    }
+
+    fn apply(&self, f: ScatterFn, values: &MLXArray) -> Result<MLXArray, MLXError> {
+        if self.array.ndim() == 0 {
+            return Err(MLXError::InvalidArgument("cannot index a scalar array".into()));
+        }
+        let updates = self.updates(values)?;
+        scatter_with(f, self.array, std::slice::from_ref(self.indices), &updates, &[0], &self.stream)
+    }
+
+    pub fn set(&self, values: &MLXArray) -> MLXArray {
+        self.try_set(values).unwrap()
+    }
+
+    pub fn try_set(&self, values: &MLXArray) -> Result<MLXArray, MLXError> {
+        self.apply(mlx_scatter, values)
+    }
+
+    pub fn add(&self, values: &MLXArray) -> MLXArray {
+        self.try_add(values).unwrap()
+    }
+
+    pub fn try_add(&self, values: &MLXArray) -> Result<MLXArray, MLXError> {
+        self.apply(mlx_scatter_add, values)
+    }
+
+    pub fn subtract(&self, values: &MLXArray) -> MLXArray {
+        self.try_subtract(values).unwrap()
+    }
+
+    pub fn try_subtract(&self, values: &MLXArray) -> Result<MLXArray, MLXError> {
+        self.apply(mlx_scatter_add, &values.try_negative_on(&self.stream)?)
+    }
+
+    pub fn multiply(&self, values: &MLXArray) -> MLXArray {
+        self.try_multiply(values).unwrap()
+    }
+
+    pub fn try_multiply(&self, values: &MLXArray) -> Result<MLXArray, MLXError> {
+        self.apply(mlx_scatter_prod, values)
+    }
+
+    pub fn maximum(&self, values: &MLXArray) -> MLXArray {
+        self.try_maximum(values).unwrap()
+    }
+
+    pub fn try_maximum(&self, values: &MLXArray) -> Result<MLXArray, MLXError> {
+        self.apply(mlx_scatter_max, values)
+    }
+
+    pub fn minimum(&self, values: &MLXArray) -> MLXArray {
+        self.try_minimum(values).unwrap()
+    }
+
+    pub fn try_minimum(&self, values: &MLXArray) -> Result<MLXArray, MLXError> {
+        self.apply(mlx_scatter_min, values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::array::MLXArray;
+    use crate::array_op::arg_sort;
+    use crate::scatter::{put_along_axis, scatter, scatter_add, take_along_axis, try_scatter};
+
+    #[test]
+    fn test_take_along_axis() {
+        let x = MLXArray::array(&[3i32, 1, 2, 9, 7, 8], &[2, 3]);
+        let idx = arg_sort(&x, 1);
+        let sorted = take_along_axis(&x, &idx, 1);
+        assert_eq!(&[1, 2, 3, 7, 8, 9], sorted.to_slice::<i32>().unwrap());
+    }
+
+    #[test]
+    fn test_put_along_axis() {
+        let x = MLXArray::array(&[0i32; 4], &[2, 2]);
+        let idx = MLXArray::array(&[1i32, 0], &[2, 1]);
+        let values = MLXArray::array(&[5i32, 6], &[2, 1]);
+        let y = put_along_axis(&x, &idx, &values, 1);
+        assert_eq!(&[0, 5, 6, 0], y.to_slice::<i32>().unwrap());
+    }
+
+    #[test]
+    fn test_scatter() {
+        let x = MLXArray::array(&[0.0f32; 4], &[4]);
+        let idx = MLXArray::array(&[1i32, 1, 3], &[3]);
+        let updates = MLXArray::array(&[1.0f32, 2.0, 3.0], &[3, 1]);
+        let y = scatter_add(&x, std::slice::from_ref(&idx), &updates, &[0]);
+        assert_eq!(&[0.0, 3.0, 0.0, 3.0], y.to_slice::<f32>().unwrap());
+        let y = scatter(&x, std::slice::from_ref(&idx), &updates, &[0]);
+        assert_eq!(3.0, y.to_slice::<f32>().unwrap()[3]);
+        assert!(try_scatter(&x, &[idx], &updates, &[0, 1]).is_err());
+    }
+
+    #[test]
+    fn test_at() {
+        let x = MLXArray::array(&[1.0f32, 1.0, 1.0, 1.0], &[2, 2]);
+        let idx = MLXArray::array(&[1i32, 1], &[2]);
+        let y = x.at(&idx).add(&1.0.into());
+        assert_eq!(&[1.0, 1.0, 3.0, 3.0], y.to_slice::<f32>().unwrap());
+        let y = x.at(&idx).multiply(&3.0.into());
+        assert_eq!(&[1.0, 1.0, 9.0, 9.0], y.to_slice::<f32>().unwrap());
+        let row = MLXArray::array(&[5.0f32, -5.0], &[2]);
+        let y = x.at(&idx).maximum(&row);
+        assert_eq!(&[1.0, 1.0, 5.0, 1.0], y.to_slice::<f32>().unwrap());
+        let y = x.at(&idx).subtract(&1.0.into());
+        assert_eq!(&[1.0, 1.0, -1.0, -1.0], y.to_slice::<f32>().unwrap());
+    }
+}
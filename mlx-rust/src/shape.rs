@@ -0,0 +1,395 @@
+use std::ffi::CString;
+
+use mlx_sys::{
+    mlx_as_strided, mlx_broadcast_arrays, mlx_broadcast_to, mlx_flatten, mlx_pad, mlx_repeat,
+    mlx_repeat_all, mlx_split, mlx_split_equal_parts, mlx_stack, mlx_tile, mlx_unflatten,
+};
+
+use crate::array::MLXArray;
+use crate::error::{guard, MLXError};
+use crate::stream::{get_default_stream, MLXStream};
+use crate::VectorMLXArray;
+
+/// How [`MLXArray::pad`] fills the new elements.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PadMode {
+    /// Fills with the given value.
+    Constant(f32),
+    /// Repeats the values at the edge of each axis.
+    Edge,
+}
+
+impl PadMode {
+    fn name(&self) -> &'static str {
+        match self {
+            PadMode::Constant(_) => "constant",
+            PadMode::Edge => "edge",
+        }
+    }
+}
+
+impl MLXArray {
+    /// Stacks arrays of the same shape along a new axis.
+    pub fn stack(arrays: &[MLXArray], axis: i32) -> MLXArray {
+        Self::try_stack(arrays, axis).unwrap()
+    }
+
+    pub fn stack_on(arrays: &[MLXArray], axis: i32, stream: &MLXStream) -> MLXArray {
+        Self::try_stack_on(arrays, axis, stream).unwrap()
+    }
+
+    pub fn try_stack(arrays: &[MLXArray], axis: i32) -> Result<MLXArray, MLXError> {
+        Self::try_stack_on(arrays, axis, &get_default_stream())
+    }
+
+    pub fn try_stack_on(arrays: &[MLXArray], axis: i32, stream: &MLXStream) -> Result<MLXArray, MLXError> {
+        let arrays = VectorMLXArray::from(arrays);
+        let handle = guard(|| unsafe { mlx_stack(arrays.as_ptr(), axis, stream.as_ptr()) })?;
+        Ok(MLXArray::from_raw(handle))
+    }
+
+    /// Splits into `parts` arrays of equal size along `axis`.
+    pub fn split(&self, parts: i32, axis: i32) -> Vec<MLXArray> {
+        self.try_split(parts, axis).unwrap()
+    }
+
+    pub fn split_on(&self, parts: i32, axis: i32, stream: &MLXStream) -> Vec<MLXArray> {
+        self.try_split_on(parts, axis, stream).unwrap()
+    }
+
+    pub fn try_split(&self, parts: i32, axis: i32) -> Result<Vec<MLXArray>, MLXError> {
+        self.try_split_on(parts, axis, &get_default_stream())
+    }
+
+    pub fn try_split_on(&self, parts: i32, axis: i32, stream: &MLXStream) -> Result<Vec<MLXArray>, MLXError> {
+        let handle = guard(|| unsafe {
+            mlx_split_equal_parts(self.as_ptr(), parts, axis, stream.as_ptr())
+        })?;
+        Ok(VectorMLXArray::from_raw(handle).to_vec())
+    }
+
+    /// Splits along `axis` before each of the given indices, so `n` indices give `n + 1` arrays.
+    pub fn split_at(&self, indices: &[i32], axis: i32) -> Vec<MLXArray> {
+        self.try_split_at(indices, axis).unwrap()
+    }
+
+    pub fn split_at_on(&self, indices: &[i32], axis: i32, stream: &MLXStream) -> Vec<MLXArray> {
+        self.try_split_at_on(indices, axis, stream).unwrap()
+    }
+
+    pub fn try_split_at(&self, indices: &[i32], axis: i32) -> Result<Vec<MLXArray>, MLXError> {
+        self.try_split_at_on(indices, axis, &get_default_stream())
+    }
+
+    pub fn try_split_at_on(&self, indices: &[i32], axis: i32, stream: &MLXStream) -> Result<Vec<MLXArray>, MLXError> {
+        let handle = guard(|| unsafe {
+            mlx_split(
+                self.as_ptr(),
+                indices.as_ptr(),
+                indices.len(),
+                axis,
+                stream.as_ptr(),
+            )
+        })?;
+        Ok(VectorMLXArray::from_raw(handle).to_vec())
+    }
+
+    /// Repeats the whole array `reps[i]` times along axis `i`.
+    pub fn tile(&self, reps: &[i32]) -> MLXArray {
+        self.try_tile(reps).unwrap()
+    }
+
+    pub fn tile_on(&self, reps: &[i32], stream: &MLXStream) -> MLXArray {
+        self.try_tile_on(reps, stream).unwrap()
+    }
+
+    pub fn try_tile(&self, reps: &[i32]) -> Result<MLXArray, MLXError> {
+        self.try_tile_on(reps, &get_default_stream())
+    }
+
+    pub fn try_tile_on(&self, reps: &[i32], stream: &MLXStream) -> Result<MLXArray, MLXError> {
+        let handle = guard(|| unsafe {
+            mlx_tile(self.as_ptr(), reps.as_ptr(), reps.len(), stream.as_ptr())
+        })?;
+        Ok(MLXArray::from_raw(handle))
+    }
+
+    /// Repeats each element `repeats` times along `axis`, or along the flattened array if `axis`
+    /// is `None`.
+    pub fn repeat(&self, repeats: i32, axis: Option<i32>) -> MLXArray {
+        self.try_repeat(repeats, axis).unwrap()
+    }
+
+    pub fn repeat_on(&self, repeats: i32, axis: Option<i32>, stream: &MLXStream) -> MLXArray {
+        self.try_repeat_on(repeats, axis, stream).unwrap()
+    }
+
+    pub fn try_repeat(&self, repeats: i32, axis: Option<i32>) -> Result<MLXArray, MLXError> {
+        self.try_repeat_on(repeats, axis, &get_default_stream())
+    }
+
+    pub fn try_repeat_on(&self, repeats: i32, axis: Option<i32>, stream: &MLXStream) -> Result<MLXArray, MLXError> {
+        let handle = match axis {
+            Some(axis) => guard(|| unsafe { mlx_repeat(self.as_ptr(), repeats, axis, stream.as_ptr()) })?,
+            None => guard(|| unsafe { mlx_repeat_all(self.as_ptr(), repeats, stream.as_ptr()) })?,
+        };
+        Ok(MLXArray::from_raw(handle))
+    }
+
+    /// Pads the leading axes with `(before, after)` elements each. A single pair is used for
+    /// every axis.
+    pub fn pad(&self, widths: &[(i32, i32)], mode: PadMode) -> MLXArray {
+        self.try_pad(widths, mode).unwrap()
+    }
+
+    pub fn pad_on(&self, widths: &[(i32, i32)], mode: PadMode, stream: &MLXStream) -> MLXArray {
+        self.try_pad_on(widths, mode, stream).unwrap()
+    }
+
+    pub fn try_pad(&self, widths: &[(i32, i32)], mode: PadMode) -> Result<MLXArray, MLXError> {
+        self.try_pad_on(widths, mode, &get_default_stream())
+    }
+
+    pub fn try_pad_on(&self, widths: &[(i32, i32)], mode: PadMode, stream: &MLXStream) -> Result<MLXArray, MLXError> {
+        let ndim = self.ndim();
+        if widths.len() > ndim {
+            return Err(MLXError::InvalidArgument(format!(
+                "got {} pad widths for an array with {} dimensions",
+                widths.len(),
+                ndim
+            )));
+        }
+        let widths = if widths.len() == 1 { vec![widths[0]; ndim] } else { widths.to_vec() };
+        let axes: Vec<i32> = (0..widths.len() as i32).collect();
+        let low: Vec<i32> = widths.iter().map(|w| w.0).collect();
+        let high: Vec<i32> = widths.iter().map(|w| w.1).collect();
+        let value: MLXArray = match mode {
+            PadMode::Constant(v) => v.into(),
+            PadMode::Edge => 0.0.into(),
+        };
+        let mode_name = CString::new(mode.name()).unwrap();
+        let handle = guard(|| unsafe {
+            mlx_pad(
+                self.as_ptr(),
+                axes.as_ptr(),
+                axes.len(),
+                low.as_ptr(),
+                low.len(),
+                high.as_ptr(),
+                high.len(),
+                value.as_ptr(),
+                mode_name.as_ptr(),
+                stream.as_ptr(),
+            )
+        })?;
+        Ok(MLXArray::from_raw(handle))
+    }
+
+    pub fn broadcast_to(&self, shape: &[i32]) -> MLXArray {
+        self.try_broadcast_to(shape).unwrap()
+    }
+
+    pub fn broadcast_to_on(&self, shape: &[i32], stream: &MLXStream) -> MLXArray {
+        self.try_broadcast_to_on(shape, stream).unwrap()
+    }
+
+    pub fn try_broadcast_to(&self, shape: &[i32]) -> Result<MLXArray, MLXError> {
+        self.try_broadcast_to_on(shape, &get_default_stream())
+    }
+
+    pub fn try_broadcast_to_on(&self, shape: &[i32], stream: &MLXStream) -> Result<MLXArray, MLXError> {
+        let handle = guard(|| unsafe {
+            mlx_broadcast_to(self.as_ptr(), shape.as_ptr(), shape.len(), stream.as_ptr())
+        })?;
+        Ok(MLXArray::from_raw(handle))
+    }
+
+    /// Broadcasts the arrays against each other.
+    pub fn broadcast_arrays(arrays: &[MLXArray]) -> Vec<MLXArray> {
+        Self::try_broadcast_arrays(arrays).unwrap()
+    }
+
+    pub fn broadcast_arrays_on(arrays: &[MLXArray], stream: &MLXStream) -> Vec<MLXArray> {
+        Self::try_broadcast_arrays_on(arrays, stream).unwrap()
+    }
+
+    pub fn try_broadcast_arrays(arrays: &[MLXArray]) -> Result<Vec<MLXArray>, MLXError> {
+        Self::try_broadcast_arrays_on(arrays, &get_default_stream())
+    }
+
+    pub fn try_broadcast_arrays_on(arrays: &[MLXArray], stream: &MLXStream) -> Result<Vec<MLXArray>, MLXError> {
+        let arrays = VectorMLXArray::from(arrays);
+        let handle = guard(|| unsafe { mlx_broadcast_arrays(arrays.as_ptr(), stream.as_ptr()) })?;
+        Ok(VectorMLXArray::from_raw(handle).to_vec())
+    }
+
+    /// Merges the axes `start_axis..=end_axis` into one, negative axes count from the end.
+    pub fn flatten(&self, start_axis: i32, end_axis: i32) -> MLXArray {
+        self.try_flatten(start_axis, end_axis).unwrap()
+    }
+
+    pub fn flatten_on(&self, start_axis: i32, end_axis: i32, stream: &MLXStream) -> MLXArray {
+        self.try_flatten_on(start_axis, end_axis, stream).unwrap()
+    }
+
+    pub fn try_flatten(&self, start_axis: i32, end_axis: i32) -> Result<MLXArray, MLXError> {
+        self.try_flatten_on(start_axis, end_axis, &get_default_stream())
+    }
+
+    pub fn try_flatten_on(&self, start_axis: i32, end_axis: i32, stream: &MLXStream) -> Result<MLXArray, MLXError> {
+        let handle = guard(|| unsafe {
+            mlx_flatten(self.as_ptr(), start_axis, end_axis, stream.as_ptr())
+        })?;
+        Ok(MLXArray::from_raw(handle))
+    }
+
+    /// Splits `axis` into the given shape, the inverse of [`MLXArray::flatten`].
+    pub fn unflatten(&self, axis: i32, shape: &[i32]) -> MLXArray {
+        self.try_unflatten(axis, shape).unwrap()
+    }
+
+    pub fn unflatten_on(&self, axis: i32, shape: &[i32], stream: &MLXStream) -> MLXArray {
+        self.try_unflatten_on(axis, shape, stream).unwrap()
+    }
+
+    pub fn try_unflatten(&self, axis: i32, shape: &[i32]) -> Result<MLXArray, MLXError> {
+        self.try_unflatten_on(axis, shape, &get_default_stream())
+    }
+
+    pub fn try_unflatten_on(&self, axis: i32, shape: &[i32], stream: &MLXStream) -> Result<MLXArray, MLXError> {
+        let handle = guard(|| unsafe {
+            mlx_unflatten(self.as_ptr(), axis, shape.as_ptr(), shape.len(), stream.as_ptr())
+        })?;
+        Ok(MLXArray::from_raw(handle))
+    }
+
+    /// Prepends axes of size one until the array has at least `n` dimensions.
+    pub fn atleast_nd(&self, n: usize) -> MLXArray {
+        self.try_atleast_nd(n).unwrap()
+    }
+
+    pub fn try_atleast_nd(&self, n: usize) -> Result<MLXArray, MLXError> {
+        let ndim = self.ndim();
+        if ndim >= n {
+            return Ok(self.clone());
+        }
+        let mut shape = vec![1; n - ndim];
+        shape.extend_from_slice(self.shape());
+        self.try_reshape(&shape)
+    }
+
+    /// A view of the underlying buffer with the given shape and strides, both in elements.
+    ///
+    /// Nothing is checked, reading outside of the buffer is undefined.
+    pub fn as_strided(&self, shape: &[i32], strides: &[usize], offset: usize) -> MLXArray {
+        self.try_as_strided(shape, strides, offset).unwrap()
+    }
+
+    pub fn as_strided_on(&self, shape: &[i32], strides: &[usize], offset: usize, stream: &MLXStream) -> MLXArray {
+        self.try_as_strided_on(shape, strides, offset, stream).unwrap()
+    }
+
+    pub fn try_as_strided(&self, shape: &[i32], strides: &[usize], offset: usize) -> Result<MLXArray, MLXError> {
+        self.try_as_strided_on(shape, strides, offset, &get_default_stream())
+    }
+
+    pub fn try_as_strided_on(&self, shape: &[i32], strides: &[usize], offset: usize, stream: &MLXStream) -> Result<MLXArray, MLXError> {
+        if shape.len() != strides.len() {
+            return Err(MLXError::InvalidArgument(format!(
+                "shape has {} dimensions but {} strides were given",
+                shape.len(),
+                strides.len()
+            )));
+        }
+        let handle = guard(|| unsafe {
+            mlx_as_strided(
+                self.as_ptr(),
+                shape.as_ptr(),
+                shape.len(),
+                strides.as_ptr(),
+                strides.len(),
+                offset,
+                stream.as_ptr(),
+            )
+        })?;
+        Ok(MLXArray::from_raw(handle))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::array::MLXArray;
+    use crate::shape::PadMode;
+
+    #[test]
+    fn test_stack_and_split() {
+        let a = MLXArray::array(&[1.0f32, 2.0], &[2]);
+        let b = MLXArray::array(&[3.0f32, 4.0], &[2]);
+        let stacked = MLXArray::stack(&[a, b], 0);
+        assert_eq!(&[2, 2], stacked.shape());
+
+        let parts = stacked.split(2, 1);
+        assert_eq!(2, parts.len());
+        assert_eq!(&[2.0, 4.0], parts[1].reshape(&[2]).to_slice::<f32>().unwrap());
+
+        let x = MLXArray::array(&[0i32, 1, 2, 3, 4], &[5]);
+        let parts = x.split_at(&[1, 3], 0);
+        assert_eq!(3, parts.len());
+        assert_eq!(&[1, 2], parts[1].to_slice::<i32>().unwrap());
+        assert_eq!(&[3, 4], parts[2].to_slice::<i32>().unwrap());
+    }
+
+    #[test]
+    fn test_tile_and_repeat() {
+        let x = MLXArray::array(&[1i32, 2], &[2]);
+        assert_eq!(&[1, 2, 1, 2], x.tile(&[2]).to_slice::<i32>().unwrap());
+        assert_eq!(&[1, 1, 2, 2], x.repeat(2, Some(0)).to_slice::<i32>().unwrap());
+        let y = MLXArray::array(&[1i32, 2, 3, 4], &[2, 2]);
+        assert_eq!(&[8], y.repeat(2, None).shape());
+    }
+
+    #[test]
+    fn test_pad() {
+        let x = MLXArray::array(&[1.0f32, 2.0], &[2]);
+        let p = x.pad(&[(1, 2)], PadMode::Constant(0.0));
+        assert_eq!(&[0.0, 1.0, 2.0, 0.0, 0.0], p.to_slice::<f32>().unwrap());
+        let p = x.pad(&[(1, 1)], PadMode::Edge);
+        assert_eq!(&[1.0, 1.0, 2.0, 2.0], p.to_slice::<f32>().unwrap());
+        assert!(x.try_pad(&[(1, 1), (1, 1)], PadMode::Edge).is_err());
+    }
+
+    #[test]
+    fn test_broadcast() {
+        let x = MLXArray::array(&[1.0f32, 2.0], &[2]);
+        assert_eq!(&[3, 2], x.broadcast_to(&[3, 2]).shape());
+        let y = MLXArray::array(&[1.0f32, 2.0, 3.0], &[3, 1]);
+        let r = MLXArray::broadcast_arrays(&[x, y]);
+        assert_eq!(&[3, 2], r[0].shape());
+        assert_eq!(&[3, 2], r[1].shape());
+    }
+
+    #[test]
+    fn test_flatten_and_unflatten() {
+        let x = MLXArray::array(&[0.0f32; 24], &[2, 3, 4]);
+        let flat = x.flatten(1, 2);
+        assert_eq!(&[2, 12], flat.shape());
+        assert_eq!(&[2, 3, 4], flat.unflatten(1, &[3, 4]).shape());
+        assert_eq!(&[24], x.flatten(0, -1).shape());
+    }
+
+    #[test]
+    fn test_atleast_nd() {
+        let x: MLXArray = 1.0.into();
+        assert_eq!(&[1, 1, 1], x.atleast_nd(3).shape());
+        let y = MLXArray::array(&[1.0f32, 2.0], &[2]);
+        assert_eq!(&[1, 2], y.atleast_nd(2).shape());
+        assert_eq!(&[2], y.atleast_nd(1).shape());
+    }
+
+    #[test]
+    fn test_as_strided() {
+        let x = MLXArray::array(&[0i32, 1, 2, 3, 4], &[5]);
+        let windows = x.as_strided(&[3, 3], &[1, 1], 0);
+        assert_eq!(&[0, 1, 2, 1, 2, 3, 2, 3, 4], windows.to_slice::<i32>().unwrap());
+    }
+}
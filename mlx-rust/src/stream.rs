@@ -1,23 +1,65 @@
-use std::{fmt::Display, sync::Mutex};
+use std::{cell::RefCell, fmt::Display, sync::Mutex};
 
 use mlx_sys::{mlx_default_stream, mlx_stream, mlx_stream_, mlx_stream_new};
 
 use crate::{
-    device::{get_default_device, MLXDevice},
+    device::{global_default_device, MLXDevice},
+    error::init_error_handler,
     object::MLXObject,
 };
 
 lazy_static::lazy_static! {
-    static ref DEFAULT_STREAM: Mutex<MLXStream> = Mutex::new(MLXStream::from_raw(unsafe {
-        mlx_default_stream(get_default_device().as_ptr())
-    }));
+    static ref DEFAULT_STREAM: Mutex<MLXStream> = Mutex::new({
+        // make sure mlx reports errors to us instead of aborting before any op runs
+        init_error_handler();
+        MLXStream::from_raw(unsafe { mlx_default_stream(global_default_device().as_ptr()) })
+    });
 }
 
+thread_local! {
+    static SCOPED_STREAMS: RefCell<Vec<MLXStream>> = const { RefCell::new(Vec::new()) };
+}
+
+/// The stream ops run on when none is given: the innermost [`with_stream`] (or
+/// [`with_device`](crate::device::with_device)) scope of this thread, the process wide default
+/// otherwise.
 pub fn get_default_stream() -> MLXStream {
+    if let Some(stream) = SCOPED_STREAMS.with(|s| s.borrow().last().cloned()) {
+        return stream;
+    }
     let g = DEFAULT_STREAM.lock().unwrap();
     g.clone()
 }
 
+/// Replaces the process wide default stream, scopes opened with [`with_stream`] still win.
+pub fn set_default_stream(stream: MLXStream) {
+    let mut g = DEFAULT_STREAM.lock().unwrap();
+    *g = stream;
+}
+
+struct PopStream;
+
+impl Drop for PopStream {
+    fn drop(&mut self) {
+        SCOPED_STREAMS.with(|s| s.borrow_mut().pop());
+    }
+}
+
+/// Runs `f` with `stream` as the default stream of the current thread, so every op called
+/// without an explicit stream inside `f` runs on it. Scopes nest and are left even if `f` panics.
+///
+/// ```
+/// use mlx_rust::device::MLXDevice;
+/// use mlx_rust::stream::{get_default_stream, with_stream, MLXStream};
+/// let cpu = MLXStream::default_stream(MLXDevice::cpu());
+/// with_stream(&cpu, || assert_eq!(cpu, get_default_stream()));
+/// ```
+pub fn with_stream<R>(stream: &MLXStream, f: impl FnOnce() -> R) -> R {
+    SCOPED_STREAMS.with(|s| s.borrow_mut().push(stream.clone()));
+    let _pop = PopStream;
+    f()
+}
+
 #[derive(Clone, PartialEq, Debug)]
 pub struct MLXStream {
     innner: MLXObject<mlx_stream_>,
@@ -53,3 +95,48 @@ impl MLXStream {
         self.innner.as_ptr()
     }
 }
+
+/// Held by tests that read or replace the process wide default stream, which
+/// [`set_default_device`](crate::device::set_default_device) replaces too.
+#[cfg(test)]
+pub(crate) static DEFAULT_STREAM_TESTS: Mutex<()> = Mutex::new(());
+
+#[cfg(test)]
+mod tests {
+    use std::panic;
+
+    use crate::array::MLXArray;
+    use crate::device::MLXDevice;
+    use crate::stream::{get_default_stream, with_stream, MLXStream, DEFAULT_STREAM_TESTS};
+
+    #[test]
+    fn test_with_stream() {
+        let _lock = DEFAULT_STREAM_TESTS.lock().unwrap_or_else(|e| e.into_inner());
+        let before = get_default_stream();
+        let cpu = MLXStream::default_stream(MLXDevice::cpu());
+        let other = MLXStream::new(MLXDevice::cpu(), 1);
+        let sum = with_stream(&cpu, || {
+            assert_eq!(cpu, get_default_stream());
+            with_stream(&other, || assert_eq!(other, get_default_stream()));
+            assert_eq!(cpu, get_default_stream());
+            let x = MLXArray::array(&[1.0f32, 2.0], &[2]);
+            (&x + &x).to_slice::<f32>().unwrap().to_vec()
+        });
+        assert_eq!(vec![2.0, 4.0], sum);
+        assert_eq!(before, get_default_stream());
+    }
+
+    #[test]
+    fn test_with_stream_is_thread_local_and_panic_safe() {
+        let _lock = DEFAULT_STREAM_TESTS.lock().unwrap_or_else(|e| e.into_inner());
+        let before = get_default_stream();
+        let cpu = MLXStream::default_stream(MLXDevice::cpu());
+        with_stream(&cpu, || {
+            let seen = std::thread::spawn(get_default_stream).join().unwrap();
+            assert_eq!(before, seen);
+        });
+        let r = panic::catch_unwind(|| with_stream(&cpu, || panic!("boom")));
+        assert!(r.is_err());
+        assert_eq!(before, get_default_stream());
+    }
+}
@@ -1,8 +1,13 @@
+use std::ffi::CString;
 use std::ops::Range;
+use std::slice;
 
-use mlx_sys::{mlx_arange, mlx_array_from_bool, mlx_array_from_data, mlx_array_from_float, mlx_array_from_int, mlx_concatenate, mlx_full, mlx_ones, mlx_zeros, mlx_zeros_like};
+use half::{bf16, f16};
+use num_complex::Complex32;
+use mlx_sys::{mlx_arange, mlx_array_from_bool, mlx_array_from_data, mlx_array_from_float, mlx_array_from_int, mlx_concatenate, mlx_eye, mlx_full, mlx_identity, mlx_linspace, mlx_meshgrid, mlx_ones, mlx_ones_like, mlx_tri, mlx_tril, mlx_triu, mlx_zeros, mlx_zeros_like};
 
-use crate::{MLXArray, r#type::MlxType, stream::MLXStream, VectorMLXArray};
+use crate::{MLXArray, r#type::{Dtype, MlxType}, stream::MLXStream, VectorMLXArray};
+use crate::error::{guard, MLXError};
 use crate::stream::get_default_stream;
 
 impl MLXArray {
@@ -19,12 +24,48 @@ impl MLXArray {
         Self::from_raw(handle)
     }
 
+    /// Copies little-endian `data` holding elements of `dtype` in row-major order.
+    pub(crate) fn from_bytes(data: &[u8], shape: &[i32], dtype: Dtype) -> Result<Self, MLXError> {
+        let size: usize = shape.iter().map(|d| *d as usize).product();
+        if data.len() != size * dtype.size_of() {
+            return Err(MLXError::InvalidArgument(format!(
+                "{} bytes do not hold a {} array of shape {:?}",
+                data.len(),
+                dtype,
+                shape
+            )));
+        }
+        // mlx reads the elements in place, copy them if they are not aligned
+        let mut aligned = Vec::new();
+        let data = if (data.as_ptr() as usize).is_multiple_of(dtype.size_of()) {
+            data
+        } else {
+            aligned.resize(data.len().div_ceil(8), 0u64);
+            let bytes = unsafe { slice::from_raw_parts_mut(aligned.as_mut_ptr() as *mut u8, data.len()) };
+            bytes.copy_from_slice(data);
+            bytes
+        };
+        let handle = unsafe {
+            mlx_array_from_data(
+                data.as_ptr() as *const ::std::os::raw::c_void,
+                shape.as_ptr() as *const ::std::os::raw::c_int,
+                shape.len() as ::std::os::raw::c_int,
+                dtype.as_raw(),
+            )
+        };
+        Ok(Self::from_raw(handle))
+    }
+
     // pub fn rand<T: MlxType>(shapes: &[i32]) -> Self {
     //     let handle = unsafe { mlx_array };
     //     Self::from_raw(handle)
     // }
 
-    pub fn arange<T: MlxType>(range: Range<f64>, step: f64, stream: MLXStream) -> Self {
+    pub fn arange<T: MlxType>(range: Range<f64>, step: f64) -> Self {
+        Self::arange_on::<T>(range, step, &get_default_stream())
+    }
+
+    pub fn arange_on<T: MlxType>(range: Range<f64>, step: f64, stream: &MLXStream) -> Self {
         let handle = unsafe {
             mlx_arange(
                 range.start,
@@ -37,14 +78,22 @@ impl MLXArray {
         Self::from_raw(handle)
     }
 
-    pub fn cat<T: Into<VectorMLXArray>>(array: T, axis: i32,  stream: MLXStream) -> MLXArray {
+    pub fn cat<T: Into<VectorMLXArray>>(array: T, axis: i32) -> MLXArray {
+        Self::cat_on::<T>(array, axis, &get_default_stream())
+    }
+
+    pub fn cat_on<T: Into<VectorMLXArray>>(array: T, axis: i32, stream: &MLXStream) -> MLXArray {
         let handle = unsafe {
             mlx_concatenate(array.into().as_ptr(), axis, stream.as_ptr())
         };
         MLXArray::from_raw(handle)
     }
 
-    pub fn ones<T: MlxType>(shape: &[i32], stream: MLXStream) -> MLXArray {
+    pub fn ones<T: MlxType>(shape: &[i32]) -> MLXArray {
+        Self::ones_on::<T>(shape, &get_default_stream())
+    }
+
+    pub fn ones_on<T: MlxType>(shape: &[i32], stream: &MLXStream) -> MLXArray {
         let handle = unsafe {
             mlx_ones(
                 shape.as_ptr() as *const ::std::os::raw::c_int,
@@ -55,7 +104,11 @@ impl MLXArray {
         };
         MLXArray::from_raw(handle)
     }
-    pub fn zeros<T: MlxType>(shape: &[i32], stream: MLXStream) -> MLXArray {
+    pub fn zeros<T: MlxType>(shape: &[i32]) -> MLXArray {
+        Self::zeros_on::<T>(shape, &get_default_stream())
+    }
+
+    pub fn zeros_on<T: MlxType>(shape: &[i32], stream: &MLXStream) -> MLXArray {
         let handle = unsafe {
             mlx_zeros(
                 shape.as_ptr() as *const ::std::os::raw::c_int,
@@ -67,8 +120,11 @@ impl MLXArray {
         MLXArray::from_raw(handle)
     }
 
-    pub fn zeros_like(&self, stream: Option<MLXStream>) -> MLXArray {
-        let stream = stream.unwrap_or_else(|| get_default_stream());
+    pub fn zeros_like(&self) -> MLXArray {
+        self.zeros_like_on(&get_default_stream())
+    }
+
+    pub fn zeros_like_on(&self, stream: &MLXStream) -> MLXArray {
         let handle = unsafe {
             mlx_zeros_like(
                 self.as_ptr(),
@@ -78,38 +134,274 @@ impl MLXArray {
         MLXArray::from_raw(handle)
     }
 
-    pub fn full<T: Into<MLXArray>>(shape: &[i32], value: T, stream: MLXStream) -> MLXArray {
+    pub fn full<T: Into<MLXArray>>(shape: &[i32], value: T) -> MLXArray {
+        Self::full_on::<T>(shape, value, &get_default_stream())
+    }
+
+    pub fn full_on<T: Into<MLXArray>>(shape: &[i32], value: T, stream: &MLXStream) -> MLXArray {
         let array: MLXArray = value.into();
         let handle = unsafe {
             mlx_full(
                 shape.as_ptr() as *const ::std::os::raw::c_int,
                 shape.len(),
                 array.as_ptr(),
-                array.dtype(),
+                array.raw_dtype(),
                 stream.as_ptr(),
             )
         };
         MLXArray::from_raw(handle)
     }
+
+    pub fn ones_like(&self) -> MLXArray {
+        self.ones_like_on(&get_default_stream())
+    }
+
+    pub fn ones_like_on(&self, stream: &MLXStream) -> MLXArray {
+        let handle = unsafe { mlx_ones_like(self.as_ptr(), stream.as_ptr()) };
+        MLXArray::from_raw(handle)
+    }
+
+    /// An array with the shape and dtype of `self`, filled with `value`.
+    pub fn full_like<T: Into<MLXArray>>(&self, value: T) -> MLXArray {
+        self.full_like_on::<T>(value, &get_default_stream())
+    }
+
+    pub fn full_like_on<T: Into<MLXArray>>(&self, value: T, stream: &MLXStream) -> MLXArray {
+        let array: MLXArray = value.into();
+        let shape = self.shape();
+        let handle = unsafe {
+            mlx_full(
+                shape.as_ptr() as *const ::std::os::raw::c_int,
+                shape.len(),
+                array.as_ptr(),
+                self.raw_dtype(),
+                stream.as_ptr(),
+            )
+        };
+        MLXArray::from_raw(handle)
+    }
+
+    /// `num` evenly spaced values from `start` to `stop`, both included.
+    pub fn linspace<T: MlxType>(start: f64, stop: f64, num: i32) -> MLXArray {
+        Self::linspace_on::<T>(start, stop, num, &get_default_stream())
+    }
+
+    pub fn linspace_on<T: MlxType>(start: f64, stop: f64, num: i32, stream: &MLXStream) -> MLXArray {
+        let handle = unsafe { mlx_linspace(start, stop, num, T::mlx_array_dtype, stream.as_ptr()) };
+        MLXArray::from_raw(handle)
+    }
+
+    /// An `n x m` matrix (`n x n` if `m` is `None`) with ones on the `k`-th diagonal, `k > 0`
+    /// being above the main diagonal.
+    pub fn eye<T: MlxType>(n: i32, m: Option<i32>, k: i32) -> MLXArray {
+        Self::eye_on::<T>(n, m, k, &get_default_stream())
+    }
+
+    pub fn eye_on<T: MlxType>(n: i32, m: Option<i32>, k: i32, stream: &MLXStream) -> MLXArray {
+        let handle = unsafe { mlx_eye(n, m.unwrap_or(n), k, T::mlx_array_dtype, stream.as_ptr()) };
+        MLXArray::from_raw(handle)
+    }
+
+    /// The `n x n` identity matrix.
+    pub fn identity<T: MlxType>(n: i32) -> MLXArray {
+        Self::identity_on::<T>(n, &get_default_stream())
+    }
+
+    pub fn identity_on<T: MlxType>(n: i32, stream: &MLXStream) -> MLXArray {
+        let handle = unsafe { mlx_identity(n, T::mlx_array_dtype, stream.as_ptr()) };
+        MLXArray::from_raw(handle)
+    }
+
+    /// An `n x m` matrix with ones at and below the `k`-th diagonal and zeros elsewhere.
+    pub fn tri<T: MlxType>(n: i32, m: Option<i32>, k: i32) -> MLXArray {
+        Self::tri_on::<T>(n, m, k, &get_default_stream())
+    }
+
+    pub fn tri_on<T: MlxType>(n: i32, m: Option<i32>, k: i32, stream: &MLXStream) -> MLXArray {
+        let handle = unsafe { mlx_tri(n, m.unwrap_or(n), k, T::mlx_array_dtype, stream.as_ptr()) };
+        MLXArray::from_raw(handle)
+    }
+
+    /// Zeros the elements above the `k`-th diagonal of the last two axes.
+    pub fn tril(&self, k: i32) -> MLXArray {
+        self.try_tril(k).unwrap()
+    }
+
+    pub fn tril_on(&self, k: i32, stream: &MLXStream) -> MLXArray {
+        self.try_tril_on(k, stream).unwrap()
+    }
+
+    pub fn try_tril(&self, k: i32) -> Result<MLXArray, MLXError> {
+        self.try_tril_on(k, &get_default_stream())
+    }
+
+    pub fn try_tril_on(&self, k: i32, stream: &MLXStream) -> Result<MLXArray, MLXError> {
+        let handle = guard(|| unsafe { mlx_tril(self.as_ptr(), k, stream.as_ptr()) })?;
+        Ok(MLXArray::from_raw(handle))
+    }
+
+    /// Zeros the elements below the `k`-th diagonal of the last two axes.
+    pub fn triu(&self, k: i32) -> MLXArray {
+        self.try_triu(k).unwrap()
+    }
+
+    pub fn triu_on(&self, k: i32, stream: &MLXStream) -> MLXArray {
+        self.try_triu_on(k, stream).unwrap()
+    }
+
+    pub fn try_triu(&self, k: i32) -> Result<MLXArray, MLXError> {
+        self.try_triu_on(k, &get_default_stream())
+    }
+
+    pub fn try_triu_on(&self, k: i32, stream: &MLXStream) -> Result<MLXArray, MLXError> {
+        let handle = guard(|| unsafe { mlx_triu(self.as_ptr(), k, stream.as_ptr()) })?;
+        Ok(MLXArray::from_raw(handle))
+    }
+
+    /// Coordinate grids from 1-D arrays, with `"xy"` (cartesian) or `"ij"` (matrix) indexing.
+    /// With `sparse` the grids are not broadcast to the full shape.
+    pub fn meshgrid(arrays: &[MLXArray], sparse: bool, indexing: &str) -> Vec<MLXArray> {
+        Self::try_meshgrid(arrays, sparse, indexing).unwrap()
+    }
+
+    pub fn meshgrid_on(arrays: &[MLXArray], sparse: bool, indexing: &str, stream: &MLXStream) -> Vec<MLXArray> {
+        Self::try_meshgrid_on(arrays, sparse, indexing, stream).unwrap()
+    }
+
+    pub fn try_meshgrid(arrays: &[MLXArray], sparse: bool, indexing: &str) -> Result<Vec<MLXArray>, MLXError> {
+        Self::try_meshgrid_on(arrays, sparse, indexing, &get_default_stream())
+    }
+
+    pub fn try_meshgrid_on(arrays: &[MLXArray], sparse: bool, indexing: &str, stream: &MLXStream) -> Result<Vec<MLXArray>, MLXError> {
+        if indexing != "xy" && indexing != "ij" {
+            return Err(MLXError::InvalidArgument(format!(
+                "meshgrid indexing must be \"xy\" or \"ij\", got {:?}",
+                indexing
+            )));
+        }
+        let arrays = VectorMLXArray::from(arrays);
+        let indexing = CString::new(indexing).unwrap();
+        let handle = guard(|| unsafe {
+            mlx_meshgrid(arrays.as_ptr(), sparse, indexing.as_ptr(), stream.as_ptr())
+        })?;
+        Ok(VectorMLXArray::from_raw(handle).to_vec())
+    }
+
+    /// Builds an array from (possibly nested) `Vec`s or arrays, the shape is inferred from the
+    /// nesting. Rust `f64` values are stored as `f32`.
+    pub fn from_vec<D: ArrayData>(data: Vec<D>) -> MLXArray {
+        Self::try_from_vec(data).unwrap()
+    }
+
+    pub fn try_from_vec<D: ArrayData>(data: Vec<D>) -> Result<MLXArray, MLXError> {
+        let shape = data.shape()?;
+        let mut flat = Vec::new();
+        data.flatten_into(&mut flat);
+        Ok(MLXArray::array(&flat, &shape))
+    }
 }
 
-impl From<f32> for MLXArray {
-    fn from(value: f32) -> Self {
-        let handle = unsafe { mlx_array_from_float(value) };
-        Self::from_raw(handle)
+/// Nested Rust data that can be turned into an array, see [`MLXArray::from_vec`].
+pub trait ArrayData {
+    type Elem: MlxType + Copy;
+
+    /// The shape of the data, an error if nested sequences have different lengths.
+    fn shape(&self) -> Result<Vec<i32>, MLXError>;
+
+    fn flatten_into(&self, out: &mut Vec<Self::Elem>);
+}
+
+impl<T: MlxType + Copy> ArrayData for T {
+    type Elem = T;
+
+    fn shape(&self) -> Result<Vec<i32>, MLXError> {
+        Ok(vec![])
+    }
+
+    fn flatten_into(&self, out: &mut Vec<T>) {
+        out.push(*self);
     }
 }
 
-impl From<i8> for MLXArray {
-    fn from(value: i8) -> Self {
-        let handle = unsafe { mlx_array_from_int(value as i32) };
-        Self::from_raw(handle)
+impl ArrayData for f64 {
+    type Elem = f32;
+
+    fn shape(&self) -> Result<Vec<i32>, MLXError> {
+        Ok(vec![])
+    }
+
+    fn flatten_into(&self, out: &mut Vec<f32>) {
+        out.push(*self as f32);
+    }
+}
+
+fn nested_shape<D: ArrayData>(items: &[D]) -> Result<Vec<i32>, MLXError> {
+    let inner = match items.first() {
+        Some(first) => first.shape()?,
+        None => vec![],
+    };
+    for item in items.iter().skip(1) {
+        let shape = item.shape()?;
+        if shape != inner {
+            return Err(MLXError::Shape(format!(
+                "ragged nested data, found shapes {:?} and {:?}",
+                inner, shape
+            )));
+        }
+    }
+    let mut shape = vec![items.len() as i32];
+    shape.extend(inner);
+    Ok(shape)
+}
+
+impl<D: ArrayData> ArrayData for Vec<D> {
+    type Elem = D::Elem;
+
+    fn shape(&self) -> Result<Vec<i32>, MLXError> {
+        nested_shape(self)
+    }
+
+    fn flatten_into(&self, out: &mut Vec<Self::Elem>) {
+        self.iter().for_each(|item| item.flatten_into(out));
+    }
+}
+
+impl<D: ArrayData, const N: usize> ArrayData for [D; N] {
+    type Elem = D::Elem;
+
+    fn shape(&self) -> Result<Vec<i32>, MLXError> {
+        nested_shape(self)
+    }
+
+    fn flatten_into(&self, out: &mut Vec<Self::Elem>) {
+        self.iter().for_each(|item| item.flatten_into(out));
     }
 }
 
-impl From<i16> for MLXArray {
-    fn from(value: i16) -> Self {
-        let handle = unsafe { mlx_array_from_int(value as i32) };
+/// Collects scalars into a 1-D array, or rows of nested data into an array with one more axis.
+impl<D: ArrayData> FromIterator<D> for MLXArray {
+    fn from_iter<I: IntoIterator<Item = D>>(iter: I) -> Self {
+        MLXArray::from_vec(iter.into_iter().collect())
+    }
+}
+
+/// Creates an array from nested brackets, the shape is inferred.
+///
+/// ```
+/// use mlx_rust::array;
+/// let a = array![[1., 2.], [3., 4.]];
+/// assert_eq!(&[2, 2], a.shape());
+/// ```
+#[macro_export]
+macro_rules! array {
+    ($($x:expr),* $(,)?) => {
+        $crate::MLXArray::from_vec(vec![$($x),*])
+    };
+}
+
+impl From<f32> for MLXArray {
+    fn from(value: f32) -> Self {
+        let handle = unsafe { mlx_array_from_float(value) };
         Self::from_raw(handle)
     }
 }
@@ -128,15 +420,111 @@ impl From<bool> for MLXArray {
     }
 }
 
+/// Narrowed to `float32` like `f64` elements in [`MLXArray::from_vec`], there is no `float64`
+/// dtype: the extra precision is lost and values beyond the `f32` range become infinite.
+impl From<f64> for MLXArray {
+    fn from(value: f64) -> Self {
+        let handle = unsafe { mlx_array_from_float(value as f32) };
+        Self::from_raw(handle)
+    }
+}
+
+macro_rules! impl_from_scalar {
+    ($($type:ty),*) => {
+        $(
+            impl From<$type> for MLXArray {
+                fn from(value: $type) -> Self {
+                    MLXArray::array(&[value], &[])
+                }
+            }
+        )*
+    };
+}
+
+impl_from_scalar!(u8, u16, u32, u64, i8, i16, i64, f16, bf16, Complex32);
+
 #[cfg(test)]
 mod tests {
-    use half::f16;
+    use half::{bf16, f16};
+    use num_complex::Complex32;
 
-    use crate::{array::MLXArray, stream::get_default_stream};
+    use crate::{array::MLXArray, r#type::Dtype};
+
+    #[test]
+    fn test_from_scalar() {
+        assert_eq!(Dtype::U8, MLXArray::from(3u8).dtype());
+        assert_eq!(Dtype::I8, MLXArray::from(-3i8).dtype());
+        assert_eq!(Dtype::U64, MLXArray::from(u64::MAX).dtype());
+        assert_eq!(i64::MIN, MLXArray::from(i64::MIN).to_scalar::<i64>().unwrap());
+        assert_eq!(f16::from_f32(1.5), MLXArray::from(f16::from_f32(1.5)).to_scalar::<f16>().unwrap());
+        assert_eq!(Dtype::BF16, MLXArray::from(bf16::ONE).dtype());
+        assert_eq!(Dtype::F32, MLXArray::from(0.25f64).dtype());
+        assert_eq!(Complex32::new(1.0, 2.0), MLXArray::from(Complex32::new(1.0, 2.0)).to_scalar::<Complex32>().unwrap());
+        assert_eq!(0, MLXArray::from(0u16).ndim());
+    }
 
     #[test]
     fn test_array_arange() {
-        let r = MLXArray::arange::<f16>(0.0f64..1.0f64, 0.1f64, get_default_stream());
+        let r = MLXArray::arange::<f16>(0.0f64..1.0f64, 0.1f64);
         println!("{}", r)
     }
+
+    #[test]
+    fn test_linspace() {
+        let r = MLXArray::linspace::<f32>(0.0, 1.0, 5);
+        assert_eq!(&[0.0, 0.25, 0.5, 0.75, 1.0], r.to_slice::<f32>().unwrap());
+    }
+
+    #[test]
+    fn test_eye_and_tri() {
+        let e = MLXArray::eye::<i32>(2, Some(3), 1);
+        assert_eq!(&[0, 1, 0, 0, 0, 1], e.to_slice::<i32>().unwrap());
+        let i = MLXArray::identity::<f32>(2);
+        assert_eq!(&[1.0, 0.0, 0.0, 1.0], i.to_slice::<f32>().unwrap());
+        let t = MLXArray::tri::<i32>(2, None, 0);
+        assert_eq!(&[1, 0, 1, 1], t.to_slice::<i32>().unwrap());
+    }
+
+    #[test]
+    fn test_tril_triu() {
+        let x = array![[1, 2, 3], [4, 5, 6], [7, 8, 9]];
+        assert_eq!(&[1, 0, 0, 4, 5, 0, 7, 8, 9], x.tril(0).to_slice::<i32>().unwrap());
+        assert_eq!(&[0, 2, 3, 0, 0, 6, 0, 0, 0], x.triu(1).to_slice::<i32>().unwrap());
+    }
+
+    #[test]
+    fn test_meshgrid() {
+        let x = MLXArray::array(&[1i32, 2, 3], &[3]);
+        let y = MLXArray::array(&[4i32, 5], &[2]);
+        let grids = MLXArray::meshgrid(&[x.clone(), y.clone()], false, "xy");
+        assert_eq!(&[2, 3], grids[0].shape());
+        assert_eq!(&[1, 2, 3, 1, 2, 3], grids[0].to_slice::<i32>().unwrap());
+        let grids = MLXArray::meshgrid(&[x.clone(), y.clone()], false, "ij");
+        assert_eq!(&[3, 2], grids[1].shape());
+        assert!(MLXArray::try_meshgrid(&[x, y], false, "yx").is_err());
+    }
+
+    #[test]
+    fn test_like() {
+        let x = MLXArray::array(&[1i32, 2], &[2]);
+        assert_eq!(&[1, 1], x.ones_like().to_slice::<i32>().unwrap());
+        let f = x.full_like(7);
+        assert_eq!(&[7, 7], f.to_slice::<i32>().unwrap());
+    }
+
+    #[test]
+    fn test_from_nested_data() {
+        let a = array![[1., 2.], [3., 4.]];
+        assert_eq!(&[2, 2], a.shape());
+        assert_eq!(&[1.0, 2.0, 3.0, 4.0], a.to_slice::<f32>().unwrap());
+
+        let b = MLXArray::from_vec(vec![vec![1u8, 2, 3]]);
+        assert_eq!(&[1, 3], b.shape());
+
+        let c: MLXArray = (0..4).map(|i| [i, i * 2]).collect();
+        assert_eq!(&[4, 2], c.shape());
+        assert_eq!(&[0, 0, 1, 2, 2, 4, 3, 6], c.to_slice::<i32>().unwrap());
+
+        assert!(MLXArray::try_from_vec(vec![vec![1, 2], vec![3]]).is_err());
+    }
 }
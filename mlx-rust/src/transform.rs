@@ -2,10 +2,12 @@ use std::marker::PhantomData;
 
 use mlx_sys::{
     mlx_closure_value_and_grad, mlx_closure_value_and_grad_, mlx_closure_value_and_grad_apply,
-    mlx_free, mlx_jvp, mlx_value_and_grad, mlx_vector_vector_array_get, mlx_vjp,
+    mlx_free, mlx_jvp, mlx_value_and_grad, mlx_vector_vector_array, mlx_vector_vector_array_get,
+    mlx_vjp,
 };
 
 use crate::closure::{MLXClosure, MLXFunc};
+use crate::error::{guard, MLXError};
 use crate::object::MLXObject;
 use crate::{MLXArray, VectorMLXArray};
 
@@ -15,25 +17,43 @@ use crate::{MLXArray, VectorMLXArray};
 /// function `f` evaluated at `primals`.
 /// return (out, gradient)
 pub fn jvp<IN, OUT>(f: impl MLXFunc<IN, OUT>, primals: IN, tangents: IN) -> (OUT, VectorMLXArray)
+where
+    IN: for<'a> From<&'a VectorMLXArray> + Into<VectorMLXArray>,
+    OUT: for<'b> From<&'b VectorMLXArray> + Into<VectorMLXArray>,
+{
+    try_jvp(f, primals, tangents).unwrap()
+}
+
+pub fn try_jvp<IN, OUT>(f: impl MLXFunc<IN, OUT>, primals: IN, tangents: IN) -> Result<(OUT, VectorMLXArray), MLXError>
 where
     IN: for<'a> From<&'a VectorMLXArray> + Into<VectorMLXArray>,
     OUT: for<'b> From<&'b VectorMLXArray> + Into<VectorMLXArray>,
 {
     let closure = MLXClosure::new(f);
-    unsafe {
-        let vector_pair = mlx_jvp(
+    let primals = primals.into();
+    let tangents = tangents.into();
+    let vector_pair = guard(|| unsafe {
+        mlx_jvp(
             closure.as_ptr(),
-            primals.into().as_ptr(),
-            tangents.into().as_ptr(),
-        );
-        let out = mlx_vector_vector_array_get(vector_pair, 0);
-        let gradient = mlx_vector_vector_array_get(vector_pair, 1);
-        mlx_free(vector_pair as *mut ::std::os::raw::c_void);
-        (
-            (&VectorMLXArray::from_raw(out)).into(),
-            VectorMLXArray::from_raw(gradient),
+            primals.as_ptr(),
+            tangents.as_ptr(),
         )
-    }
+    })?;
+    Ok(unsafe { split_vector_pair(vector_pair) })
+}
+
+/// Takes ownership of an `(out, gradient)` vector pair returned by mlx-c.
+unsafe fn split_vector_pair<OUT>(vector_pair: mlx_vector_vector_array) -> (OUT, VectorMLXArray)
+where
+    OUT: for<'b> From<&'b VectorMLXArray>,
+{
+    let out = mlx_vector_vector_array_get(vector_pair, 0);
+    let gradient = mlx_vector_vector_array_get(vector_pair, 1);
+    mlx_free(vector_pair as *mut ::std::os::raw::c_void);
+    (
+        (&VectorMLXArray::from_raw(out)).into(),
+        VectorMLXArray::from_raw(gradient),
+    )
 }
 
 /// Compute the vector-Jacobian product.
@@ -46,25 +66,33 @@ pub fn vjp<IN, OUT>(
     primals: IN,
     cotangents: MLXArray,
 ) -> (OUT, VectorMLXArray)
+where
+    IN: for<'a> From<&'a VectorMLXArray> + Into<VectorMLXArray>,
+    OUT: for<'b> From<&'b VectorMLXArray> + Into<VectorMLXArray>,
+{
+    try_vjp(f, primals, cotangents).unwrap()
+}
+
+pub fn try_vjp<IN, OUT>(
+    f: impl MLXFunc<IN, OUT>,
+    primals: IN,
+    cotangents: MLXArray,
+) -> Result<(OUT, VectorMLXArray), MLXError>
 where
     IN: for<'a> From<&'a VectorMLXArray> + Into<VectorMLXArray>,
     OUT: for<'b> From<&'b VectorMLXArray> + Into<VectorMLXArray>,
 {
     let closure = MLXClosure::new(f);
-    unsafe {
-        let vector_pair = mlx_vjp(
+    let primals = primals.into();
+    let cotangents = VectorMLXArray::from_array(cotangents);
+    let vector_pair = guard(|| unsafe {
+        mlx_vjp(
             closure.as_ptr(),
-            primals.into().as_ptr(),
-            VectorMLXArray::from_array(cotangents).as_ptr(),
-        );
-        let out = mlx_vector_vector_array_get(vector_pair, 0);
-        let gradient = mlx_vector_vector_array_get(vector_pair, 1);
-        mlx_free(vector_pair as *mut ::std::os::raw::c_void);
-        (
-            (&VectorMLXArray::from_raw(out)).into(),
-            VectorMLXArray::from_raw(gradient),
+            primals.as_ptr(),
+            cotangents.as_ptr(),
         )
-    }
+    })?;
+    Ok(unsafe { split_vector_pair(vector_pair) })
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -89,30 +117,37 @@ where
     Self: 'static,
 {
     pub fn apply(&self, input: IN) -> (OUT, VectorMLXArray) {
+        self.try_apply(input).unwrap()
+    }
+
+    pub fn try_apply(&self, input: IN) -> Result<(OUT, VectorMLXArray), MLXError> {
         let i: VectorMLXArray = input.into();
-        unsafe {
-            let vector_pair = mlx_closure_value_and_grad_apply(self.inner.as_ptr(), i.as_ptr());
-            let out = mlx_vector_vector_array_get(vector_pair, 0);
-            let gradient = mlx_vector_vector_array_get(vector_pair, 1);
-            mlx_free(vector_pair as *mut ::std::os::raw::c_void);
-            (
-                (&VectorMLXArray::from_raw(out)).into(),
-                VectorMLXArray::from_raw(gradient),
-            )
-        }
+        let vector_pair = guard(|| unsafe {
+            mlx_closure_value_and_grad_apply(self.inner.as_ptr(), i.as_ptr())
+        })?;
+        Ok(unsafe { split_vector_pair(vector_pair) })
     }
 }
 
 ///Note require f return scalar MlxArray or shape ()
 pub fn value_and_grad<IN, OUT, F>(f: F, argnums: &[i32]) -> ValueAndGrad<IN, OUT>
+where
+    F: MLXFunc<IN, OUT>,
+    IN: for<'a> From<&'a VectorMLXArray> + Into<VectorMLXArray>,
+    OUT: for<'b> From<&'b VectorMLXArray> + Into<VectorMLXArray>,
+{
+    try_value_and_grad(f, argnums).unwrap()
+}
+
+pub fn try_value_and_grad<IN, OUT, F>(f: F, argnums: &[i32]) -> Result<ValueAndGrad<IN, OUT>, MLXError>
 where
     F: MLXFunc<IN, OUT>,
     IN: for<'a> From<&'a VectorMLXArray> + Into<VectorMLXArray>,
     OUT: for<'b> From<&'b VectorMLXArray> + Into<VectorMLXArray>,
 {
     let closure = MLXClosure::new(f);
-    let handle = unsafe { mlx_value_and_grad(closure.as_ptr(), argnums.as_ptr(), argnums.len()) };
-    ValueAndGrad::from_raw(handle)
+    let handle = guard(|| unsafe { mlx_value_and_grad(closure.as_ptr(), argnums.as_ptr(), argnums.len()) })?;
+    Ok(ValueAndGrad::from_raw(handle))
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -130,8 +165,12 @@ where
     OUT: for<'b> From<&'b VectorMLXArray> + Into<VectorMLXArray> + 'static,
 {
     pub fn apply(&self, input: IN) -> VectorMLXArray {
-        let (_, gradient) = self.0.apply(input);
-        gradient
+        self.try_apply(input).unwrap()
+    }
+
+    pub fn try_apply(&self, input: IN) -> Result<VectorMLXArray, MLXError> {
+        let (_, gradient) = self.0.try_apply(input)?;
+        Ok(gradient)
     }
 }
 
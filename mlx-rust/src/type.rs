@@ -1,18 +1,245 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
 use half::{bf16, f16};
+use num_complex::Complex32;
 use mlx_sys::{
-    mlx_array, mlx_array_data_bool, mlx_array_data_float16, mlx_array_data_float32,
+    mlx_array, mlx_array_data_bool, mlx_array_data_complex64, mlx_array_data_float16, mlx_array_data_float32,
     mlx_array_data_int16, mlx_array_data_int32, mlx_array_data_int64, mlx_array_data_int8,
     mlx_array_data_uint16, mlx_array_data_uint32, mlx_array_data_uint64, mlx_array_data_uint8,
     mlx_array_dtype_, mlx_array_dtype__MLX_BFLOAT16, mlx_array_dtype__MLX_BOOL,
-    mlx_array_dtype__MLX_FLOAT16, mlx_array_dtype__MLX_FLOAT32, mlx_array_dtype__MLX_INT16,
+    mlx_array_dtype__MLX_COMPLEX64, mlx_array_dtype__MLX_FLOAT16, mlx_array_dtype__MLX_FLOAT32, mlx_array_dtype__MLX_INT16,
     mlx_array_dtype__MLX_INT32, mlx_array_dtype__MLX_INT64, mlx_array_dtype__MLX_INT8,
     mlx_array_dtype__MLX_UINT16, mlx_array_dtype__MLX_UINT32, mlx_array_dtype__MLX_UINT64,
-    mlx_array_dtype__MLX_UINT8, mlx_array_item_bfloat16, mlx_array_item_bool,
+    mlx_array_dtype__MLX_UINT8, mlx_array_item_bfloat16, mlx_array_item_bool, mlx_array_item_complex64,
     mlx_array_item_float16, mlx_array_item_float32, mlx_array_item_int16, mlx_array_item_int32,
     mlx_array_item_int64, mlx_array_item_int8, mlx_array_item_uint16, mlx_array_item_uint32,
     mlx_array_item_uint64, mlx_array_item_uint8,
 };
 
+use crate::error::MLXError;
+
+/// The element type of an array, known at runtime.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Dtype {
+    Bool,
+    U8,
+    U16,
+    U32,
+    U64,
+    I8,
+    I16,
+    I32,
+    I64,
+    F16,
+    BF16,
+    F32,
+    Complex64,
+}
+
+impl Dtype {
+    pub const ALL: [Dtype; 13] = [
+        Dtype::Bool,
+        Dtype::U8,
+        Dtype::U16,
+        Dtype::U32,
+        Dtype::U64,
+        Dtype::I8,
+        Dtype::I16,
+        Dtype::I32,
+        Dtype::I64,
+        Dtype::F16,
+        Dtype::BF16,
+        Dtype::F32,
+        Dtype::Complex64,
+    ];
+
+    /// The dtype of a compile-time `MlxType`.
+    pub fn of<T: MlxType>() -> Dtype {
+        Dtype::try_from(T::mlx_array_dtype).unwrap()
+    }
+
+    /// Size of one element in bytes.
+    pub fn size_of(&self) -> usize {
+        match self {
+            Dtype::Bool | Dtype::U8 | Dtype::I8 => 1,
+            Dtype::U16 | Dtype::I16 | Dtype::F16 | Dtype::BF16 => 2,
+            Dtype::U32 | Dtype::I32 | Dtype::F32 => 4,
+            Dtype::U64 | Dtype::I64 | Dtype::Complex64 => 8,
+        }
+    }
+
+    pub fn is_floating(&self) -> bool {
+        matches!(self, Dtype::F16 | Dtype::BF16 | Dtype::F32)
+    }
+
+    pub fn is_complex(&self) -> bool {
+        matches!(self, Dtype::Complex64)
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.is_unsigned() || self.is_signed_integer()
+    }
+
+    pub fn is_unsigned(&self) -> bool {
+        matches!(self, Dtype::U8 | Dtype::U16 | Dtype::U32 | Dtype::U64)
+    }
+
+    pub fn is_signed_integer(&self) -> bool {
+        matches!(self, Dtype::I8 | Dtype::I16 | Dtype::I32 | Dtype::I64)
+    }
+
+    /// The smallest and largest value of an integer dtype, `None` for the others.
+    pub fn integer_bounds(&self) -> Option<(i128, i128)> {
+        match self {
+            Dtype::U8 => Some((0, u8::MAX as i128)),
+            Dtype::U16 => Some((0, u16::MAX as i128)),
+            Dtype::U32 => Some((0, u32::MAX as i128)),
+            Dtype::U64 => Some((0, u64::MAX as i128)),
+            Dtype::I8 => Some((i8::MIN as i128, i8::MAX as i128)),
+            Dtype::I16 => Some((i16::MIN as i128, i16::MAX as i128)),
+            Dtype::I32 => Some((i32::MIN as i128, i32::MAX as i128)),
+            Dtype::I64 => Some((i64::MIN as i128, i64::MAX as i128)),
+            _ => None,
+        }
+    }
+
+    /// The name used by MLX and numpy, e.g. `bfloat16`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Dtype::Bool => "bool",
+            Dtype::U8 => "uint8",
+            Dtype::U16 => "uint16",
+            Dtype::U32 => "uint32",
+            Dtype::U64 => "uint64",
+            Dtype::I8 => "int8",
+            Dtype::I16 => "int16",
+            Dtype::I32 => "int32",
+            Dtype::I64 => "int64",
+            Dtype::F16 => "float16",
+            Dtype::BF16 => "bfloat16",
+            Dtype::F32 => "float32",
+            Dtype::Complex64 => "complex64",
+        }
+    }
+
+    pub(crate) fn as_raw(&self) -> mlx_array_dtype_ {
+        match self {
+            Dtype::Bool => mlx_array_dtype__MLX_BOOL,
+            Dtype::U8 => mlx_array_dtype__MLX_UINT8,
+            Dtype::U16 => mlx_array_dtype__MLX_UINT16,
+            Dtype::U32 => mlx_array_dtype__MLX_UINT32,
+            Dtype::U64 => mlx_array_dtype__MLX_UINT64,
+            Dtype::I8 => mlx_array_dtype__MLX_INT8,
+            Dtype::I16 => mlx_array_dtype__MLX_INT16,
+            Dtype::I32 => mlx_array_dtype__MLX_INT32,
+            Dtype::I64 => mlx_array_dtype__MLX_INT64,
+            Dtype::F16 => mlx_array_dtype__MLX_FLOAT16,
+            Dtype::BF16 => mlx_array_dtype__MLX_BFLOAT16,
+            Dtype::F32 => mlx_array_dtype__MLX_FLOAT32,
+            Dtype::Complex64 => mlx_array_dtype__MLX_COMPLEX64,
+        }
+    }
+
+    fn index(&self) -> usize {
+        Dtype::ALL.iter().position(|d| d == self).unwrap()
+    }
+}
+
+impl TryFrom<mlx_array_dtype_> for Dtype {
+    type Error = MLXError;
+
+    fn try_from(value: mlx_array_dtype_) -> Result<Self, Self::Error> {
+        Dtype::ALL
+            .into_iter()
+            .find(|d| d.as_raw() == value)
+            .ok_or_else(|| MLXError::InvalidArgument(format!("unknown mlx dtype {}", value)))
+    }
+}
+
+impl Display for Dtype {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Dtype {
+    type Err = MLXError;
+
+    /// Parses MLX / numpy / torch style names such as `"bfloat16"`, `"float32"` or `"f16"`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.strip_prefix("torch.").unwrap_or(s);
+        let dtype = match s {
+            "bool" | "bool_" => Dtype::Bool,
+            "uint8" | "u8" => Dtype::U8,
+            "uint16" | "u16" => Dtype::U16,
+            "uint32" | "u32" => Dtype::U32,
+            "uint64" | "u64" => Dtype::U64,
+            "int8" | "i8" => Dtype::I8,
+            "int16" | "i16" => Dtype::I16,
+            "int32" | "i32" | "int" => Dtype::I32,
+            "int64" | "i64" | "long" => Dtype::I64,
+            "float16" | "f16" | "half" => Dtype::F16,
+            "bfloat16" | "bf16" => Dtype::BF16,
+            "float32" | "f32" | "float" => Dtype::F32,
+            "complex64" | "c64" => Dtype::Complex64,
+            _ => return Err(MLXError::InvalidArgument(format!("unknown dtype {}", s))),
+        };
+        Ok(dtype)
+    }
+}
+
+/// The dtype of the result of a binary op on arrays of type `a` and `b`.
+///
+/// Follows the MLX promotion rules: unsigned and signed integers of the same width
+/// promote to the next wider signed type, `uint64` with a signed type goes to `float32`,
+/// floats win over integers and `float16` with `bfloat16` goes to `float32`.
+pub fn promote_types(a: Dtype, b: Dtype) -> Dtype {
+    use Dtype::*;
+    // rows and columns are in the order of `Dtype::ALL`
+    const RULES: [[Dtype; 13]; 13] = [
+        // Bool      U8         U16        U32        U64        I8         I16        I32        I64        F16        BF16       F32        Complex64
+        [Bool,      U8,        U16,       U32,       U64,       I8,        I16,       I32,       I64,       F16,       BF16,      F32,       Complex64], // Bool
+        [U8,        U8,        U16,       U32,       U64,       I16,       I16,       I32,       I64,       F16,       BF16,      F32,       Complex64], // U8
+        [U16,       U16,       U16,       U32,       U64,       I32,       I32,       I32,       I64,       F16,       BF16,      F32,       Complex64], // U16
+        [U32,       U32,       U32,       U32,       U64,       I64,       I64,       I64,       I64,       F16,       BF16,      F32,       Complex64], // U32
+        [U64,       U64,       U64,       U64,       U64,       F32,       F32,       F32,       F32,       F16,       BF16,      F32,       Complex64], // U64
+        [I8,        I16,       I32,       I64,       F32,       I8,        I16,       I32,       I64,       F16,       BF16,      F32,       Complex64], // I8
+        [I16,       I16,       I32,       I64,       F32,       I16,       I16,       I32,       I64,       F16,       BF16,      F32,       Complex64], // I16
+        [I32,       I32,       I32,       I64,       F32,       I32,       I32,       I32,       I64,       F16,       BF16,      F32,       Complex64], // I32
+        [I64,       I64,       I64,       I64,       F32,       I64,       I64,       I64,       I64,       F16,       BF16,      F32,       Complex64], // I64
+        [F16,       F16,       F16,       F16,       F16,       F16,       F16,       F16,       F16,       F16,       F32,       F32,       Complex64], // F16
+        [BF16,      BF16,      BF16,      BF16,      BF16,      BF16,      BF16,      BF16,      BF16,      F32,       BF16,      F32,       Complex64], // BF16
+        [F32,       F32,       F32,       F32,       F32,       F32,       F32,       F32,       F32,       F32,       F32,       F32,       Complex64], // F32
+        [Complex64, Complex64, Complex64, Complex64, Complex64, Complex64, Complex64, Complex64, Complex64, Complex64, Complex64, Complex64, Complex64], // Complex64
+    ];
+    RULES[a.index()][b.index()]
+}
+
+/// The dtype a weakly typed scalar of dtype `scalar` takes when combined with an array of dtype
+/// `array`.
+///
+/// The scalar adopts the array's dtype as long as it keeps its kind: bools always do, integers do
+/// unless the array is bool, floats only next to floating or complex arrays and complex scalars
+/// only next to complex arrays. Otherwise the scalar keeps its own dtype and the usual
+/// [`promote_types`] rules apply.
+pub fn weak_scalar_dtype(scalar: Dtype, array: Dtype) -> Dtype {
+    let adopts = if scalar == Dtype::Bool {
+        true
+    } else if scalar.is_integer() {
+        array != Dtype::Bool
+    } else if scalar.is_floating() {
+        array.is_floating() || array.is_complex()
+    } else {
+        array.is_complex()
+    };
+    if adopts {
+        array
+    } else {
+        scalar
+    }
+}
+
 pub trait MlxType {
     const mlx_array_dtype: mlx_array_dtype_;
 }
@@ -82,3 +309,73 @@ impl ScalarMlxType for bf16 {
         r as *const bf16
     }
 }
+
+/// `complex64` arrays hold pairs of `f32`, with the same layout as `num_complex::Complex32`.
+impl ScalarMlxType for Complex32 {
+    const mlx_array_dtype: mlx_array_dtype_ = mlx_array_dtype__MLX_COMPLEX64;
+
+    unsafe fn to_scalar(handle: mlx_array) -> Self {
+        let r = mlx_array_item_complex64(handle);
+        Complex32::new(r.re, r.im)
+    }
+
+    unsafe fn to_slice(handle: mlx_array) -> *const Self {
+        let r = mlx_array_data_complex64(handle);
+        r as *const Complex32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use half::bf16;
+    use num_complex::Complex32;
+
+    use crate::array::MLXArray;
+    use crate::r#type::{promote_types, weak_scalar_dtype, Dtype};
+
+    #[test]
+    fn test_weak_scalar_dtype() {
+        assert_eq!(Dtype::F16, weak_scalar_dtype(Dtype::F32, Dtype::F16));
+        assert_eq!(Dtype::U8, weak_scalar_dtype(Dtype::I32, Dtype::U8));
+        assert_eq!(Dtype::BF16, weak_scalar_dtype(Dtype::I64, Dtype::BF16));
+        assert_eq!(Dtype::F32, weak_scalar_dtype(Dtype::F32, Dtype::I32));
+        assert_eq!(Dtype::I32, weak_scalar_dtype(Dtype::I32, Dtype::Bool));
+        assert_eq!(Dtype::I8, weak_scalar_dtype(Dtype::Bool, Dtype::I8));
+        assert_eq!(Dtype::Complex64, weak_scalar_dtype(Dtype::Complex64, Dtype::F32));
+    }
+
+    #[test]
+    fn test_promote_types() {
+        assert_eq!(Dtype::I16, promote_types(Dtype::U8, Dtype::I8));
+        assert_eq!(Dtype::F32, promote_types(Dtype::U64, Dtype::I32));
+        assert_eq!(Dtype::F32, promote_types(Dtype::F16, Dtype::BF16));
+        assert_eq!(Dtype::F16, promote_types(Dtype::I64, Dtype::F16));
+        assert_eq!(Dtype::Complex64, promote_types(Dtype::Bool, Dtype::Complex64));
+        for a in Dtype::ALL {
+            for b in Dtype::ALL {
+                assert_eq!(promote_types(a, b), promote_types(b, a));
+            }
+        }
+    }
+
+    #[test]
+    fn test_complex64() {
+        let data = [Complex32::new(1.0, 2.0), Complex32::new(-3.0, 0.5)];
+        let array = MLXArray::array(&data, &[2]);
+        assert_eq!(Dtype::Complex64, array.dtype());
+        assert_eq!(&data, array.to_slice::<Complex32>().unwrap());
+        let doubled = &array + &array;
+        assert_eq!(Complex32::new(-6.0, 1.0), doubled.to_slice::<Complex32>().unwrap()[1]);
+    }
+
+    #[test]
+    fn test_parse_dtype() {
+        assert_eq!(Dtype::BF16, "bfloat16".parse::<Dtype>().unwrap());
+        assert_eq!(Dtype::F16, "torch.float16".parse::<Dtype>().unwrap());
+        assert!("float128".parse::<Dtype>().is_err());
+        assert_eq!(Dtype::BF16, Dtype::of::<bf16>());
+        assert_eq!(2, Dtype::BF16.size_of());
+        assert!(Dtype::BF16.is_floating());
+        assert!(!Dtype::I32.is_floating());
+    }
+}
@@ -97,6 +97,20 @@ impl VectorMLXArray {
     pub fn len(&self) -> usize {
         unsafe { mlx_vector_array_size(self.as_ptr()) }
     }
+
+    /// Collects the arrays into a `Vec`.
+    pub fn to_vec(&self) -> Vec<MLXArray> {
+        (0..self.len()).filter_map(|i| self.get(i)).collect()
+    }
+}
+
+impl From<&[MLXArray]> for VectorMLXArray {
+    fn from(arrs: &[MLXArray]) -> Self {
+        let ptr = arrs.as_ptr() as *mut mlx_array;
+        //safety: The reference count of the given arrays will be increased.
+        let handle = unsafe { mlx_vector_array_from_arrays(ptr, arrs.len()) };
+        VectorMLXArray::from_raw(handle)
+    }
 }
 
 // impl<T: Into<MLXArray>> From<(T, T)> for VectorMLXArray {
@@ -208,6 +222,15 @@ mod tests {
         assert_eq!(array.to_string(), vec.get(0).unwrap().to_string());
     }
 
+    #[test]
+    fn test_vector_array_from_slice() {
+        let arrays: Vec<MLXArray> = vec![12.0.into(), 13.0.into(), 14.0.into()];
+        let vec = VectorMLXArray::from(arrays.as_slice());
+        assert_eq!(vec.len(), 3);
+        let back = vec.to_vec();
+        assert_eq!(arrays[2].to_string(), back[2].to_string());
+    }
+
     #[test]
     fn test_vector_array_from_rust_array() {
         let array: MLXArray = 12.0.into();